@@ -0,0 +1,197 @@
+//! Compares rebuilding the combined process list from scratch each tick
+//! (`group_processes`, what `SystemMonitor` used to do) with updating a
+//! `ProcessIndex` in place, on a synthetic host with thousands of
+//! processes of which a few percent change per tick.
+//!
+//! Run with `cargo bench --bench process_index`.
+
+use rust_dashboard_lib::process_index::{group_processes, ProcessIndex, ProcessSample};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Counts allocations so the two approaches can be compared on more
+/// than wall time.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+const TICKS: usize = 200;
+const WARMUP_TICKS: usize = 20;
+
+/// Small deterministic PRNG so runs are comparable without a `rand`
+/// dependency.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+}
+
+#[derive(Clone)]
+struct Proc {
+    pid: u32,
+    name: String,
+    cpu: f32,
+    memory: u64,
+}
+
+/// Host state evolving tick by tick: some processes change usage, a few
+/// exit and are replaced by new PIDs.
+struct Host {
+    procs: Vec<Proc>,
+    names: Vec<String>,
+    next_pid: u32,
+    rng: Lcg,
+    change_percent: u64,
+    churn_percent: u64,
+}
+
+impl Host {
+    fn new(processes: usize, groups: usize, change_percent: u64, churn_percent: u64) -> Self {
+        let names: Vec<String> = (0..groups).map(|i| format!("process-{}", i)).collect();
+        let mut rng = Lcg(0x5eed);
+        let procs = (0..processes)
+            .map(|i| Proc {
+                pid: i as u32 + 100,
+                name: names[rng.next() as usize % groups].clone(),
+                cpu: (rng.next() % 1000) as f32 / 100.0,
+                memory: rng.next() % (512 << 20),
+            })
+            .collect();
+        Host {
+            procs,
+            names,
+            next_pid: processes as u32 + 100,
+            rng,
+            change_percent,
+            churn_percent,
+        }
+    }
+
+    fn tick(&mut self) {
+        for i in 0..self.procs.len() {
+            if self.rng.chance(self.churn_percent) {
+                let name = self.names[self.rng.next() as usize % self.names.len()].clone();
+                self.procs[i] = Proc {
+                    pid: self.next_pid,
+                    name,
+                    cpu: 0.0,
+                    memory: 4 << 20,
+                };
+                self.next_pid += 1;
+            } else if self.rng.chance(self.change_percent) {
+                let p = &mut self.procs[i];
+                p.cpu = (self.rng.next() % 1000) as f32 / 100.0;
+                p.memory = self.rng.next() % (512 << 20);
+            }
+        }
+    }
+
+    fn samples(&self) -> impl Iterator<Item = ProcessSample<'_>> {
+        self.procs.iter().map(|p| ProcessSample {
+            pid: p.pid,
+            name: p.name.as_str().into(),
+            cpu_usage: p.cpu,
+            memory: p.memory,
+            net_rx_rate: 0.0,
+            net_tx_rate: 0.0,
+            connections: 0,
+        })
+    }
+}
+
+struct Measurement {
+    per_tick: Duration,
+    allocations_per_tick: f64,
+}
+
+fn measure(host: &mut Host, mut step: impl FnMut(&Host)) -> Measurement {
+    for _ in 0..WARMUP_TICKS {
+        host.tick();
+        step(host);
+    }
+    let mut elapsed = Duration::ZERO;
+    let mut allocations = 0;
+    for _ in 0..TICKS {
+        host.tick();
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        step(host);
+        elapsed += start.elapsed();
+        allocations += ALLOCATIONS.load(Ordering::Relaxed) - before;
+    }
+    Measurement {
+        per_tick: elapsed / TICKS as u32,
+        allocations_per_tick: allocations as f64 / TICKS as f64,
+    }
+}
+
+fn compare(processes: usize, groups: usize, change_percent: u64, churn_percent: u64) {
+    let rebuild = measure(
+        &mut Host::new(processes, groups, change_percent, churn_percent),
+        |host| {
+            black_box(group_processes(host.samples()));
+        },
+    );
+    let mut index = ProcessIndex::new();
+    let incremental = measure(
+        &mut Host::new(processes, groups, change_percent, churn_percent),
+        |host| {
+            black_box(index.update(host.samples()));
+        },
+    );
+
+    println!(
+        "{:>6} processes, {:>4} groups, {:>2}% changing, {}% churn",
+        processes, groups, change_percent, churn_percent
+    );
+    for (label, m) in [("rebuild", &rebuild), ("index", &incremental)] {
+        println!(
+            "  {:<8} {:>10.1?}/tick {:>10.1} allocs/tick",
+            label, m.per_tick, m.allocations_per_tick
+        );
+    }
+    println!(
+        "  speedup  {:>9.1}x      {:>10.1}x fewer allocs",
+        rebuild.per_tick.as_secs_f64() / incremental.per_tick.as_secs_f64().max(1e-12),
+        rebuild.allocations_per_tick / incremental.allocations_per_tick.max(1.0)
+    );
+}
+
+fn main() {
+    compare(1_000, 200, 5, 1);
+    compare(5_000, 600, 5, 1);
+    compare(20_000, 1_500, 5, 1);
+    // Worst case for the index: everything changes every tick.
+    compare(5_000, 600, 100, 1);
+}
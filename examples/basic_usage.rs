@@ -51,8 +51,8 @@ fn main() {
         let total_gb = total as f64 / 1024.0 / 1024.0 / 1024.0;
         let percent_used = (used as f64 / total as f64) * 100.0;
         println!(
-            "  {} ({}) mounted at {}: {:.2}% used ({:.2} GiB / {:.2} GiB)",
-            name, fs, mount, percent_used, used_gb, total_gb
+            "  {} ({}) mounted at {}: {:.2}% used ({:.2} GiB / {:.2} GiB, {:.2} GiB free)",
+            name, fs, mount, percent_used, used_gb, total_gb, avail_gb
         );
     }
 
@@ -65,6 +65,28 @@ fn main() {
         println!("  {}: RX: {:.2} MB, TX: {:.2} MB", iface, rx_mb, tx_mb);
     }
 
+    // Get per-interface link details
+    println!("\nInterface Details:");
+    for iface in monitor.network_interfaces() {
+        println!(
+            "  {}: {:?}, MTU {}, addrs [{}], errors rx/tx {}/{}, dropped rx/tx {}/{}",
+            iface.interface,
+            iface.link_state,
+            iface.mtu,
+            iface
+                .ipv4
+                .iter()
+                .chain(iface.ipv6.iter())
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", "),
+            iface.rx_errors,
+            iface.tx_errors,
+            iface.rx_dropped,
+            iface.tx_dropped
+        );
+    }
+
     // Refresh and show updated CPU usage
     println!("\nRefreshing system data...");
     thread::sleep(Duration::from_millis(500));
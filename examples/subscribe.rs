@@ -0,0 +1,53 @@
+//! Subscription example for MonitorService
+//!
+//! This example lets MonitorService drive the refresh loop and prints
+//! each snapshot it publishes, instead of calling `refresh()` by hand.
+
+use rust_dashboard_lib::service::{MonitorService, ServiceEvent};
+use std::time::Duration;
+
+fn main() {
+    // Initialize logger
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let service = MonitorService::builder()
+        .interval(Duration::from_secs(1))
+        .build();
+    let events = service.subscribe();
+    service.start();
+
+    println!("MonitorService Example");
+    println!("======================");
+
+    // Print five snapshots, then stop
+    for event in events.iter() {
+        match event {
+            ServiceEvent::Snapshot(snapshot) => {
+                println!(
+                    "CPU {:>5.1}%  Memory {:.2} GiB  Processes {}  (refresh {:.1} ms)",
+                    snapshot.cpu_usage,
+                    snapshot.memory.used as f64 / 1024.0 / 1024.0 / 1024.0,
+                    snapshot.processes.len(),
+                    snapshot.overhead.cost.last_cycle_ms
+                );
+                if snapshot.overhead.cost.cycles >= 5 {
+                    break;
+                }
+            }
+            ServiceEvent::LinkChanged(link) => {
+                println!(
+                    "{}: {:?} -> {:?}",
+                    link.interface, link.previous, link.current
+                );
+            }
+            ServiceEvent::Recovered(message) => eprintln!("{}", message),
+            ServiceEvent::Alert(alert) => println!("alert: {}", alert.message),
+            ServiceEvent::Remediation(event) => println!("remediation: {}", event.message),
+            ServiceEvent::RecordingStopped(status) => {
+                println!("recording stopped: {} snapshots", status.snapshots_written)
+            }
+        }
+    }
+
+    service.stop();
+}
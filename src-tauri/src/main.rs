@@ -1,157 +1,266 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use rust_dashboard_lib::config::AppConfig;
-use rust_dashboard_lib::system::{CombinedProcess, ProcessDetails, SystemMonitor};
-use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use rust_dashboard_lib::alerts::{check_configured_commands, AlertRule};
+use rust_dashboard_lib::anomaly::AnomalyConfig;
+use rust_dashboard_lib::baseline::{
+    Baseline, BaselineInfo, BaselineStore, SnapshotDiff, DEFAULT_TOP,
+};
+#[cfg(feature = "columnar")]
+use rust_dashboard_lib::columnar::{export_points, recording_points, ColumnarFormat, SeriesPoint};
+use rust_dashboard_lib::config::{AdaptiveConfig, AppConfig, RefreshSchedule, Subsystem};
+use rust_dashboard_lib::delta::{DeltaEncoder, SnapshotMessage};
+use rust_dashboard_lib::export::{ExportData, ExportFormat, Exporter, HistorySeries};
+use rust_dashboard_lib::forecast::{DiskForecast, ForecastConfig};
+use rust_dashboard_lib::leaks::{LeakConfig, SuspectedLeak};
+use rust_dashboard_lib::push::PushConfig;
+use rust_dashboard_lib::query::{ProcessPage, ProcessQuery};
+use rust_dashboard_lib::recording::{self, RecordingConfig, RecordingStatus};
+use rust_dashboard_lib::remediation::{RemediationConfig, RemediationEvent};
+use rust_dashboard_lib::replay::{Replay, ReplayStatus};
+use rust_dashboard_lib::service::{MonitorService, ServiceEvent, SnapshotReader};
+use rust_dashboard_lib::snapshot::{HostInfo, SnapshotInterest, SystemSnapshot};
+use rust_dashboard_lib::sockets::{self, SocketEntry, SocketFilter};
+use rust_dashboard_lib::system::{unix_now, CombinedProcess, ProcessDetails, SystemMonitor};
+use rust_dashboard_lib::watch::{Watch, WatchTarget};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tauri::menu::{Menu, MenuItem};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
 use tauri::{Emitter, Listener, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use tauri_plugin_notification::NotificationExt;
 
 // --- Constants ---
 
 const HISTORY_CAPACITY: usize = 300;
 const TRAY_POPUP_WIDTH: f64 = 340.0;
 const TRAY_POPUP_HEIGHT: f64 = 480.0;
-/// Background-thread sleep granularity. The loop sleeps in TICK-sized
-/// chunks and re-reads `refresh_interval` and `paused` between each chunk,
-/// so changes to either setting take effect within one tick instead of at
-/// the end of the configured interval (which could be up to 60 s).
-const TICK: std::time::Duration = std::time::Duration::from_millis(250);
-
-// --- Response structs for frontend consumption ---
-
-#[derive(serde::Serialize, Clone)]
-pub struct SystemSnapshot {
-    pub cpu_usage: f32,
-    pub per_cpu: Vec<f32>,
-    pub memory: MemoryInfo,
-    pub disks: Vec<DiskInfo>,
-    pub networks: Vec<NetworkInfo>,
-    pub processes: Vec<CombinedProcess>,
-    pub self_usage: Option<SelfUsage>,
-    pub uptime_seconds: u64,
-    pub load_average: (f64, f64, f64),
-}
-
-#[derive(serde::Serialize, Clone)]
-pub struct SelfUsage {
-    pub cpu: f32,
-    pub memory: u64,
-}
-
-#[derive(serde::Serialize, Clone)]
-pub struct MemoryInfo {
-    pub used: u64,
-    pub free: u64,
-    pub total: u64,
-    pub available: u64,
-    pub swap_used: u64,
-    pub swap_total: u64,
-}
-
-#[derive(serde::Serialize, Clone)]
-pub struct DiskInfo {
-    pub name: String,
-    pub filesystem: String,
-    pub mount_point: String,
-    pub used: u64,
-    pub available: u64,
-    pub total: u64,
-}
-
-#[derive(serde::Serialize, Clone)]
-pub struct NetworkInfo {
-    pub interface: String,
-    pub rx_bytes: u64,
-    pub tx_bytes: u64,
-    pub rx_rate: f64,
-    pub tx_rate: f64,
-}
 
 // --- App State ---
 
 pub struct AppState {
+    /// Owns the refresh loop; the forwarding thread in `setup` turns
+    /// its events into history entries and frontend events.
+    pub service: MonitorService,
+    /// Latest published snapshot. Commands that only read snapshot data
+    /// use this, so they never wait behind a refresh.
+    pub snapshots: SnapshotReader,
+    /// Same monitor the service refreshes, for commands that need more
+    /// than the snapshot (process details) or act on it (kill).
     pub monitor: Arc<Mutex<SystemMonitor>>,
-    pub refresh_interval: Arc<AtomicU32>,
-    pub paused: Arc<AtomicBool>,
     pub cpu_history: Arc<Mutex<VecDeque<(f64, f32)>>>,
     pub memory_history: Arc<Mutex<VecDeque<(f64, f64)>>>,
     pub history_start: std::time::Instant,
+    /// Snapshot subscription of each window, keyed by window label.
+    /// Only windows that are visible count towards the monitor's
+    /// visible set (see `visible_subsystems`) or receive updates (see
+    /// `publish`).
+    pub windows: Arc<Mutex<HashMap<String, WindowStream>>>,
+    /// Recording being replayed in place of live data, if any. The
+    /// service polls it; replay commands move its play head.
+    pub replay: Arc<Mutex<Option<Arc<Mutex<Replay>>>>>,
+}
+
+/// One window's `system-delta` stream: what it renders, and the encoder
+/// tracking what it already holds. The window fetches a full snapshot
+/// with `subscribe_snapshots`, then applies deltas, resyncing the same
+/// way if it sees a gap.
+#[derive(Default)]
+pub struct WindowStream {
+    interest: SnapshotInterest,
+    encoder: DeltaEncoder,
 }
 
-// --- Helper to build snapshot from monitor ---
+type Windows = Mutex<HashMap<String, WindowStream>>;
 
-fn build_snapshot(monitor: &SystemMonitor) -> SystemSnapshot {
-    let (used, free, total, available, swap_used, swap_total) = monitor.memory_info();
-    let per_cpu: Vec<f32> = monitor
-        .sys
-        .cpus()
+/// Labels of registered windows that are currently visible, with their
+/// interests. Entries for windows that no longer exist are pruned.
+fn visible_windows(
+    app_handle: &tauri::AppHandle,
+    windows: &Windows,
+) -> Vec<(String, SnapshotInterest)> {
+    // Copy out and release the lock before querying windows:
+    // `is_visible` round-trips through the main thread, which may itself
+    // be waiting on this mutex inside `set_subscription`.
+    let registered: Vec<(String, SnapshotInterest)> = windows
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
         .iter()
-        .map(|cpu| cpu.cpu_usage())
+        .map(|(label, stream)| (label.clone(), stream.interest.clone()))
         .collect();
-    let self_usage = monitor
-        .usage_for_pid(std::process::id())
-        .map(|(cpu, mem)| SelfUsage { cpu, memory: mem });
-
-    SystemSnapshot {
-        cpu_usage: monitor.global_cpu_usage(),
-        per_cpu,
-        memory: MemoryInfo {
-            used,
-            free,
-            total,
-            available,
-            swap_used,
-            swap_total,
-        },
-        disks: monitor
-            .disk_info()
-            .into_iter()
-            .map(
-                |(name, filesystem, mount_point, used, available, total)| DiskInfo {
-                    name,
-                    filesystem,
-                    mount_point,
-                    used,
-                    available,
-                    total,
-                },
-            )
-            .collect(),
-        networks: monitor
-            .network_info_with_rates()
+
+    let mut visible = Vec::new();
+    let mut gone = Vec::new();
+    for (label, interest) in registered {
+        match app_handle.get_webview_window(&label) {
+            Some(win) => {
+                if win.is_visible().unwrap_or(false) {
+                    visible.push((label, interest));
+                }
+            }
+            None => gone.push(label),
+        }
+    }
+    if !gone.is_empty() {
+        let mut windows = windows.lock().unwrap_or_else(|e| e.into_inner());
+        for label in gone {
+            windows.remove(&label);
+        }
+    }
+    visible
+}
+
+/// Union of the subsystems rendered by currently visible windows.
+fn visible_subsystems(app_handle: &tauri::AppHandle, windows: &Windows) -> HashSet<Subsystem> {
+    visible_windows(app_handle, windows)
+        .into_iter()
+        .flat_map(|(_, interest)| interest.subsystems)
+        .collect()
+}
+
+/// Send each visible window a delta of the part of `snapshot` it
+/// renders. Hidden windows are skipped without advancing their encoder,
+/// so once shown they get one delta against what they last held.
+fn publish(app_handle: &tauri::AppHandle, windows: &Windows, snapshot: &SystemSnapshot) {
+    let visible = visible_windows(app_handle, windows);
+    let messages: Vec<(String, SnapshotMessage)> = {
+        let mut windows = windows.lock().unwrap_or_else(|e| e.into_inner());
+        visible
             .into_iter()
-            .map(
-                |(interface, rx_bytes, tx_bytes, rx_rate, tx_rate)| NetworkInfo {
-                    interface,
-                    rx_bytes,
-                    tx_bytes,
-                    rx_rate,
-                    tx_rate,
-                },
-            )
-            .collect(),
-        processes: monitor.combined_process_list().to_vec(),
-        self_usage,
-        uptime_seconds: monitor.system_uptime(),
-        load_average: monitor.load_average(),
+            .filter_map(|(label, _)| {
+                let stream = windows.get_mut(&label)?;
+                let message = stream.encoder.encode(&stream.interest.apply(snapshot));
+                Some((label, message))
+            })
+            .collect()
+    };
+    for (label, message) in messages {
+        let _ = app_handle.emit_to(label.as_str(), "system-delta", &message);
     }
 }
 
 // --- Tauri Commands ---
 
+const NOT_PUBLISHED: &str = "No snapshot published yet";
+
 #[tauri::command]
 fn get_system_snapshot(state: tauri::State<'_, AppState>) -> Result<SystemSnapshot, String> {
-    let monitor = state.monitor.lock().map_err(|e| e.to_string())?;
-    Ok(build_snapshot(&monitor))
+    let snapshot = state.snapshots.latest().ok_or(NOT_PUBLISHED)?;
+    Ok(SystemSnapshot::clone(&snapshot))
+}
+
+/// Full snapshot of what the calling window subscribed to, at its
+/// current delta sequence number, for a window that is starting to
+/// listen to `system-delta` or resyncing after a gap.
+#[tauri::command]
+fn subscribe_snapshots(
+    state: tauri::State<'_, AppState>,
+    window: tauri::Window,
+) -> Result<SnapshotMessage, String> {
+    let mut windows = state.windows.lock().map_err(|e| e.to_string())?;
+    let stream = windows.entry(window.label().to_string()).or_default();
+    if let Some(full) = stream.encoder.full() {
+        return Ok(full);
+    }
+    // Nothing sent to this window yet: seed its encoder from the latest
+    // snapshot. Before the first publish the window's first delta
+    // message arrives as a full snapshot instead.
+    let snapshot = state.snapshots.latest().ok_or(NOT_PUBLISHED)?;
+    Ok(stream.encoder.encode(&stream.interest.apply(&snapshot)))
 }
 
 #[tauri::command]
 fn get_processes(state: tauri::State<'_, AppState>) -> Result<Vec<CombinedProcess>, String> {
+    let snapshot = state.snapshots.latest().ok_or(NOT_PUBLISHED)?;
+    Ok(snapshot.processes.clone())
+}
+
+/// Filter, sort and page the process list in the backend. Needs the
+/// monitor for per-PID user and command line, so it waits for the lock
+/// on a blocking worker rather than on the main thread during a refresh.
+#[tauri::command]
+async fn query_processes(
+    state: tauri::State<'_, AppState>,
+    query: ProcessQuery,
+) -> Result<ProcessPage, String> {
+    let monitor = state.monitor.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let monitor = monitor.lock().map_err(|e| e.to_string())?;
+        query.run(&monitor).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Pin a process or group; its history is recorded from the next
+/// process refresh. Returns the watch id.
+#[tauri::command]
+fn watch_process(state: tauri::State<'_, AppState>, target: WatchTarget) -> Result<u64, String> {
+    let mut monitor = state.monitor.lock().map_err(|e| e.to_string())?;
+    Ok(monitor.watches.add(target))
+}
+
+#[tauri::command]
+fn unwatch_process(state: tauri::State<'_, AppState>, id: u64) -> Result<bool, String> {
+    let mut monitor = state.monitor.lock().map_err(|e| e.to_string())?;
+    Ok(monitor.watches.remove(id))
+}
+
+/// Process groups whose memory is growing steadily; see
+/// `leaks::LeakDetector`.
+#[tauri::command]
+fn get_suspected_leaks(state: tauri::State<'_, AppState>) -> Result<Vec<SuspectedLeak>, String> {
     let monitor = state.monitor.lock().map_err(|e| e.to_string())?;
-    Ok(monitor.combined_process_list().to_vec())
+    Ok(monitor.leaks.suspects().to_vec())
+}
+
+#[tauri::command]
+fn set_leak_config(state: tauri::State<'_, AppState>, config: LeakConfig) -> Result<(), String> {
+    let mut monitor = state.monitor.lock().map_err(|e| e.to_string())?;
+    monitor.leaks.set_config(config);
+    Ok(())
+}
+
+/// Usage trend and time-until-full per mount, soonest full first; see
+/// `forecast::DiskForecaster`.
+#[tauri::command]
+fn get_disk_forecasts(state: tauri::State<'_, AppState>) -> Result<Vec<DiskForecast>, String> {
+    let monitor = state.monitor.lock().map_err(|e| e.to_string())?;
+    Ok(monitor
+        .disk_forecasts
+        .forecasts()
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+#[tauri::command]
+fn set_anomaly_config(
+    state: tauri::State<'_, AppState>,
+    config: AnomalyConfig,
+) -> Result<(), String> {
+    let mut monitor = state.monitor.lock().map_err(|e| e.to_string())?;
+    monitor.anomalies.set_config(config);
+    monitor.watches.anomaly_config = config;
+    Ok(())
+}
+
+#[tauri::command]
+fn set_disk_forecast_config(
+    state: tauri::State<'_, AppState>,
+    config: ForecastConfig,
+) -> Result<(), String> {
+    let mut monitor = state.monitor.lock().map_err(|e| e.to_string())?;
+    monitor.disk_forecasts.set_config(config);
+    Ok(())
+}
+
+/// Pinned processes and groups with their recorded history.
+#[tauri::command]
+fn get_watches(state: tauri::State<'_, AppState>) -> Result<Vec<Watch>, String> {
+    let monitor = state.monitor.lock().map_err(|e| e.to_string())?;
+    Ok(monitor.watches.watches().to_vec())
 }
 
 #[tauri::command]
@@ -172,15 +281,349 @@ fn kill_process(state: tauri::State<'_, AppState>, pid: u32) -> Result<(), Strin
     monitor.kill_process(pid)
 }
 
+#[tauri::command]
+fn get_connections(filter: Option<SocketFilter>) -> Result<Vec<SocketEntry>, String> {
+    // Reads procfs directly — no monitor lock needed, so a slow fd scan on
+    // a busy host doesn't stall the background refresh.
+    let entries = sockets::read_socket_table(std::path::Path::new(sockets::PROC_ROOT))
+        .map_err(|e| e.to_string())?;
+    Ok(filter.unwrap_or_default().apply(entries))
+}
+
 #[tauri::command]
 fn set_refresh_interval(state: tauri::State<'_, AppState>, seconds: u32) {
     let clamped = seconds.clamp(1, 60);
-    state.refresh_interval.store(clamped, Ordering::Release);
+    state
+        .service
+        .set_interval(Duration::from_secs(clamped as u64));
+}
+
+#[tauri::command]
+fn set_refresh_schedule(
+    state: tauri::State<'_, AppState>,
+    schedule: RefreshSchedule,
+) -> Result<(), String> {
+    let mut monitor = state.monitor.lock().map_err(|e| e.to_string())?;
+    monitor.set_schedule(schedule);
+    Ok(())
+}
+
+#[tauri::command]
+fn set_adaptive_config(
+    state: tauri::State<'_, AppState>,
+    config: AdaptiveConfig,
+) -> Result<(), String> {
+    state.service.set_adaptive_config(config);
+    Ok(())
+}
+
+/// Replace the alert rules without restarting. Invalid conditions are
+/// rejected and the previous rules stay active. Command actions have to
+/// match ones in `config.toml`, so the page can't pick a program to run.
+#[tauri::command]
+fn set_alert_rules(state: tauri::State<'_, AppState>, rules: Vec<AlertRule>) -> Result<(), String> {
+    check_configured_commands(&rules, &AppConfig::load().alerts).map_err(|e| e.to_string())?;
+    state
+        .service
+        .set_alert_rules(&rules)
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the remediation policies without restarting. Invalid policies
+/// are rejected and the previous ones stay active. Only `config.toml` can
+/// turn off dry run or `only_own_processes` or move the audit trail; see
+/// `RemediationConfig::restricted_to`.
+#[tauri::command]
+fn set_remediation_config(
+    state: tauri::State<'_, AppState>,
+    config: RemediationConfig,
+) -> Result<(), String> {
+    let configured = with_default_audit_log(AppConfig::load().remediation);
+    let config = config
+        .restricted_to(&configured)
+        .map_err(|e| e.to_string())?;
+    state
+        .service
+        .set_remediation_config(config)
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the InfluxDB/StatsD push targets without restarting. An
+/// invalid config is rejected and the previous targets keep receiving.
+#[tauri::command]
+fn set_push_config(state: tauri::State<'_, AppState>, config: PushConfig) -> Result<(), String> {
+    state
+        .service
+        .set_push_config(config)
+        .map_err(|e| e.to_string())
+}
+
+/// Recent remediation decisions, oldest first.
+#[tauri::command]
+fn get_remediation_log(state: tauri::State<'_, AppState>) -> Vec<RemediationEvent> {
+    state.service.remediation_log()
+}
+
+/// Start recording snapshots to rotating files, with `config` or else
+/// the `[recording]` settings from `config.toml`. The directory has to
+/// be inside the home directory.
+#[tauri::command]
+fn start_recording(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+    config: Option<RecordingConfig>,
+) -> Result<RecordingStatus, String> {
+    let config = config.unwrap_or_else(|| AppConfig::load().recording);
+    let status = start_recording_safely(&state.service, config)?;
+    recording_changed(&app_handle, Some(&status));
+    Ok(status)
+}
+
+/// Stop recording. The last file is compressed in the background.
+#[tauri::command]
+fn stop_recording(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Option<RecordingStatus> {
+    let status = state.service.stop_recording();
+    recording_changed(&app_handle, status.as_ref());
+    status
+}
+
+#[tauri::command]
+fn get_recording_status(state: tauri::State<'_, AppState>) -> Option<RecordingStatus> {
+    state.service.recording_status()
+}
+
+fn start_recording_safely(
+    service: &MonitorService,
+    mut config: RecordingConfig,
+) -> Result<RecordingStatus, String> {
+    let directory = config.resolved_directory().map_err(|e| e.to_string())?;
+    config.directory = Some(safe_directory(&directory)?);
+    service.start_recording(config).map_err(|e| e.to_string())
+}
+
+/// Recordings in the configured recording directory, oldest first.
+#[tauri::command]
+fn get_recordings() -> Result<Vec<PathBuf>, String> {
+    let directory = AppConfig::load()
+        .recording
+        .resolved_directory()
+        .map_err(|e| e.to_string())?;
+    Ok(recording::list_recordings(&directory))
+}
+
+/// Replay `paths` (the files of one recording, in any order) in place
+/// of live data, paused on the first snapshot. Files have to be inside
+/// the home directory. Indexing a long recording takes a while, so it
+/// runs off the main thread.
+#[tauri::command]
+async fn open_replay(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+    paths: Vec<String>,
+) -> Result<ReplayStatus, String> {
+    let home = dirs::home_dir().ok_or("Cannot determine home directory")?;
+    let paths = paths
+        .iter()
+        .map(|path| {
+            let canonical = Path::new(path)
+                .canonicalize()
+                .map_err(|e| format!("Cannot resolve {}: {}", path, e))?;
+            if !canonical.starts_with(&home) {
+                return Err("Replays are only allowed from your home directory".to_string());
+            }
+            Ok(canonical)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let replay = tauri::async_runtime::spawn_blocking(move || Replay::open(&paths))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    let replay = Arc::new(Mutex::new(replay));
+    let status = lock_replay(&replay).status(Instant::now());
+    *state.replay.lock().map_err(|e| e.to_string())? = Some(replay.clone());
+    clear_history(&state);
+    state.service.set_source(replay);
+    let _ = app_handle.emit("replay-changed", Some(&status));
+    Ok(status)
+}
+
+/// Leave the replay and go back to live data.
+#[tauri::command]
+fn close_replay(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), String> {
+    state.replay.lock().map_err(|e| e.to_string())?.take();
+    state.service.clear_source();
+    clear_history(&state);
+    let _ = app_handle.emit("replay-changed", None::<ReplayStatus>);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_replay_status(state: tauri::State<'_, AppState>) -> Option<ReplayStatus> {
+    replay_status(&state.replay)
+}
+
+#[tauri::command]
+fn replay_play(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<ReplayStatus, String> {
+    control_replay(&state, &app_handle, |replay, now| {
+        replay.play(now);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn replay_pause(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+) -> Result<ReplayStatus, String> {
+    control_replay(&state, &app_handle, |replay, now| {
+        replay.pause(now);
+        Ok(())
+    })
+}
+
+/// Jump to `timestamp` (Unix seconds). Chart history starts over.
+#[tauri::command]
+fn replay_seek(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+    timestamp: f64,
+) -> Result<ReplayStatus, String> {
+    clear_history(&state);
+    control_replay(&state, &app_handle, |replay, now| {
+        replay.seek(timestamp, now);
+        Ok(())
+    })
+}
+
+#[tauri::command]
+fn replay_set_speed(
+    state: tauri::State<'_, AppState>,
+    app_handle: tauri::AppHandle,
+    speed: f64,
+) -> Result<ReplayStatus, String> {
+    control_replay(&state, &app_handle, |replay, now| {
+        replay.set_speed(speed, now).map_err(|e| e.to_string())
+    })
+}
+
+fn lock_replay(replay: &Mutex<Replay>) -> std::sync::MutexGuard<'_, Replay> {
+    replay.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn replay_status(replay: &Mutex<Option<Arc<Mutex<Replay>>>>) -> Option<ReplayStatus> {
+    let replay = replay.lock().unwrap_or_else(|e| e.into_inner()).clone()?;
+    let status = lock_replay(&replay).status(Instant::now());
+    Some(status)
+}
+
+/// Apply `control` to the open replay and broadcast where it stands.
+fn control_replay(
+    state: &AppState,
+    app_handle: &tauri::AppHandle,
+    control: impl FnOnce(&mut Replay, Instant) -> Result<(), String>,
+) -> Result<ReplayStatus, String> {
+    let replay = state
+        .replay
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or("No replay open")?;
+    let status = {
+        let mut replay = lock_replay(&replay);
+        let now = Instant::now();
+        control(&mut replay, now)?;
+        replay.status(now)
+    };
+    let _ = app_handle.emit("replay-changed", Some(&status));
+    Ok(status)
+}
+
+/// Drop chart history, so live and replayed points (or points from
+/// before a seek) don't share a chart.
+fn clear_history(state: &AppState) {
+    state
+        .cpu_history
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+    state
+        .memory_history
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clear();
+}
+
+/// The tray's start/stop recording item.
+struct RecordingMenuItem(MenuItem<tauri::Wry>);
+
+fn recording_menu_text(recording: bool) -> &'static str {
+    if recording {
+        "Stop Recording"
+    } else {
+        "Start Recording"
+    }
+}
+
+/// Tell every window a recording started or stopped (`status.active`)
+/// and relabel the tray item.
+fn recording_changed(app_handle: &tauri::AppHandle, status: Option<&RecordingStatus>) {
+    let recording = status.is_some_and(|s| s.active);
+    if let Some(item) = app_handle.try_state::<RecordingMenuItem>() {
+        let _ = item.0.set_text(recording_menu_text(recording));
+    }
+    let _ = app_handle.emit("recording-changed", status);
+}
+
+/// Keep the audit trail next to `config.toml` unless configured
+/// elsewhere.
+fn with_default_audit_log(mut config: RemediationConfig) -> RemediationConfig {
+    if config.audit_log.is_none() {
+        config.audit_log = AppConfig::config_path()
+            .ok()
+            .map(|path| path.with_file_name("remediation-audit.jsonl"));
+    }
+    config
+}
+
+/// Change what the calling window renders, e.g. when its active view
+/// changes. Sections it gains arrive with the next delta.
+#[tauri::command]
+fn set_subscription(
+    state: tauri::State<'_, AppState>,
+    window: tauri::Window,
+    interest: SnapshotInterest,
+) -> Result<(), String> {
+    let mut windows = state.windows.lock().map_err(|e| e.to_string())?;
+    windows
+        .entry(window.label().to_string())
+        .or_default()
+        .interest = interest;
+    Ok(())
+}
+
+/// Re-enumerate disks. Runs on the monitor thread like any refresh; the
+/// result arrives on the `system-delta` stream.
+#[tauri::command]
+fn refresh_disks(state: tauri::State<'_, AppState>) {
+    state.service.request_disk_rescan();
 }
 
 #[tauri::command]
 fn set_paused(state: tauri::State<'_, AppState>, app_handle: tauri::AppHandle, paused: bool) {
-    state.paused.store(paused, Ordering::Release);
+    if paused {
+        state.service.pause();
+    } else {
+        state.service.resume();
+    }
     // Broadcast to all windows (including detached panels and the tray
     // popup) so each window's frontend can react locally — Svelte stores
     // are per-window, so the main window's paused store doesn't otherwise
@@ -188,11 +631,11 @@ fn set_paused(state: tauri::State<'_, AppState>, app_handle: tauri::AppHandle, p
     let _ = app_handle.emit("paused-changed", paused);
 }
 
+/// Refresh everything now. Repeated clicks before the monitor thread
+/// gets to it coalesce into one refresh.
 #[tauri::command]
-fn manual_refresh(state: tauri::State<'_, AppState>) -> Result<(), String> {
-    let mut monitor = state.monitor.lock().map_err(|e| e.to_string())?;
-    monitor.refresh();
-    Ok(())
+fn manual_refresh(state: tauri::State<'_, AppState>) {
+    state.service.request_refresh();
 }
 
 #[tauri::command]
@@ -212,44 +655,39 @@ fn load_config() -> AppConfig {
     AppConfig::load()
 }
 
+/// Write `config.toml`. The page can't add alert command actions or loosen
+/// remediation safeguards this way either; see `set_alert_rules` and
+/// `set_remediation_config`.
 #[tauri::command]
-fn save_config(config: AppConfig) -> Result<(), String> {
+fn save_config(mut config: AppConfig) -> Result<(), String> {
+    let current = AppConfig::load();
+    check_configured_commands(&config.alerts, &current.alerts).map_err(|e| e.to_string())?;
+    config.remediation = config
+        .remediation
+        .restricted_to(&current.remediation)
+        .map_err(|e| e.to_string())?;
     config.save().map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-fn tray_refresh(state: tauri::State<'_, AppState>) -> Result<SystemSnapshot, String> {
-    let mut monitor = state.monitor.lock().map_err(|e| e.to_string())?;
-    // Respect the global paused state. When paused, return a snapshot of
-    // the current cached data without refreshing — matches the freeze that
-    // the background thread observes and avoids unnecessary mutex hold time.
-    if !state.paused.load(Ordering::Acquire) {
-        monitor.refresh();
+fn tray_refresh(state: tauri::State<'_, AppState>) {
+    // Respect the global paused state. The result arrives on the regular
+    // system-delta stream, so the popup doesn't need a snapshot back.
+    if !state.service.is_paused() {
+        state.service.request_refresh();
     }
-    Ok(build_snapshot(&monitor))
 }
 
-#[tauri::command]
-fn export_to_file(data: String, path: String) -> Result<(), String> {
-    let path_ref = std::path::Path::new(&path);
-
-    // Validate file extension
-    match path_ref.extension().and_then(|e| e.to_str()) {
-        Some("json") | Some("csv") => {}
-        _ => return Err("Only .json and .csv file extensions are allowed".to_string()),
-    }
-
-    let parent = path_ref
-        .parent()
-        .ok_or("Invalid path: no parent directory")?;
-
-    // The save dialog may produce a path under a subdirectory that doesn't
-    // exist yet (e.g. ~/Exports/2026-05/dashboard.json). Walk up until we
+/// Create `dir` if needed and return its canonical form, refusing
+/// anything outside the home directory.
+fn safe_directory(dir: &Path) -> Result<PathBuf, String> {
+    // The directory may not exist yet (a save-dialog path like
+    // ~/Exports/2026-05/dashboard.json, or a new recording directory). Walk up until we
     // find an existing ancestor; canonicalize and security-check THAT
     // ancestor. This preserves the path-traversal protection while letting
     // us create intermediate directories on demand.
     let home = dirs::home_dir().ok_or("Cannot determine home directory")?;
-    let mut existing_ancestor = parent;
+    let mut existing_ancestor = dir;
     while !existing_ancestor.exists() {
         existing_ancestor = existing_ancestor
             .parent()
@@ -265,24 +703,264 @@ fn export_to_file(data: String, path: String) -> Result<(), String> {
     // Create any missing intermediate directories under the verified-safe
     // ancestor. create_dir_all never creates symlinks, so the new
     // components inherit the ancestor's safety.
-    std::fs::create_dir_all(parent).map_err(|e| format!("Cannot create directory: {}", e))?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("Cannot create directory: {}", e))?;
 
     // Defense-in-depth: re-canonicalize after creation. This resolves any
     // `..` components in the original path (Path::parent is lexical and
     // doesn't, so a malicious path like ~/foo/../../etc/file.json would
     // walk up to ~/foo as an existing ancestor and pass the first check,
     // but resolve to /etc here and fail the second).
-    let canonical_parent = parent
+    let canonical = dir
         .canonicalize()
         .map_err(|e| format!("Cannot resolve path after creating directories: {}", e))?;
-    if !canonical_parent.starts_with(&home) {
+    if !canonical.starts_with(&home) {
         return Err("Writes are only allowed within your home directory".to_string());
     }
+    Ok(canonical)
+}
+
+/// `path` for an export, checked to have an export extension and to be
+/// under the home directory, with its directory created.
+fn safe_export_path(path: &str) -> Result<PathBuf, String> {
+    let path_ref = Path::new(path);
+
+    // Validate file extension
+    match path_ref.extension().and_then(|e| e.to_str()) {
+        Some(
+            "json" | "csv" | "ndjson" | "jsonl" | "md" | "html" | "parquet" | "arrow" | "feather",
+        ) => {}
+        _ => {
+            return Err(
+                "Only .json, .csv, .ndjson, .jsonl, .md, .html, .parquet, .arrow and \
+                 .feather file extensions are allowed"
+                    .to_string(),
+            )
+        }
+    }
+
+    let parent = path_ref
+        .parent()
+        .ok_or("Invalid path: no parent directory")?;
+    let canonical_parent = safe_directory(parent)?;
 
     let file_name = path_ref.file_name().ok_or("Invalid filename")?;
-    let safe_path = canonical_parent.join(file_name);
+    Ok(canonical_parent.join(file_name))
+}
+
+/// The latest snapshot and chart history. While a replay is open both
+/// come from the recording, at its recorded times: the history is the
+/// last `HISTORY_CAPACITY` frames up to the play head.
+fn export_data(state: &AppState) -> Result<ExportData, String> {
+    let host = HostInfo::current(&state.monitor.lock().map_err(|e| e.to_string())?);
+    let replay = state
+        .replay
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    if let Some(replay) = replay {
+        let replay = lock_replay(&replay);
+        let head = replay.status(Instant::now()).frame;
+        let mut cpu = Vec::new();
+        let mut memory = Vec::new();
+        let mut last = None;
+        for frame in (head + 1).saturating_sub(HISTORY_CAPACITY)..=head {
+            let frame = replay.frame(frame).map_err(|e| e.to_string())?;
+            cpu.push((frame.timestamp, frame.snapshot.cpu_usage as f64));
+            memory.push((frame.timestamp, used_gb(&frame.snapshot)));
+            last = Some(frame);
+        }
+        let last = last.ok_or(NOT_PUBLISHED)?;
+        return Ok(ExportData {
+            timestamp: last.timestamp,
+            host,
+            snapshot: last.snapshot,
+            history: vec![
+                HistorySeries::new("cpu_usage", "%", cpu),
+                HistorySeries::new("memory_used", "GiB", memory),
+            ],
+        });
+    }
+
+    let snapshot = state.snapshots.latest().ok_or(NOT_PUBLISHED)?;
+    // History is kept in seconds since startup; shift it to unix time.
+    let offset = unix_now() - state.history_start.elapsed().as_secs_f64();
+    let cpu = {
+        let history = state.cpu_history.lock().map_err(|e| e.to_string())?;
+        history
+            .iter()
+            .map(|&(t, v)| (t + offset, v as f64))
+            .collect()
+    };
+    let memory = {
+        let history = state.memory_history.lock().map_err(|e| e.to_string())?;
+        history.iter().map(|&(t, v)| (t + offset, v)).collect()
+    };
+    Ok(ExportData {
+        timestamp: unix_now(),
+        host,
+        snapshot: SystemSnapshot::clone(&snapshot),
+        history: vec![
+            HistorySeries::new("cpu_usage", "%", cpu),
+            HistorySeries::new("memory_used", "GiB", memory),
+        ],
+    })
+}
 
-    std::fs::write(&safe_path, data).map_err(|e| e.to_string())
+/// Memory in use as charted, in GiB.
+fn used_gb(snapshot: &SystemSnapshot) -> f64 {
+    snapshot.memory.used as f64 / 1024.0 / 1024.0 / 1024.0
+}
+
+/// Write the latest snapshot and chart history to `path` in the format
+/// picked by `options` (see `export::Exporter`).
+#[tauri::command]
+fn export_report(
+    state: tauri::State<'_, AppState>,
+    options: Exporter,
+    path: String,
+) -> Result<(), String> {
+    let safe_path = safe_export_path(&path)?;
+    let data = export_data(&state)?;
+    let file = std::fs::File::create(&safe_path).map_err(|e| e.to_string())?;
+    let mut file = std::io::BufWriter::new(file);
+    options.write(&data, &mut file).map_err(|e| e.to_string())?;
+    std::io::Write::flush(&mut file).map_err(|e| e.to_string())
+}
+
+/// Whether `export_columnar` works in this build.
+#[tauri::command]
+fn columnar_export_available() -> bool {
+    cfg!(feature = "columnar")
+}
+
+/// Write chart history and the latest snapshot, or every frame of the
+/// open replay, to `path` as Parquet or Arrow (by its extension; see
+/// `columnar`). `last_seconds` keeps only the end of either.
+#[cfg(feature = "columnar")]
+#[tauri::command]
+fn export_columnar(
+    state: tauri::State<'_, AppState>,
+    path: String,
+    last_seconds: Option<f64>,
+) -> Result<(), String> {
+    let safe_path = safe_export_path(&path)?;
+    let format = safe_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(ColumnarFormat::from_extension)
+        .ok_or("Parquet and Arrow exports need a .parquet, .arrow or .feather file")?;
+    let until = |end: f64| last_seconds.map(|seconds| (end - seconds, end));
+    let replay = state
+        .replay
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    // Points are written as they're produced, so a long replay is never
+    // in memory all at once.
+    let write = |points: &mut dyn Iterator<Item = SeriesPoint>| {
+        let file = std::fs::File::create(&safe_path).map_err(|e| e.to_string())?;
+        let mut file = std::io::BufWriter::new(file);
+        format.write(points, &mut file).map_err(|e| e.to_string())?;
+        std::io::Write::flush(&mut file).map_err(|e| e.to_string())
+    };
+    match replay {
+        Some(replay) => {
+            let replay = lock_replay(&replay);
+            write(&mut recording_points(
+                replay.frames(),
+                until(replay.end()),
+                usize::MAX,
+            ))
+        }
+        None => {
+            let data = export_data(&state)?;
+            write(&mut export_points(&data, until(data.timestamp)))
+        }
+    }
+}
+
+#[cfg(not(feature = "columnar"))]
+#[tauri::command]
+fn export_columnar(path: String, last_seconds: Option<f64>) -> Result<(), String> {
+    let _ = (path, last_seconds);
+    Err("This build can't export Parquet or Arrow; rebuild with the `columnar` feature".to_string())
+}
+
+// --- Baselines ---
+
+fn baseline_store() -> Result<BaselineStore, String> {
+    BaselineStore::open_default().map_err(|e| e.to_string())
+}
+
+/// The snapshot on screen and when it was taken: the replayed one while
+/// a replay is open, so recorded sessions can be saved and compared too.
+fn shown_snapshot(state: &AppState) -> Result<(Arc<SystemSnapshot>, f64), String> {
+    let snapshot = state.snapshots.latest().ok_or(NOT_PUBLISHED)?;
+    let timestamp = replay_status(&state.replay).map_or_else(unix_now, |s| s.position);
+    Ok((snapshot, timestamp))
+}
+
+#[tauri::command]
+fn list_baselines() -> Result<Vec<BaselineInfo>, String> {
+    baseline_store()?.list().map_err(|e| e.to_string())
+}
+
+/// Save the snapshot on screen as baseline `name`, replacing any
+/// baseline of that name.
+#[tauri::command]
+fn save_baseline(state: tauri::State<'_, AppState>, name: String) -> Result<BaselineInfo, String> {
+    let (snapshot, timestamp) = shown_snapshot(&state)?;
+    let host = HostInfo::current(&state.monitor.lock().map_err(|e| e.to_string())?);
+    let baseline = Baseline::new(&name, timestamp, host, SystemSnapshot::clone(&snapshot))
+        .map_err(|e| e.to_string())?;
+    baseline_store()?
+        .save(&baseline)
+        .map_err(|e| e.to_string())?;
+    Ok(baseline.info())
+}
+
+#[tauri::command]
+fn delete_baseline(name: String) -> Result<(), String> {
+    baseline_store()?.delete(&name).map_err(|e| e.to_string())
+}
+
+/// Compare the snapshot on screen against baseline `name`.
+#[tauri::command]
+fn compare_baseline(
+    state: tauri::State<'_, AppState>,
+    name: String,
+    top: Option<usize>,
+) -> Result<SnapshotDiff, String> {
+    let baseline = baseline_store()?.load(&name).map_err(|e| e.to_string())?;
+    let (snapshot, timestamp) = shown_snapshot(&state)?;
+    Ok(SnapshotDiff::compare(
+        &baseline,
+        &snapshot,
+        timestamp,
+        top.unwrap_or(DEFAULT_TOP),
+    ))
+}
+
+/// Write `compare_baseline`'s diff to `path`, in the format its
+/// extension names. Columnar extensions, which `safe_export_path`
+/// allows for history, are rejected.
+#[tauri::command]
+fn export_baseline_diff(
+    state: tauri::State<'_, AppState>,
+    name: String,
+    path: String,
+) -> Result<(), String> {
+    let safe_path = safe_export_path(&path)?;
+    let format = safe_path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(ExportFormat::from_extension)
+        .ok_or("Baseline diffs export to .json, .csv, .ndjson, .jsonl, .md or .html files")?;
+    let diff = compare_baseline(state, name, None)?;
+    let file = std::fs::File::create(&safe_path).map_err(|e| e.to_string())?;
+    let mut file = std::io::BufWriter::new(file);
+    diff.write(format, &mut file).map_err(|e| e.to_string())?;
+    std::io::Write::flush(&mut file).map_err(|e| e.to_string())
 }
 
 // --- Main ---
@@ -297,25 +975,49 @@ fn main() {
     }));
 
     let config = AppConfig::load();
-    let monitor = Arc::new(Mutex::new(SystemMonitor::new()));
-    let refresh_interval = Arc::new(AtomicU32::new(config.refresh_interval_seconds));
-    let paused = Arc::new(AtomicBool::new(false));
+    let service = MonitorService::builder()
+        .interval(Duration::from_secs(config.refresh_interval_seconds as u64))
+        .schedule(config.refresh)
+        .adaptive(config.adaptive)
+        .leak_detection(config.leaks)
+        .disk_forecast(config.disk_forecast)
+        .anomaly_detection(config.anomalies)
+        .build();
+    if let Err(e) = service.set_alert_rules(&config.alerts) {
+        log::warn!("Alert rules disabled: {}", e);
+    }
+    if let Err(e) = service.set_remediation_config(with_default_audit_log(config.remediation)) {
+        log::warn!("Remediation policies disabled: {}", e);
+    }
+    if let Err(e) = service.set_push_config(config.push) {
+        log::warn!("Push exporters disabled: {}", e);
+    }
+    if config.recording.enabled {
+        if let Err(e) = start_recording_safely(&service, config.recording) {
+            log::warn!("Recording not started: {}", e);
+        }
+    }
     let cpu_history = Arc::new(Mutex::new(VecDeque::with_capacity(HISTORY_CAPACITY)));
     let memory_history = Arc::new(Mutex::new(VecDeque::with_capacity(HISTORY_CAPACITY)));
     let history_start = std::time::Instant::now();
+    let windows = Arc::new(Mutex::new(HashMap::new()));
+    let replay = Arc::new(Mutex::new(None));
 
     let app_state = AppState {
-        monitor: monitor.clone(),
-        refresh_interval: refresh_interval.clone(),
-        paused: paused.clone(),
+        snapshots: service.snapshots(),
+        monitor: service.monitor(),
+        service,
         cpu_history: cpu_history.clone(),
         memory_history: memory_history.clone(),
         history_start,
+        windows: windows.clone(),
+        replay: replay.clone(),
     };
 
     let app = tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_window_state::Builder::default().build())
         .manage(app_state)
         .setup(move |app| {
@@ -329,8 +1031,17 @@ fn main() {
                 .ok_or("No default window icon found")?;
 
             let show_item = MenuItem::with_id(app, "show", "Show Dashboard", true, None::<&str>)?;
+            let recording = app.state::<AppState>().service.recording_status().is_some();
+            let record_item = MenuItem::with_id(
+                app,
+                "record",
+                recording_menu_text(recording),
+                true,
+                None::<&str>,
+            )?;
             let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-            let tray_menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+            let tray_menu = Menu::with_items(app, &[&show_item, &record_item, &quit_item])?;
+            app.manage(RecordingMenuItem(record_item));
 
             TrayIconBuilder::new()
                 .icon(icon)
@@ -344,6 +1055,24 @@ fn main() {
                             let _ = win.set_focus();
                         }
                     }
+                    "record" => {
+                        let state = app_handle.state::<AppState>();
+                        if state.service.recording_status().is_some() {
+                            let status = state.service.stop_recording();
+                            recording_changed(app_handle, status.as_ref());
+                        } else {
+                            match start_recording_safely(
+                                &state.service,
+                                AppConfig::load().recording,
+                            ) {
+                                Ok(status) => recording_changed(app_handle, Some(&status)),
+                                Err(e) => {
+                                    log::warn!("Recording not started: {}", e);
+                                    let _ = app_handle.emit("system-error", e);
+                                }
+                            }
+                        }
+                    }
                     "quit" => {
                         app_handle.exit(0);
                     }
@@ -459,79 +1188,76 @@ fn main() {
                 }
             });
 
-            // --- Background thread for system monitoring ---
+            // --- Monitor service and event forwarding ---
+            let state = app.state::<AppState>();
+            let visibility_handle = app.handle().clone();
+            let visibility_windows = windows.clone();
+            state.service.set_visibility_source(move || {
+                visible_subsystems(&visibility_handle, &visibility_windows)
+            });
+            let notify_handle = app.handle().clone();
+            state.service.set_notifier(move |alert| {
+                notify_handle
+                    .notification()
+                    .builder()
+                    .title(format!("Rust Dashboard: {}", alert.rule))
+                    .body(&alert.message)
+                    .show()
+                    .map_err(|e| e.to_string())
+            });
+            let events = state.service.subscribe();
+            state.service.start();
+
             let bg_handle = app.handle().clone();
-            let monitor = monitor.clone();
-            let refresh_interval = refresh_interval.clone();
-            let paused = paused.clone();
             let cpu_history = cpu_history.clone();
             let memory_history = memory_history.clone();
-
+            let windows = windows.clone();
+            let replay = replay.clone();
             std::thread::spawn(move || {
-                // Track whether we've already emitted a system-error for
-                // mutex poisoning. std::sync::Mutex has no stable API to
-                // clear poison, so subsequent lock() calls all return Err.
-                // Emitting once per detection would cause the error banner
-                // to flicker on every loop iteration after recovery. Emit
-                // once on the first detection, then stay silent.
-                let mut poison_alerted = false;
-                loop {
-                    let interval_secs = refresh_interval.load(Ordering::Acquire);
-                    let was_paused = paused.load(Ordering::Acquire);
-
-                    if !was_paused {
-                        let snapshot = {
-                            let mut mon = monitor.lock().unwrap_or_else(|e| {
-                                log::warn!("Monitor mutex was poisoned, recovering: {}", e);
-                                if !poison_alerted {
-                                    let _ = bg_handle.emit(
-                                        "system-error",
-                                        "Monitor recovered from internal error — data may be temporarily stale",
-                                    );
-                                    poison_alerted = true;
+                // Ends when the service stops and drops the subscription.
+                for event in events {
+                    match event {
+                        ServiceEvent::Snapshot(snapshot) => {
+                            let elapsed = history_start.elapsed().as_secs_f64();
+                            {
+                                let mut hist =
+                                    cpu_history.lock().unwrap_or_else(|e| e.into_inner());
+                                hist.push_back((elapsed, snapshot.cpu_usage));
+                                while hist.len() > HISTORY_CAPACITY {
+                                    hist.pop_front();
                                 }
-                                e.into_inner()
-                            });
-                            mon.refresh();
-                            build_snapshot(&mon)
-                        };
+                            }
+                            {
+                                let mut hist =
+                                    memory_history.lock().unwrap_or_else(|e| e.into_inner());
+                                hist.push_back((elapsed, used_gb(&snapshot)));
+                                while hist.len() > HISTORY_CAPACITY {
+                                    hist.pop_front();
+                                }
+                            }
 
-                        // Update history
-                        let elapsed = history_start.elapsed().as_secs_f64();
-                        {
-                            let mut hist = cpu_history.lock().unwrap_or_else(|e| e.into_inner());
-                            hist.push_back((elapsed, snapshot.cpu_usage));
-                            while hist.len() > HISTORY_CAPACITY {
-                                hist.pop_front();
+                            publish(&bg_handle, &windows, &snapshot);
+                            if let Some(status) = replay_status(&replay) {
+                                let _ = bg_handle.emit("replay-changed", Some(status));
                             }
                         }
-                        {
-                            let mut hist = memory_history.lock().unwrap_or_else(|e| e.into_inner());
-                            let used_gb = snapshot.memory.used as f64 / 1024.0 / 1024.0 / 1024.0;
-                            hist.push_back((elapsed, used_gb));
-                            while hist.len() > HISTORY_CAPACITY {
-                                hist.pop_front();
-                            }
+                        ServiceEvent::LinkChanged(link_event) => {
+                            let _ = bg_handle.emit("network-link-changed", &link_event);
                         }
-
-                        // Emit to frontend (all windows)
-                        let _ = bg_handle.emit("system-update", &snapshot);
-                    }
-
-                    // Sleep in TICK-sized chunks instead of one long sleep,
-                    // so a change to refresh_interval or paused takes effect
-                    // within one tick (~250 ms) rather than at the end of
-                    // the configured interval (up to 60 s).
-                    let target = std::time::Duration::from_secs(interval_secs as u64);
-                    let mut waited = std::time::Duration::ZERO;
-                    while waited < target {
-                        std::thread::sleep(TICK);
-                        waited += TICK;
-                        if refresh_interval.load(Ordering::Acquire) != interval_secs {
-                            break;
+                        ServiceEvent::Recovered(message) => {
+                            let _ = bg_handle.emit("system-error", message);
+                        }
+                        ServiceEvent::Alert(alert) => {
+                            let _ = bg_handle.emit("alert", &alert);
                         }
-                        if paused.load(Ordering::Acquire) != was_paused {
-                            break;
+                        ServiceEvent::Remediation(event) => {
+                            let _ = bg_handle.emit("remediation", &event);
+                        }
+                        ServiceEvent::RecordingStopped(status) => {
+                            if let Some(error) = &status.error {
+                                let _ = bg_handle.emit("system-error", error);
+                            }
+                            recording_changed(&bg_handle, Some(&status));
                         }
                     }
                 }
@@ -541,17 +1267,54 @@ fn main() {
         })
         .invoke_handler(tauri::generate_handler![
             get_system_snapshot,
+            subscribe_snapshots,
             get_processes,
+            query_processes,
+            watch_process,
+            unwatch_process,
+            get_watches,
+            get_suspected_leaks,
+            set_leak_config,
+            get_disk_forecasts,
+            set_disk_forecast_config,
+            set_anomaly_config,
             get_process_details,
             kill_process,
+            get_connections,
             set_refresh_interval,
+            set_refresh_schedule,
+            set_subscription,
+            set_adaptive_config,
+            set_alert_rules,
+            set_remediation_config,
+            get_remediation_log,
+            set_push_config,
+            start_recording,
+            stop_recording,
+            get_recording_status,
+            get_recordings,
+            open_replay,
+            close_replay,
+            get_replay_status,
+            replay_play,
+            replay_pause,
+            replay_seek,
+            replay_set_speed,
+            refresh_disks,
             set_paused,
             manual_refresh,
             get_cpu_history,
             get_memory_history,
             load_config,
             save_config,
-            export_to_file,
+            export_report,
+            export_columnar,
+            columnar_export_available,
+            list_baselines,
+            save_baseline,
+            delete_baseline,
+            compare_baseline,
+            export_baseline_diff,
             tray_refresh,
         ])
         .build(tauri::generate_context!())
@@ -0,0 +1,471 @@
+//! What happens when an alert rule fires or resolves: a desktop
+//! notification, a local command, or a webhook. Actions run on a
+//! background thread so a slow endpoint never delays a refresh, are
+//! retried with backoff, and are rate limited per action.
+
+use crate::alerts::{AlertEvent, AlertState};
+use crate::system::unix_now;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn default_min_interval() -> u64 {
+    60
+}
+
+fn default_retries() -> u32 {
+    2
+}
+
+fn default_retry_delay() -> u64 {
+    1000
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+/// The kinds of action, tagged by `type` in `config.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ActionKind {
+    /// Native desktop notification, shown by the host app through the
+    /// notifier installed with `MonitorService::set_notifier`.
+    Notify,
+    /// Run `program` with `args`. The alert is passed as `RDASH_ALERT_*`
+    /// environment variables and as JSON on stdin; a non-zero exit
+    /// counts as a failure.
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default = "default_timeout")]
+        timeout_seconds: u64,
+    },
+    /// POST the alert as JSON to an `http://` or `https://` URL.
+    Webhook { url: String },
+}
+
+/// One action attached to an alert rule.
+///
+/// ```toml
+/// [[alerts]]
+/// name = "disk filling up"
+/// condition = "full_in < 1d"
+///
+/// [[alerts.actions]]
+/// type = "notify"
+///
+/// [[alerts.actions]]
+/// type = "webhook"
+/// url = "https://alerts.example.com/hook"
+/// min_interval_seconds = 600
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertAction {
+    #[serde(flatten)]
+    pub kind: ActionKind,
+    /// Minimum seconds between runs of this action for the same rule
+    /// and state, so a flapping rule doesn't flood the target. Events
+    /// inside the window are dropped.
+    #[serde(default = "default_min_interval")]
+    pub min_interval_seconds: u64,
+    /// Extra attempts after a failure.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Delay before the first retry, doubling for each one after.
+    #[serde(default = "default_retry_delay")]
+    pub retry_delay_ms: u64,
+}
+
+impl AlertAction {
+    pub fn new(kind: ActionKind) -> Self {
+        Self {
+            kind,
+            min_interval_seconds: default_min_interval(),
+            retries: default_retries(),
+            retry_delay_ms: default_retry_delay(),
+        }
+    }
+
+    /// Check settings that can be checked up front, like the webhook URL.
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.kind {
+            ActionKind::Notify => Ok(()),
+            ActionKind::Command { program, .. } if program.trim().is_empty() => {
+                Err("command action needs a program".to_string())
+            }
+            ActionKind::Command { .. } => Ok(()),
+            ActionKind::Webhook { url } => check_http_url("webhook", url),
+        }
+    }
+
+    /// Short description for logs.
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            ActionKind::Notify => "notification".to_string(),
+            ActionKind::Command { program, .. } => format!("command `{}`", program),
+            ActionKind::Webhook { url } => format!("webhook {}", url),
+        }
+    }
+}
+
+/// Shows a desktop notification for an alert.
+pub type Notifier = dyn Fn(&AlertEvent) -> Result<(), String> + Send + Sync;
+
+/// The JSON sent to commands and webhooks.
+#[derive(Debug, Serialize)]
+pub struct ActionPayload<'a> {
+    #[serde(flatten)]
+    pub event: &'a AlertEvent,
+    /// Seconds since the Unix epoch.
+    pub timestamp: f64,
+    pub host: String,
+}
+
+impl<'a> ActionPayload<'a> {
+    pub fn new(event: &'a AlertEvent) -> Self {
+        Self {
+            event,
+            timestamp: unix_now(),
+            host: sysinfo::System::host_name().unwrap_or_default(),
+        }
+    }
+}
+
+/// Why an attempt failed, and whether trying again could help.
+pub(crate) enum Failure {
+    Retry(String),
+    Permanent(String),
+}
+
+/// Run `action` for `event` now, retrying per the action's settings.
+/// Returns the number of attempts made, or the last error. Blocks; the
+/// service runs actions through an `ActionDispatcher` instead.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::actions::{run_action, ActionKind, AlertAction};
+/// use rust_dashboard_lib::alerts::{AlertEvent, AlertState};
+///
+/// let event = AlertEvent {
+///     rule: "test".to_string(),
+///     state: AlertState::Firing,
+///     matches: vec!["worker".to_string()],
+///     message: "test: worker matches `cpu > 90`".to_string(),
+/// };
+/// let action = AlertAction::new(ActionKind::Notify);
+/// assert!(run_action(&action, &event, None).is_err());
+/// let attempts = run_action(&action, &event, Some(&|_: &AlertEvent| Ok(()))).unwrap();
+/// assert_eq!(attempts, 1);
+/// ```
+pub fn run_action(
+    action: &AlertAction,
+    event: &AlertEvent,
+    notifier: Option<&Notifier>,
+) -> Result<u32, String> {
+    let mut delay = Duration::from_millis(action.retry_delay_ms);
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let result = match &action.kind {
+            ActionKind::Notify => match notifier {
+                Some(notify) => notify(event).map_err(Failure::Retry),
+                None => Err(Failure::Permanent(
+                    "no desktop notifier is installed".to_string(),
+                )),
+            },
+            ActionKind::Command {
+                program,
+                args,
+                timeout_seconds,
+            } => run_command(program, args, Duration::from_secs(*timeout_seconds), event),
+            ActionKind::Webhook { url } => post_webhook(url, event),
+        };
+        match result {
+            Ok(()) => return Ok(attempts),
+            Err(Failure::Permanent(e)) => return Err(e),
+            Err(Failure::Retry(e)) if attempts > action.retries => return Err(e),
+            Err(Failure::Retry(e)) => {
+                log::debug!(
+                    "{} for alert `{}` failed (attempt {}): {}",
+                    action.describe(),
+                    event.rule,
+                    attempts,
+                    e
+                );
+                thread::sleep(delay);
+                delay *= 2;
+            }
+        }
+    }
+}
+
+fn run_command(
+    program: &str,
+    args: &[String],
+    timeout: Duration,
+    event: &AlertEvent,
+) -> Result<(), Failure> {
+    let payload = serde_json::to_vec(&ActionPayload::new(event))
+        .map_err(|e| Failure::Permanent(e.to_string()))?;
+    let state = match event.state {
+        AlertState::Firing => "firing",
+        AlertState::Resolved => "resolved",
+    };
+    let mut child = Command::new(program)
+        .args(args)
+        .env("RDASH_ALERT_RULE", &event.rule)
+        .env("RDASH_ALERT_STATE", state)
+        .env("RDASH_ALERT_MATCHES", event.matches.join("\n"))
+        .env("RDASH_ALERT_MESSAGE", &event.message)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| Failure::Permanent(format!("cannot run `{}`: {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores stdin may exit before reading it.
+        let _ = stdin.write_all(&payload);
+    }
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => {
+                return Err(Failure::Retry(format!("`{}` {}", program, status)));
+            }
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(Failure::Retry(format!(
+                    "`{}` timed out after {}s",
+                    program,
+                    timeout.as_secs()
+                )));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(e) => return Err(Failure::Retry(e.to_string())),
+        }
+    }
+}
+
+impl Failure {
+    pub(crate) fn into_message(self) -> String {
+        match self {
+            Failure::Retry(message) | Failure::Permanent(message) => message,
+        }
+    }
+}
+
+/// Check that `url` is an `http://` or `https://` URL with a host.
+/// `what` names the URL's use in error messages.
+pub(crate) fn check_http_url(what: &str, url: &str) -> Result<(), String> {
+    let parsed = url::Url::parse(url).map_err(|e| format!("{} `{}`: {}", what, url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "{} `{}`: expected an http:// or https:// URL",
+            what, url
+        ));
+    }
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(format!("{} `{}`: missing host", what, url));
+    }
+    Ok(())
+}
+
+fn post_webhook(url: &str, event: &AlertEvent) -> Result<(), Failure> {
+    let body = serde_json::to_vec(&ActionPayload::new(event))
+        .map_err(|e| Failure::Permanent(e.to_string()))?;
+    http_post(
+        "webhook",
+        url,
+        &[("Content-Type", "application/json")],
+        &body,
+    )
+}
+
+/// One agent for every webhook and push request, so connections (and
+/// TLS sessions) to the same endpoint are reused. Redirects aren't
+/// followed: a POST would turn into a GET, and headers such as
+/// `Authorization` shouldn't follow it to another host.
+fn agent() -> &'static ureq::Agent {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT.get_or_init(|| {
+        ureq::AgentBuilder::new()
+            .timeout_connect(HTTP_TIMEOUT)
+            .timeout_read(HTTP_TIMEOUT)
+            .timeout_write(HTTP_TIMEOUT)
+            .redirects(0)
+            .user_agent(concat!("rust-dashboard/", env!("CARGO_PKG_VERSION")))
+            .build()
+    })
+}
+
+/// POST `body` to an `http://` or `https://` URL with `headers`,
+/// expecting a 2xx reply.
+pub(crate) fn http_post(
+    what: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Result<(), Failure> {
+    check_http_url(what, url).map_err(Failure::Permanent)?;
+    let mut request = agent().post(url);
+    for (name, value) in headers {
+        request = request.set(name, value);
+    }
+    let status = match request.send_bytes(body) {
+        Ok(response) => {
+            let status = response.status();
+            // Read the reply to the end so the connection can be reused.
+            let _ = std::io::copy(&mut response.into_reader(), &mut std::io::sink());
+            status
+        }
+        Err(ureq::Error::Status(status, _)) => status,
+        Err(ureq::Error::Transport(e)) => {
+            return Err(match e.kind() {
+                ureq::ErrorKind::InvalidUrl
+                | ureq::ErrorKind::UnknownScheme
+                | ureq::ErrorKind::HTTP => Failure::Permanent(format!("{}: {}", url, e)),
+                _ => Failure::Retry(format!("{}: {}", url, e)),
+            });
+        }
+    };
+    match status {
+        200..=299 => Ok(()),
+        // Rate limited or a server-side problem: worth another try.
+        429 | 500..=599 => Err(Failure::Retry(format!("{} returned {}", url, status))),
+        _ => Err(Failure::Permanent(format!("{} returned {}", url, status))),
+    }
+}
+
+struct Job {
+    action: AlertAction,
+    event: AlertEvent,
+    notifier: Option<Arc<Notifier>>,
+}
+
+/// Runs alert actions on a background thread, dropping runs that fall
+/// inside an action's `min_interval_seconds`.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::actions::{ActionDispatcher, ActionKind, AlertAction};
+/// use rust_dashboard_lib::alerts::{AlertEvent, AlertState};
+/// use std::time::Instant;
+///
+/// let mut dispatcher = ActionDispatcher::new();
+/// dispatcher.set_notifier(|event: &AlertEvent| {
+///     println!("{}", event.message);
+///     Ok(())
+/// });
+/// let event = AlertEvent {
+///     rule: "busy".to_string(),
+///     state: AlertState::Firing,
+///     matches: vec![],
+///     message: "busy".to_string(),
+/// };
+/// let actions = [AlertAction::new(ActionKind::Notify)];
+/// let now = Instant::now();
+/// assert_eq!(dispatcher.dispatch(&actions, &event, now), 1);
+/// // Inside the default 60 s window.
+/// assert_eq!(dispatcher.dispatch(&actions, &event, now), 0);
+/// ```
+#[derive(Default)]
+pub struct ActionDispatcher {
+    jobs: Option<mpsc::Sender<Job>>,
+    notifier: Option<Arc<Notifier>>,
+    /// Last run per (rule, action index, state).
+    last_run: HashMap<(String, usize, AlertState), Instant>,
+}
+
+impl ActionDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Install what `notify` actions call. Without one they fail.
+    pub fn set_notifier(
+        &mut self,
+        notifier: impl Fn(&AlertEvent) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        self.notifier = Some(Arc::new(notifier));
+    }
+
+    /// Queue each of `actions` for `event` unless rate limited, and
+    /// return how many were queued. Outcomes are logged.
+    pub fn dispatch(&mut self, actions: &[AlertAction], event: &AlertEvent, now: Instant) -> usize {
+        let mut queued = 0;
+        for (index, action) in actions.iter().enumerate() {
+            let key = (event.rule.clone(), index, event.state);
+            let window = Duration::from_secs(action.min_interval_seconds);
+            if let Some(&last) = self.last_run.get(&key) {
+                if now.saturating_duration_since(last) < window {
+                    log::debug!(
+                        "{} for alert `{}` rate limited",
+                        action.describe(),
+                        event.rule
+                    );
+                    continue;
+                }
+            }
+            self.last_run.insert(key, now);
+            let job = Job {
+                action: action.clone(),
+                event: event.clone(),
+                notifier: self.notifier.clone(),
+            };
+            let sender = self.jobs.get_or_insert_with(spawn_worker);
+            if let Err(mpsc::SendError(job)) = sender.send(job) {
+                // The worker died; start a fresh one for this and later
+                // jobs.
+                let sender = self.jobs.insert(spawn_worker());
+                let _ = sender.send(job);
+            }
+            queued += 1;
+        }
+        queued
+    }
+
+    /// Forget rate-limit history for rules no longer configured.
+    pub fn retain_rules(&mut self, keep: impl Fn(&str) -> bool) {
+        self.last_run.retain(|(rule, _, _), _| keep(rule));
+    }
+}
+
+fn spawn_worker() -> mpsc::Sender<Job> {
+    let (tx, rx) = mpsc::channel::<Job>();
+    let spawned = thread::Builder::new()
+        .name("alert-actions".to_string())
+        .spawn(move || {
+            // Ends when the dispatcher is dropped.
+            for job in rx {
+                let notifier = job.notifier.as_deref();
+                match run_action(&job.action, &job.event, notifier) {
+                    Ok(attempts) => log::info!(
+                        "Ran {} for alert `{}` ({} attempt{})",
+                        job.action.describe(),
+                        job.event.rule,
+                        attempts,
+                        if attempts == 1 { "" } else { "s" }
+                    ),
+                    Err(e) => log::warn!(
+                        "{} for alert `{}` failed: {}",
+                        job.action.describe(),
+                        job.event.rule,
+                        e
+                    ),
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        log::error!("Cannot start the alert action thread: {}", e);
+    }
+    tx
+}
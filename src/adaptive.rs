@@ -0,0 +1,292 @@
+use crate::config::{AdaptiveConfig, Subsystem};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Root of the Linux sysfs power-supply class. Other platforms don't
+/// have it; `read_power_source` then reports `Unknown`.
+pub const POWER_SUPPLY_ROOT: &str = "/sys/class/power_supply";
+
+/// How often `AdaptiveScheduler::power_source` re-reads sysfs. Power
+/// state changes rarely and the scheduler is consulted every tick.
+pub const POWER_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How far global CPU must drop below the load threshold before the
+/// scheduler stops backing off, so usage hovering around the threshold
+/// doesn't flip the cadence every tick.
+pub const LOAD_HYSTERESIS_PERCENT: f32 = 10.0;
+
+/// Subsystems still collected in `OverheadMode::Minimal` — enough for
+/// the tray icon and alert rules.
+pub const MINIMAL_SUBSYSTEMS: [Subsystem; 2] = [Subsystem::Cpu, Subsystem::Memory];
+
+/// Weight of the newest sample in the `RefreshCost` moving averages.
+const COST_EWMA_ALPHA: f64 = 0.2;
+
+/// Where the machine is drawing power from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    /// No power-supply information (desktops without a battery, or a
+    /// platform without sysfs). Treated like AC.
+    #[default]
+    Unknown,
+}
+
+/// Read the current power source from a sysfs-style `root` (normally
+/// `POWER_SUPPLY_ROOT`). An online mains adapter wins; otherwise a
+/// discharging battery, or a battery next to an offline adapter, means
+/// battery power.
+pub fn read_power_source(root: &Path) -> PowerSource {
+    let Ok(entries) = fs::read_dir(root) else {
+        return PowerSource::Unknown;
+    };
+    let read = |dir: &Path, rel: &str| {
+        fs::read_to_string(dir.join(rel))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut mains_offline = false;
+    let mut has_battery = false;
+    let mut discharging = false;
+    for entry in entries.flatten() {
+        let dir = entry.path();
+        match read(&dir, "type").as_str() {
+            "Mains" | "USB" => {
+                if read(&dir, "online") == "1" {
+                    return PowerSource::Ac;
+                }
+                mains_offline = true;
+            }
+            "Battery" => {
+                has_battery = true;
+                discharging |= read(&dir, "status") == "Discharging";
+            }
+            _ => {}
+        }
+    }
+    if discharging || (has_battery && mains_offline) {
+        PowerSource::Battery
+    } else if has_battery {
+        PowerSource::Ac
+    } else {
+        PowerSource::Unknown
+    }
+}
+
+/// Which collection set the monitor is running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverheadMode {
+    /// Every subsystem on its configured cadence.
+    #[default]
+    Full,
+    /// Only `MINIMAL_SUBSYSTEMS`; used while no window is visible.
+    Minimal,
+}
+
+/// Signals the scheduler bases its decision on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveInputs {
+    /// Whether any dashboard window (main, detached or tray popup) is
+    /// currently visible.
+    pub any_view_visible: bool,
+    pub power: PowerSource,
+    /// Latest global CPU usage in percent.
+    pub cpu_usage: f32,
+}
+
+/// Outcome of one scheduling decision, applied with
+/// `SystemMonitor::set_adaptive`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdaptiveDecision {
+    pub mode: OverheadMode,
+    /// Every subsystem interval is multiplied by this. Always >= 1.
+    pub interval_multiplier: u32,
+    pub on_battery: bool,
+    pub under_load: bool,
+}
+
+impl Default for AdaptiveDecision {
+    fn default() -> Self {
+        Self {
+            mode: OverheadMode::Full,
+            interval_multiplier: 1,
+            on_battery: false,
+            under_load: false,
+        }
+    }
+}
+
+impl AdaptiveDecision {
+    /// Whether `subsystem` may be refreshed in this mode.
+    pub fn allows(&self, subsystem: Subsystem) -> bool {
+        match self.mode {
+            OverheadMode::Full => true,
+            OverheadMode::Minimal => MINIMAL_SUBSYSTEMS.contains(&subsystem),
+        }
+    }
+
+    /// Scale a configured interval by the back-off multiplier.
+    pub fn scale(&self, interval: Duration) -> Duration {
+        interval.saturating_mul(self.interval_multiplier.max(1))
+    }
+}
+
+/// Decides how much collection the monitor should do, based on window
+/// visibility, power source and system load.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::adaptive::{AdaptiveInputs, AdaptiveScheduler, OverheadMode, PowerSource};
+/// use rust_dashboard_lib::config::AdaptiveConfig;
+///
+/// let mut scheduler = AdaptiveScheduler::new(AdaptiveConfig::default());
+/// let decision = scheduler.decide(AdaptiveInputs {
+///     any_view_visible: false,
+///     power: PowerSource::Ac,
+///     cpu_usage: 10.0,
+/// });
+/// assert_eq!(decision.mode, OverheadMode::Minimal);
+/// ```
+pub struct AdaptiveScheduler {
+    pub config: AdaptiveConfig,
+    power_root: PathBuf,
+    power: PowerSource,
+    last_power_check: Option<Instant>,
+    under_load: bool,
+}
+
+impl AdaptiveScheduler {
+    pub fn new(config: AdaptiveConfig) -> Self {
+        Self::with_power_root(config, POWER_SUPPLY_ROOT)
+    }
+
+    /// Read power state from `root` instead of `POWER_SUPPLY_ROOT`.
+    /// Exposed so tests can point it at fixture directories.
+    pub fn with_power_root(config: AdaptiveConfig, root: impl Into<PathBuf>) -> Self {
+        Self {
+            config,
+            power_root: root.into(),
+            power: PowerSource::Unknown,
+            last_power_check: None,
+            under_load: false,
+        }
+    }
+
+    pub fn set_config(&mut self, config: AdaptiveConfig) {
+        self.config = config;
+    }
+
+    /// Current power source, re-read from sysfs at most every
+    /// `POWER_POLL_INTERVAL`.
+    pub fn power_source(&mut self) -> PowerSource {
+        let stale = match self.last_power_check {
+            Some(t) => t.elapsed() >= POWER_POLL_INTERVAL,
+            None => true,
+        };
+        if stale {
+            self.power = read_power_source(&self.power_root);
+            self.last_power_check = Some(Instant::now());
+        }
+        self.power
+    }
+
+    /// Decide the collection mode and back-off for the next tick.
+    pub fn decide(&mut self, inputs: AdaptiveInputs) -> AdaptiveDecision {
+        if !self.config.enabled {
+            self.under_load = false;
+            return AdaptiveDecision::default();
+        }
+
+        let threshold = self.config.load_threshold_percent;
+        self.under_load = if self.under_load {
+            inputs.cpu_usage >= threshold - LOAD_HYSTERESIS_PERCENT
+        } else {
+            inputs.cpu_usage >= threshold
+        };
+        let on_battery = inputs.power == PowerSource::Battery;
+
+        let mut multiplier = 1u32;
+        if on_battery {
+            multiplier = multiplier.saturating_mul(self.config.battery_multiplier.max(1));
+        }
+        if self.under_load {
+            multiplier = multiplier.saturating_mul(self.config.load_multiplier.max(1));
+        }
+
+        let mode = if !inputs.any_view_visible && self.config.minimal_when_hidden {
+            OverheadMode::Minimal
+        } else {
+            OverheadMode::Full
+        };
+
+        AdaptiveDecision {
+            mode,
+            interval_multiplier: multiplier,
+            on_battery,
+            under_load: self.under_load,
+        }
+    }
+}
+
+/// What the monitor's own refreshes cost, so the dashboard can show
+/// it's cheap. Times are wall-clock, measured around each sysinfo call;
+/// averages are exponentially weighted so mode changes show up within a
+/// few cycles.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RefreshCost {
+    /// Refresh cycles that did any work.
+    pub cycles: u64,
+    pub last_cycle_ms: f64,
+    pub avg_cycle_ms: f64,
+    /// Average time per subsystem refresh, for subsystems refreshed at
+    /// least once.
+    pub subsystem_ms: HashMap<Subsystem, f64>,
+    /// Share of wall-clock time spent refreshing, in percent of one core.
+    pub busy_percent: f64,
+    #[serde(skip)]
+    last_cycle_start: Option<Instant>,
+}
+
+fn ewma(prev: f64, sample: f64, first: bool) -> f64 {
+    if first {
+        sample
+    } else {
+        prev + COST_EWMA_ALPHA * (sample - prev)
+    }
+}
+
+impl RefreshCost {
+    /// Record how long one subsystem refresh took.
+    pub fn record_subsystem(&mut self, subsystem: Subsystem, took: Duration) {
+        let ms = took.as_secs_f64() * 1000.0;
+        let first = !self.subsystem_ms.contains_key(&subsystem);
+        let avg = self.subsystem_ms.entry(subsystem).or_insert(0.0);
+        *avg = ewma(*avg, ms, first);
+    }
+
+    /// Record a completed cycle that started at `started` and took
+    /// `took`. Busy share is measured against the time since the
+    /// previous cycle started.
+    pub fn record_cycle(&mut self, started: Instant, took: Duration) {
+        let ms = took.as_secs_f64() * 1000.0;
+        let first = self.cycles == 0;
+        self.cycles += 1;
+        self.last_cycle_ms = ms;
+        self.avg_cycle_ms = ewma(self.avg_cycle_ms, ms, first);
+        if let Some(prev) = self.last_cycle_start {
+            let period = started.duration_since(prev).as_secs_f64();
+            if period > 0.0 {
+                let busy = (took.as_secs_f64() / period * 100.0).min(100.0);
+                self.busy_percent = ewma(self.busy_percent, busy, self.cycles == 2);
+            }
+        }
+        self.last_cycle_start = Some(started);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::actions::{ActionKind, AlertAction};
+use crate::error::DashboardError;
+use crate::filter::{Filter, Scope};
+use crate::snapshot::DiskInfo;
+use crate::system::SystemMonitor;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// A named condition checked after every refresh. The condition is a
+/// `filter::Filter` expression; the rule matches while any process
+/// group satisfies it, or for a condition on disk fields, any mount. A
+/// condition with only system-wide terms, like `anomaly(cpu_usage)`,
+/// matches as "system".
+///
+/// In `config.toml`:
+///
+/// ```toml
+/// [[alerts]]
+/// name = "busy postgres"
+/// condition = 'user == "postgres" && cpu > 80'
+/// for_seconds = 60
+///
+/// [[alerts]]
+/// name = "disk filling up"
+/// condition = "full_in < 3d"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub condition: String,
+    /// How long the condition has to hold before the rule fires. Zero
+    /// fires on the first matching refresh.
+    #[serde(default)]
+    pub for_seconds: u64,
+    /// What to do when the rule fires or resolves; see
+    /// `actions::AlertAction`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<AlertAction>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertState {
+    Firing,
+    Resolved,
+}
+
+/// A rule starting or stopping to fire.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub rule: String,
+    pub state: AlertState,
+    /// Names of the groups (or mount points) matching when the rule
+    /// fired; empty when resolved.
+    pub matches: Vec<String>,
+    pub message: String,
+}
+
+/// Check `rules` that came from somewhere less trusted than
+/// `config.toml`, like the desktop app's webview. Such a source may change
+/// conditions and notify or webhook actions, but each command action has
+/// to be one `configured` (the rules loaded from `config.toml`) already
+/// gives the rule of the same name, so it can't choose a program to run.
+/// Returns `DashboardError::ConfigError` naming the first rule that breaks
+/// this.
+pub fn check_configured_commands(
+    rules: &[AlertRule],
+    configured: &[AlertRule],
+) -> Result<(), DashboardError> {
+    for rule in rules {
+        let allowed = configured.iter().find(|c| c.name == rule.name);
+        for action in &rule.actions {
+            if !matches!(action.kind, ActionKind::Command { .. }) {
+                continue;
+            }
+            if !allowed.is_some_and(|c| c.actions.iter().any(|a| a.kind == action.kind)) {
+                return Err(DashboardError::ConfigError(format!(
+                    "alert rule `{}`: command actions can only be set in config.toml",
+                    rule.name
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+struct ActiveRule {
+    rule: AlertRule,
+    filter: Filter,
+    matching_since: Option<Instant>,
+    firing: bool,
+}
+
+/// Evaluates alert rules against a monitor and reports transitions, so
+/// a rule that keeps matching fires once rather than on every refresh.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::alerts::{AlertEngine, AlertRule, AlertState};
+/// use rust_dashboard_lib::system::SystemMonitor;
+/// use std::time::Instant;
+///
+/// let mut engine = AlertEngine::new(&[AlertRule {
+///     name: "me".to_string(),
+///     condition: format!("pid == {}", std::process::id()),
+///     for_seconds: 0,
+///     actions: Vec::new(),
+/// }])
+/// .unwrap();
+/// let monitor = SystemMonitor::new();
+/// let events = engine.evaluate(&monitor, Instant::now());
+/// assert_eq!(events[0].state, AlertState::Firing);
+/// ```
+#[derive(Default)]
+pub struct AlertEngine {
+    rules: Vec<ActiveRule>,
+}
+
+impl AlertEngine {
+    /// Compile `rules`. Returns `DashboardError::ConfigError` naming the
+    /// first rule whose condition doesn't parse or whose actions are
+    /// invalid.
+    pub fn new(rules: &[AlertRule]) -> Result<Self, DashboardError> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let invalid = |e: String| {
+                    DashboardError::ConfigError(format!("alert rule `{}`: {}", rule.name, e))
+                };
+                let filter = Filter::parse(&rule.condition).map_err(|e| invalid(e.to_string()))?;
+                for action in &rule.actions {
+                    action.validate().map_err(invalid)?;
+                }
+                Ok(ActiveRule {
+                    rule: rule.clone(),
+                    filter,
+                    matching_since: None,
+                    firing: false,
+                })
+            })
+            .collect::<Result<_, DashboardError>>()?;
+        Ok(Self { rules })
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &AlertRule> {
+        self.rules.iter().map(|r| &r.rule)
+    }
+
+    pub fn rule(&self, name: &str) -> Option<&AlertRule> {
+        self.rules().find(|r| r.name == name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Check every rule against `monitor`'s current process list and
+    /// mounts and return the rules that started or stopped firing.
+    pub fn evaluate(&mut self, monitor: &SystemMonitor, now: Instant) -> Vec<AlertEvent> {
+        let processes = monitor.combined_process_list();
+        let disks = DiskInfo::list(monitor);
+        let mut events = Vec::new();
+        for active in &mut self.rules {
+            let matches: Vec<String> = match active.filter.scope() {
+                Scope::Processes => processes
+                    .iter()
+                    .filter(|p| active.filter.matches(p, monitor))
+                    .map(|p| p.name.clone())
+                    .collect(),
+                Scope::Disks => disks
+                    .iter()
+                    .filter(|d| active.filter.matches_disk(d, monitor))
+                    .map(|d| d.mount_point.clone())
+                    .collect(),
+                Scope::System if active.filter.matches_system(monitor) => {
+                    vec!["system".to_string()]
+                }
+                Scope::System => Vec::new(),
+            };
+
+            if matches.is_empty() {
+                active.matching_since = None;
+                if active.firing {
+                    active.firing = false;
+                    events.push(AlertEvent {
+                        rule: active.rule.name.clone(),
+                        state: AlertState::Resolved,
+                        matches,
+                        message: format!("{} resolved", active.rule.name),
+                    });
+                }
+                continue;
+            }
+
+            let since = *active.matching_since.get_or_insert(now);
+            let held = now.saturating_duration_since(since);
+            if !active.firing && held >= Duration::from_secs(active.rule.for_seconds) {
+                active.firing = true;
+                events.push(AlertEvent {
+                    message: format!(
+                        "{}: {} matches `{}`",
+                        active.rule.name,
+                        matches.join(", "),
+                        active.filter
+                    ),
+                    rule: active.rule.name.clone(),
+                    state: AlertState::Firing,
+                    matches,
+                });
+            }
+        }
+        events
+    }
+}
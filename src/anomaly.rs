@@ -0,0 +1,219 @@
+//! Anomaly detection on metric series with an exponentially weighted
+//! baseline: each point is scored by how many (weighted) standard
+//! deviations it sits from the moving mean, so "unusual for this
+//! machine right now" doesn't need a hand-picked threshold. There is
+//! no seasonal model; the baseline simply follows recent history.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// System-wide series tracked by `SystemMonitor::anomalies`, usable as
+/// `anomaly(<name>)` in filters and alert rules.
+pub const SYSTEM_SERIES: [&str; 6] = [
+    "cpu_usage",
+    "memory_used",
+    "swap_used",
+    "load_1",
+    "net_rx",
+    "net_tx",
+];
+
+/// Sensitivity of the detector.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnomalyConfig {
+    pub enabled: bool,
+    /// Weight of each new point in the moving mean and variance (0–1).
+    /// Smaller values give a longer memory; 0.05 is roughly the last 40
+    /// points.
+    pub alpha: f64,
+    /// Distance from the baseline, in standard deviations, at which a
+    /// point is anomalous.
+    pub z_threshold: f64,
+    /// Points used to establish the baseline before anything is flagged.
+    pub warmup_samples: u32,
+    /// Lower bound on the standard deviation as a fraction of the mean,
+    /// so a nearly constant series doesn't flag every tiny wobble.
+    pub min_relative_spread: f64,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            alpha: 0.05,
+            z_threshold: 4.0,
+            warmup_samples: 30,
+            min_relative_spread: 0.05,
+        }
+    }
+}
+
+/// EWMA baseline and z-score for one series.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::anomaly::{AnomalyConfig, AnomalyDetector};
+///
+/// let mut detector = AnomalyDetector::new(AnomalyConfig::default());
+/// for i in 0..60 {
+///     detector.observe(10.0 + (i % 3) as f64);
+/// }
+/// assert!(!detector.is_anomalous());
+/// detector.observe(95.0);
+/// assert!(detector.is_anomalous());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    mean: f64,
+    variance: f64,
+    count: u32,
+    last_z: Option<f64>,
+}
+
+impl AnomalyDetector {
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Score `value` against the baseline, then fold it in. Returns the
+    /// z-score once the baseline has `warmup_samples` points.
+    ///
+    /// Anomalous points are clamped to the threshold before updating
+    /// the baseline: a lasting level shift is still absorbed, just
+    /// slowly, while a one-off spike barely moves it.
+    pub fn observe(&mut self, value: f64) -> Option<f64> {
+        if !value.is_finite() {
+            return self.last_z;
+        }
+        if self.count == 0 {
+            self.mean = value;
+            self.count = 1;
+            return None;
+        }
+        let spread = self
+            .variance
+            .sqrt()
+            .max(self.mean.abs() * self.config.min_relative_spread)
+            .max(f64::EPSILON);
+        let z = (value - self.mean) / spread;
+        self.last_z = (self.count >= self.config.warmup_samples).then_some(z);
+
+        let limit = self.config.z_threshold * spread;
+        let clamped = if self.last_z.is_some() {
+            value.clamp(self.mean - limit, self.mean + limit)
+        } else {
+            value
+        };
+        let diff = clamped - self.mean;
+        let step = self.config.alpha * diff;
+        self.mean += step;
+        self.variance = (1.0 - self.config.alpha) * (self.variance + diff * step);
+        self.count = self.count.saturating_add(1);
+        self.last_z
+    }
+
+    /// Z-score of the last observed point, once warmed up.
+    pub fn z_score(&self) -> Option<f64> {
+        self.last_z
+    }
+
+    /// Whether the last observed point was anomalous.
+    pub fn is_anomalous(&self) -> bool {
+        self.last_z
+            .is_some_and(|z| z.abs() >= self.config.z_threshold)
+    }
+
+    /// The moving mean the next point is compared with.
+    pub fn baseline(&self) -> f64 {
+        self.mean
+    }
+}
+
+/// Run a fresh detector over `values` and return the indices of the
+/// anomalous points. Works on any history series.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::anomaly::{mark_anomalies, AnomalyConfig};
+///
+/// let mut series: Vec<f64> = (0..100).map(|i| 50.0 + (i % 5) as f64).collect();
+/// series[70] = 400.0;
+/// assert_eq!(mark_anomalies(&series, &AnomalyConfig::default()), vec![70]);
+/// ```
+pub fn mark_anomalies(values: &[f64], config: &AnomalyConfig) -> Vec<usize> {
+    let mut detector = AnomalyDetector::new(*config);
+    values
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &v)| {
+            detector.observe(v);
+            detector.is_anomalous().then_some(i)
+        })
+        .collect()
+}
+
+/// One detector per named series, fed as the monitor refreshes.
+#[derive(Debug, Default)]
+pub struct AnomalyTracker {
+    config: AnomalyConfig,
+    detectors: HashMap<String, AnomalyDetector>,
+}
+
+impl AnomalyTracker {
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            detectors: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &AnomalyConfig {
+        &self.config
+    }
+
+    /// Change sensitivity. Baselines are rebuilt from scratch.
+    pub fn set_config(&mut self, config: AnomalyConfig) {
+        self.config = config;
+        self.detectors.clear();
+    }
+
+    /// Feed the latest point of `series`.
+    pub fn observe(&mut self, series: &str, value: f64) {
+        if !self.config.enabled {
+            return;
+        }
+        let config = self.config;
+        self.detectors
+            .entry(series.to_string())
+            .or_insert_with(|| AnomalyDetector::new(config))
+            .observe(value);
+    }
+
+    pub fn detector(&self, series: &str) -> Option<&AnomalyDetector> {
+        self.detectors.get(series)
+    }
+
+    /// Whether the latest point of `series` was anomalous.
+    pub fn is_anomalous(&self, series: &str) -> bool {
+        self.detectors
+            .get(series)
+            .is_some_and(AnomalyDetector::is_anomalous)
+    }
+
+    /// Series whose latest point was anomalous, sorted.
+    pub fn anomalous(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .detectors
+            .iter()
+            .filter(|(_, d)| d.is_anomalous())
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+}
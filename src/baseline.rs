@@ -0,0 +1,775 @@
+//! Named baseline snapshots and diffs against them, for "slower than
+//! yesterday" questions: which processes are new or gone, which use the
+//! most more (or less) CPU and memory than they did, and how disk usage
+//! and network throughput moved.
+//!
+//! Baselines are stored one JSON file per name in a `BaselineStore`.
+//! Diffs render in the export formats (see `export::ExportFormat`).
+
+use crate::error::DashboardError;
+use crate::export::{
+    format_bytes, format_utc, render_html, render_markdown, text_cell, ExportFormat, Table,
+};
+use crate::snapshot::{HostInfo, SystemSnapshot};
+use crate::system::{unix_now, CombinedProcess, SystemMonitor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Processes listed per "largest change" table by default.
+pub const DEFAULT_TOP: usize = 10;
+
+/// Longest baseline name accepted.
+const MAX_NAME_LEN: usize = 64;
+
+/// A snapshot saved under a name to compare against later.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub name: String,
+    /// When the snapshot was taken, in seconds since the Unix epoch.
+    pub timestamp: f64,
+    pub host: HostInfo,
+    pub snapshot: SystemSnapshot,
+}
+
+impl Baseline {
+    /// Fails if `name` can't be used as a file name: it must be 1-64
+    /// letters, digits, `-`, `_`, `.` or spaces, not starting with `.`.
+    pub fn new(
+        name: &str,
+        timestamp: f64,
+        host: HostInfo,
+        snapshot: SystemSnapshot,
+    ) -> Result<Self, DashboardError> {
+        check_name(name)?;
+        Ok(Self {
+            name: name.to_string(),
+            timestamp,
+            host,
+            snapshot,
+        })
+    }
+
+    /// The monitor's current state, with the full process list.
+    pub fn capture(name: &str, monitor: &SystemMonitor) -> Result<Self, DashboardError> {
+        Self::new(
+            name,
+            unix_now(),
+            HostInfo::current(monitor),
+            SystemSnapshot::capture(monitor),
+        )
+    }
+
+    pub fn info(&self) -> BaselineInfo {
+        BaselineInfo {
+            name: self.name.clone(),
+            timestamp: self.timestamp,
+            hostname: self.host.hostname.clone(),
+        }
+    }
+}
+
+fn check_name(name: &str) -> Result<(), DashboardError> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ' '));
+    if valid {
+        Ok(())
+    } else {
+        Err(DashboardError::ConfigError(format!(
+            "invalid baseline name `{}`: use up to {} letters, digits, '-', '_', '.' or spaces",
+            name, MAX_NAME_LEN
+        )))
+    }
+}
+
+/// What `BaselineStore::list` reports per baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineInfo {
+    pub name: String,
+    pub timestamp: f64,
+    pub hostname: String,
+}
+
+/// A directory of baselines, stored as `<name>.json`.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::baseline::{Baseline, BaselineStore, SnapshotDiff, DEFAULT_TOP};
+/// use rust_dashboard_lib::snapshot::SystemSnapshot;
+/// use rust_dashboard_lib::system::{unix_now, SystemMonitor};
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let store = BaselineStore::new(dir.path());
+/// let monitor = SystemMonitor::new();
+/// store.save(&Baseline::capture("before upgrade", &monitor).unwrap()).unwrap();
+///
+/// let baseline = store.load("before upgrade").unwrap();
+/// let now = SystemSnapshot::capture(&monitor);
+/// let diff = SnapshotDiff::compare(&baseline, &now, unix_now(), DEFAULT_TOP);
+/// assert_eq!(diff.baseline, "before upgrade");
+/// ```
+#[derive(Debug, Clone)]
+pub struct BaselineStore {
+    directory: PathBuf,
+}
+
+impl BaselineStore {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    /// `baselines/` in the platform's local data directory.
+    pub fn default_directory() -> Option<PathBuf> {
+        Some(
+            dirs::data_local_dir()?
+                .join("rust-dashboard")
+                .join("baselines"),
+        )
+    }
+
+    /// A store in `default_directory()`.
+    pub fn open_default() -> Result<Self, DashboardError> {
+        Self::default_directory().map(Self::new).ok_or_else(|| {
+            DashboardError::ConfigError("cannot determine a baseline directory".to_string())
+        })
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf, DashboardError> {
+        check_name(name)?;
+        Ok(self.directory.join(format!("{}.json", name)))
+    }
+
+    /// Save `baseline`, replacing any baseline of the same name.
+    pub fn save(&self, baseline: &Baseline) -> Result<PathBuf, DashboardError> {
+        let path = self.path(&baseline.name)?;
+        let failed = |e: &dyn std::fmt::Display| {
+            DashboardError::ConfigError(format!("{}: {}", path.display(), e))
+        };
+        std::fs::create_dir_all(&self.directory).map_err(|e| failed(&e))?;
+        let json = serde_json::to_vec(baseline).map_err(|e| failed(&e))?;
+        // Write aside and rename, so an interrupted save keeps the old one.
+        let partial = path.with_extension("json.partial");
+        std::fs::write(&partial, json).map_err(|e| failed(&e))?;
+        std::fs::rename(&partial, &path).map_err(|e| failed(&e))?;
+        Ok(path)
+    }
+
+    pub fn load(&self, name: &str) -> Result<Baseline, DashboardError> {
+        let path = self.path(name)?;
+        let text = std::fs::read_to_string(&path).map_err(|e| {
+            let message = match e.kind() {
+                std::io::ErrorKind::NotFound => format!("no baseline named `{}`", name),
+                _ => format!("{}: {}", path.display(), e),
+            };
+            DashboardError::ConfigError(message)
+        })?;
+        serde_json::from_str(&text)
+            .map_err(|e| DashboardError::ConfigError(format!("{}: {}", path.display(), e)))
+    }
+
+    /// Saved baselines, oldest first. Files that don't parse are skipped.
+    pub fn list(&self) -> Result<Vec<BaselineInfo>, DashboardError> {
+        let entries = match std::fs::read_dir(&self.directory) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(DashboardError::ConfigError(format!(
+                    "{}: {}",
+                    self.directory.display(),
+                    e
+                )))
+            }
+        };
+        let mut baselines: Vec<BaselineInfo> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "json"))
+            .filter_map(|path| {
+                let text = std::fs::read_to_string(&path).ok()?;
+                match serde_json::from_str::<Baseline>(&text) {
+                    Ok(baseline) => Some(baseline.info()),
+                    Err(e) => {
+                        log::warn!("Skipping unreadable baseline {}: {}", path.display(), e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        baselines.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        Ok(baselines)
+    }
+
+    pub fn delete(&self, name: &str) -> Result<(), DashboardError> {
+        let path = self.path(name)?;
+        std::fs::remove_file(&path).map_err(|e| {
+            let message = match e.kind() {
+                std::io::ErrorKind::NotFound => format!("no baseline named `{}`", name),
+                _ => format!("{}: {}", path.display(), e),
+            };
+            DashboardError::ConfigError(message)
+        })
+    }
+}
+
+/// A value in the baseline and now.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Change {
+    pub before: f64,
+    pub after: f64,
+    /// `after - before`.
+    pub delta: f64,
+}
+
+impl Change {
+    pub fn new(before: f64, after: f64) -> Self {
+        Self {
+            before,
+            after,
+            delta: after - before,
+        }
+    }
+}
+
+/// Whether something is in the baseline, now, or both. Values missing
+/// on one side count as zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Presence {
+    Both,
+    New,
+    Gone,
+}
+
+impl Presence {
+    fn of<T>(before: Option<T>, after: Option<T>) -> Self {
+        match (before, after) {
+            (Some(_), None) => Self::Gone,
+            (None, Some(_)) => Self::New,
+            _ => Self::Both,
+        }
+    }
+}
+
+/// A system-wide metric, e.g. `cpu_usage` in `%`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SystemChange {
+    pub metric: String,
+    pub unit: String,
+    pub change: Change,
+}
+
+/// A process group, matched by name like the process table groups them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessChange {
+    pub name: String,
+    pub presence: Presence,
+    /// CPU usage in percent.
+    pub cpu: Change,
+    /// Memory in bytes.
+    pub memory: Change,
+    /// Running instances.
+    pub instances: Change,
+}
+
+/// A disk, matched by mount point.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskChange {
+    pub mount_point: String,
+    pub presence: Presence,
+    /// Bytes used.
+    pub used: Change,
+    pub total: Change,
+}
+
+/// A network interface's throughput, in bytes per second.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkChange {
+    pub interface: String,
+    pub presence: Presence,
+    pub rx_rate: Change,
+    pub tx_rate: Change,
+}
+
+/// How a snapshot differs from a baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub baseline: String,
+    pub baseline_timestamp: f64,
+    /// When the compared snapshot was taken.
+    pub timestamp: f64,
+    pub system: Vec<SystemChange>,
+    /// Process groups that weren't running in the baseline, by memory.
+    pub new_processes: Vec<ProcessChange>,
+    /// Process groups from the baseline no longer running, by memory.
+    pub gone_processes: Vec<ProcessChange>,
+    /// Groups in both whose CPU usage changed most, either way.
+    pub cpu_changes: Vec<ProcessChange>,
+    /// Groups in both whose memory changed most, either way.
+    pub memory_changes: Vec<ProcessChange>,
+    pub disks: Vec<DiskChange>,
+    pub networks: Vec<NetworkChange>,
+}
+
+impl SnapshotDiff {
+    /// Compare `snapshot`, taken at `timestamp`, against `baseline`,
+    /// listing up to `top` processes per "largest change" table.
+    pub fn compare(
+        baseline: &Baseline,
+        snapshot: &SystemSnapshot,
+        timestamp: f64,
+        top: usize,
+    ) -> Self {
+        let before = &baseline.snapshot;
+        let system = [
+            (
+                "cpu_usage",
+                "%",
+                before.cpu_usage as f64,
+                snapshot.cpu_usage as f64,
+            ),
+            (
+                "memory_used",
+                "bytes",
+                before.memory.used as f64,
+                snapshot.memory.used as f64,
+            ),
+            (
+                "swap_used",
+                "bytes",
+                before.memory.swap_used as f64,
+                snapshot.memory.swap_used as f64,
+            ),
+            ("load_1", "", before.load_average.0, snapshot.load_average.0),
+            ("load_5", "", before.load_average.1, snapshot.load_average.1),
+            (
+                "load_15",
+                "",
+                before.load_average.2,
+                snapshot.load_average.2,
+            ),
+            (
+                "process_groups",
+                "groups",
+                before.processes.len() as f64,
+                snapshot.processes.len() as f64,
+            ),
+        ]
+        .into_iter()
+        .map(|(metric, unit, before, after)| SystemChange {
+            metric: metric.to_string(),
+            unit: unit.to_string(),
+            change: Change::new(before, after),
+        })
+        .collect();
+
+        let processes = matched(&before.processes, &snapshot.processes, |p| &p.name);
+        let mut new_processes = Vec::new();
+        let mut gone_processes = Vec::new();
+        let mut common = Vec::new();
+        for (name, before, after) in processes {
+            let value = |p: Option<&CombinedProcess>, f: fn(&CombinedProcess) -> f64| {
+                p.map(f).unwrap_or(0.0)
+            };
+            let change = ProcessChange {
+                name: name.to_string(),
+                presence: Presence::of(before, after),
+                cpu: Change::new(
+                    value(before, |p| p.cpu_usage as f64),
+                    value(after, |p| p.cpu_usage as f64),
+                ),
+                memory: Change::new(
+                    value(before, |p| p.memory_usage as f64),
+                    value(after, |p| p.memory_usage as f64),
+                ),
+                instances: Change::new(
+                    value(before, |p| p.pids.len() as f64),
+                    value(after, |p| p.pids.len() as f64),
+                ),
+            };
+            match change.presence {
+                Presence::New => new_processes.push(change),
+                Presence::Gone => gone_processes.push(change),
+                Presence::Both => common.push(change),
+            }
+        }
+        new_processes.sort_by(|a, b| b.memory.after.total_cmp(&a.memory.after));
+        gone_processes.sort_by(|a, b| b.memory.before.total_cmp(&a.memory.before));
+        let largest = |key: fn(&ProcessChange) -> f64| {
+            let mut changed: Vec<ProcessChange> =
+                common.iter().filter(|p| key(p) != 0.0).cloned().collect();
+            changed.sort_by(|a, b| key(b).abs().total_cmp(&key(a).abs()));
+            changed.truncate(top);
+            changed
+        };
+        let cpu_changes = largest(|p| p.cpu.delta);
+        let memory_changes = largest(|p| p.memory.delta);
+
+        let disks = matched(&before.disks, &snapshot.disks, |d| &d.mount_point)
+            .into_iter()
+            .map(|(mount_point, before, after)| DiskChange {
+                mount_point: mount_point.to_string(),
+                presence: Presence::of(before, after),
+                used: Change::new(
+                    before.map_or(0.0, |d| d.used as f64),
+                    after.map_or(0.0, |d| d.used as f64),
+                ),
+                total: Change::new(
+                    before.map_or(0.0, |d| d.total as f64),
+                    after.map_or(0.0, |d| d.total as f64),
+                ),
+            })
+            .collect();
+        let networks = matched(&before.networks, &snapshot.networks, |n| &n.interface)
+            .into_iter()
+            .map(|(interface, before, after)| NetworkChange {
+                interface: interface.to_string(),
+                presence: Presence::of(before, after),
+                rx_rate: Change::new(
+                    before.map_or(0.0, |n| n.rx_rate),
+                    after.map_or(0.0, |n| n.rx_rate),
+                ),
+                tx_rate: Change::new(
+                    before.map_or(0.0, |n| n.tx_rate),
+                    after.map_or(0.0, |n| n.tx_rate),
+                ),
+            })
+            .collect();
+
+        Self {
+            baseline: baseline.name.clone(),
+            baseline_timestamp: baseline.timestamp,
+            timestamp,
+            system,
+            new_processes,
+            gone_processes,
+            cpu_changes,
+            memory_changes,
+            disks,
+            networks,
+        }
+    }
+
+    /// Format the diff as a string.
+    pub fn export(&self, format: ExportFormat) -> Result<String, DashboardError> {
+        let mut out = Vec::new();
+        self.write(format, &mut out)?;
+        String::from_utf8(out).map_err(failed)
+    }
+
+    /// Format the diff into `out`. JSON is this struct; CSV and NDJSON
+    /// have one row per compared value; Markdown/HTML are a report.
+    pub fn write(&self, format: ExportFormat, out: &mut impl Write) -> Result<(), DashboardError> {
+        match format {
+            ExportFormat::Json => {
+                serde_json::to_writer_pretty(&mut *out, self).map_err(failed)?;
+                writeln!(out).map_err(failed)
+            }
+            ExportFormat::Csv => self.write_csv(out),
+            ExportFormat::Ndjson => self.write_ndjson(out),
+            ExportFormat::Markdown => out
+                .write_all(render_markdown(&self.report_tables()).as_bytes())
+                .map_err(failed),
+            ExportFormat::Html => out
+                .write_all(render_html(&self.report_tables()).as_bytes())
+                .map_err(failed),
+        }
+    }
+
+    /// Every compared value as `(section, name, presence, metric, change)`.
+    fn rows(&self) -> Vec<(&'static str, &str, Option<Presence>, &str, Change)> {
+        let mut rows = Vec::new();
+        for s in &self.system {
+            rows.push(("system", s.metric.as_str(), None, s.unit.as_str(), s.change));
+        }
+        for (section, processes) in [
+            ("new_process", &self.new_processes),
+            ("gone_process", &self.gone_processes),
+            ("cpu_change", &self.cpu_changes),
+            ("memory_change", &self.memory_changes),
+        ] {
+            for p in processes {
+                let name = p.name.as_str();
+                rows.push((section, name, Some(p.presence), "cpu %", p.cpu));
+                rows.push((section, name, Some(p.presence), "memory bytes", p.memory));
+                rows.push((section, name, Some(p.presence), "instances", p.instances));
+            }
+        }
+        for d in &self.disks {
+            let name = d.mount_point.as_str();
+            rows.push(("disk", name, Some(d.presence), "used bytes", d.used));
+            rows.push(("disk", name, Some(d.presence), "total bytes", d.total));
+        }
+        for n in &self.networks {
+            let name = n.interface.as_str();
+            rows.push(("network", name, Some(n.presence), "rx bytes/s", n.rx_rate));
+            rows.push(("network", name, Some(n.presence), "tx bytes/s", n.tx_rate));
+        }
+        rows
+    }
+
+    fn write_csv(&self, out: &mut impl Write) -> Result<(), DashboardError> {
+        let mut writer = csv::Writer::from_writer(out);
+        writer
+            .write_record([
+                "Section", "Name", "Presence", "Metric", "Baseline", "Now", "Change",
+            ])
+            .map_err(failed)?;
+        for (section, name, presence, metric, change) in self.rows() {
+            writer
+                .write_record([
+                    section.to_string(),
+                    text_cell(name),
+                    presence.map(presence_name).unwrap_or_default().to_string(),
+                    metric.to_string(),
+                    change.before.to_string(),
+                    change.after.to_string(),
+                    change.delta.to_string(),
+                ])
+                .map_err(failed)?;
+        }
+        writer.flush().map_err(failed)
+    }
+
+    fn write_ndjson(&self, out: &mut impl Write) -> Result<(), DashboardError> {
+        let header = serde_json::json!({
+            "type": "diff",
+            "baseline": self.baseline,
+            "baseline_timestamp": self.baseline_timestamp,
+            "timestamp": self.timestamp,
+        });
+        serde_json::to_writer(&mut *out, &header).map_err(failed)?;
+        writeln!(out).map_err(failed)?;
+        for (section, name, presence, metric, change) in self.rows() {
+            let line = serde_json::json!({
+                "type": section,
+                "name": name,
+                "presence": presence,
+                "metric": metric,
+                "before": change.before,
+                "after": change.after,
+                "delta": change.delta,
+            });
+            serde_json::to_writer(&mut *out, &line).map_err(failed)?;
+            writeln!(out).map_err(failed)?;
+        }
+        Ok(())
+    }
+
+    fn report_tables(&self) -> (String, Vec<Table>) {
+        let title = format!("Comparison with baseline: {}", self.baseline);
+        let mut tables = vec![Table {
+            title: "Summary".to_string(),
+            headers: vec!["", ""],
+            rows: vec![
+                vec!["Baseline".to_string(), self.baseline.clone()],
+                vec![
+                    "Baseline taken".to_string(),
+                    format_utc(self.baseline_timestamp),
+                ],
+                vec!["Compared".to_string(), format_utc(self.timestamp)],
+            ],
+        }];
+        tables.push(Table {
+            title: "System".to_string(),
+            headers: vec!["Metric", "Baseline", "Now", "Change"],
+            rows: self
+                .system
+                .iter()
+                .map(|s| {
+                    let (before, after, delta) = match s.unit.as_str() {
+                        "bytes" => (
+                            format_bytes(s.change.before as u64),
+                            format_bytes(s.change.after as u64),
+                            signed_bytes(s.change.delta),
+                        ),
+                        "groups" => (
+                            format!("{}", s.change.before),
+                            format!("{}", s.change.after),
+                            format!("{:+}", s.change.delta),
+                        ),
+                        "%" => (
+                            format!("{:.1}%", s.change.before),
+                            format!("{:.1}%", s.change.after),
+                            format!("{:+.1}%", s.change.delta),
+                        ),
+                        _ => (
+                            format!("{:.2}", s.change.before),
+                            format!("{:.2}", s.change.after),
+                            format!("{:+.2}", s.change.delta),
+                        ),
+                    };
+                    vec![s.metric.clone(), before, after, delta]
+                })
+                .collect(),
+        });
+        let present = |p: &ProcessChange, before: bool| {
+            let (cpu, memory, instances) = if before {
+                (p.cpu.before, p.memory.before, p.instances.before)
+            } else {
+                (p.cpu.after, p.memory.after, p.instances.after)
+            };
+            vec![
+                p.name.clone(),
+                format!("{}", instances),
+                format!("{:.1}", cpu),
+                format_bytes(memory as u64),
+            ]
+        };
+        tables.push(Table {
+            title: "New processes".to_string(),
+            headers: vec!["Name", "PIDs", "CPU %", "Memory"],
+            rows: self
+                .new_processes
+                .iter()
+                .map(|p| present(p, false))
+                .collect(),
+        });
+        tables.push(Table {
+            title: "Gone processes".to_string(),
+            headers: vec!["Name", "PIDs", "CPU %", "Memory"],
+            rows: self
+                .gone_processes
+                .iter()
+                .map(|p| present(p, true))
+                .collect(),
+        });
+        tables.push(Table {
+            title: "Largest CPU changes".to_string(),
+            headers: vec!["Name", "Baseline CPU %", "Now CPU %", "Change"],
+            rows: self
+                .cpu_changes
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.name.clone(),
+                        format!("{:.1}", p.cpu.before),
+                        format!("{:.1}", p.cpu.after),
+                        format!("{:+.1}", p.cpu.delta),
+                    ]
+                })
+                .collect(),
+        });
+        tables.push(Table {
+            title: "Largest memory changes".to_string(),
+            headers: vec!["Name", "Baseline", "Now", "Change"],
+            rows: self
+                .memory_changes
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.name.clone(),
+                        format_bytes(p.memory.before as u64),
+                        format_bytes(p.memory.after as u64),
+                        signed_bytes(p.memory.delta),
+                    ]
+                })
+                .collect(),
+        });
+        tables.push(Table {
+            title: "Disks".to_string(),
+            headers: vec!["Mount", "", "Used then", "Used now", "Change", "Total"],
+            rows: self
+                .disks
+                .iter()
+                .map(|d| {
+                    vec![
+                        d.mount_point.clone(),
+                        presence_label(d.presence).to_string(),
+                        format_bytes(d.used.before as u64),
+                        format_bytes(d.used.after as u64),
+                        signed_bytes(d.used.delta),
+                        format_bytes(d.total.after.max(d.total.before) as u64),
+                    ]
+                })
+                .collect(),
+        });
+        tables.push(Table {
+            title: "Networks".to_string(),
+            headers: vec![
+                "Interface",
+                "",
+                "RX/s then",
+                "RX/s now",
+                "TX/s then",
+                "TX/s now",
+            ],
+            rows: self
+                .networks
+                .iter()
+                .map(|n| {
+                    vec![
+                        n.interface.clone(),
+                        presence_label(n.presence).to_string(),
+                        format_bytes(n.rx_rate.before as u64),
+                        format_bytes(n.rx_rate.after as u64),
+                        format_bytes(n.tx_rate.before as u64),
+                        format_bytes(n.tx_rate.after as u64),
+                    ]
+                })
+                .collect(),
+        });
+        (title, tables)
+    }
+}
+
+fn failed(e: impl std::fmt::Display) -> DashboardError {
+    DashboardError::ExportFailed(e.to_string())
+}
+
+/// Pair up items from `before` and `after` by key, in `before`'s order
+/// followed by items only in `after`.
+fn matched<'a, T>(
+    before: &'a [T],
+    after: &'a [T],
+    key: impl Fn(&T) -> &String,
+) -> Vec<(&'a str, Option<&'a T>, Option<&'a T>)> {
+    let now: HashMap<&str, &T> = after.iter().map(|t| (key(t).as_str(), t)).collect();
+    let then: HashMap<&str, &T> = before.iter().map(|t| (key(t).as_str(), t)).collect();
+    let mut pairs: Vec<_> = before
+        .iter()
+        .map(|t| {
+            let name = key(t).as_str();
+            (name, Some(t), now.get(name).copied())
+        })
+        .collect();
+    pairs.extend(
+        after
+            .iter()
+            .filter(|t| !then.contains_key(key(t).as_str()))
+            .map(|t| (key(t).as_str(), None, Some(t))),
+    );
+    pairs
+}
+
+fn presence_name(presence: Presence) -> &'static str {
+    match presence {
+        Presence::Both => "both",
+        Presence::New => "new",
+        Presence::Gone => "gone",
+    }
+}
+
+fn presence_label(presence: Presence) -> &'static str {
+    match presence {
+        Presence::Both => "",
+        Presence::New => "new",
+        Presence::Gone => "gone",
+    }
+}
+
+/// "+1.5 GiB", "-300.0 MiB".
+fn signed_bytes(delta: f64) -> String {
+    let sign = if delta < 0.0 { '-' } else { '+' };
+    format!("{}{}", sign, format_bytes(delta.abs() as u64))
+}
@@ -0,0 +1,517 @@
+//! Command-line access to the dashboard's process view, exports and
+//! baselines.
+//!
+//! ```text
+//! rdash ps [--filter EXPR] [--sort COLUMN] [--asc] [--limit N]
+//! rdash export [--format FORMAT] [--output FILE] [--filter EXPR] [--limit N]
+//!              [--history SECONDS] [--no-system] [--no-processes]
+//!              [--recording FILE]... [--from UNIX_SECONDS] [--to UNIX_SECONDS]
+//! rdash baseline save NAME | list | delete NAME
+//! rdash diff NAME [--recording FILE]... [--at UNIX_SECONDS] [--top N]
+//!                 [--format FORMAT] [--output FILE]
+//! ```
+//!
+//! `--filter` takes a `filter::Filter` expression, e.g.
+//! `rdash ps --filter 'user == "postgres" && cpu > 20'`. `export` writes
+//! JSON, CSV, NDJSON, Markdown or HTML (see `export::Exporter`), taking
+//! the format from `--output`'s extension unless `--format` is given,
+//! and with `--history` samples CPU, memory and load once a second for
+//! that long first.
+//!
+//! With the `columnar` feature, `export --format parquet` or `arrow` (or
+//! an `--output` ending in `.parquet`, `.arrow`, `.feather` or `.ipc`)
+//! writes one row per point instead (see `columnar`): the history and
+//! current snapshot, or every snapshot of the `--recording` files, in
+//! `--from`/`--to` if given.
+//!
+//! `baseline save` stores the current state under a name; `diff`
+//! compares the current state, or the snapshot an NDJSON recording
+//! holds for `--at` (its last one by default), against it (see
+//! `baseline::SnapshotDiff`). Diffs are Markdown unless `--format` or
+//! `--output` says otherwise.
+
+use rust_dashboard_lib::baseline::{Baseline, BaselineStore, SnapshotDiff, DEFAULT_TOP};
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::export::{ExportData, ExportFormat, Exporter, HistorySeries};
+use rust_dashboard_lib::query::{ProcessQuery, SortColumn, SortDirection};
+use rust_dashboard_lib::replay::Replay;
+use rust_dashboard_lib::snapshot::SystemSnapshot;
+use rust_dashboard_lib::system::{unix_now, SystemMonitor};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+const USAGE: &str =
+    "usage: rdash ps [--filter EXPR] [--sort name|cpu|memory|network|pids] [--asc] [--limit N]
+       rdash export [--format json|csv|ndjson|md|html|parquet|arrow] [--output FILE]
+                    [--filter EXPR] [--limit N] [--history SECONDS] [--no-system]
+                    [--no-processes] [--recording FILE]... [--from UNIX_SECONDS]
+                    [--to UNIX_SECONDS]
+       rdash baseline save NAME | list | delete NAME
+       rdash diff NAME [--recording FILE]... [--at UNIX_SECONDS] [--top N]
+                       [--format json|csv|ndjson|md|html] [--output FILE]";
+
+enum Command {
+    Ps(ProcessQuery),
+    Export {
+        exporter: Exporter,
+        query: ProcessQuery,
+        output: Option<PathBuf>,
+        history_seconds: u64,
+        columnar: Option<ColumnarArgs>,
+    },
+    SaveBaseline(String),
+    ListBaselines,
+    DeleteBaseline(String),
+    Diff(DiffArgs),
+}
+
+struct DiffArgs {
+    name: String,
+    /// Compare a recorded snapshot instead of the live system.
+    recordings: Vec<PathBuf>,
+    at: Option<f64>,
+    top: usize,
+    format: ExportFormat,
+    output: Option<PathBuf>,
+}
+
+/// The formats of `columnar::ColumnarFormat`, which needs the
+/// `columnar` feature.
+const COLUMNAR_FORMATS: [&str; 4] = ["parquet", "arrow", "feather", "ipc"];
+
+/// A Parquet or Arrow export.
+#[cfg_attr(not(feature = "columnar"), allow(dead_code))]
+struct ColumnarArgs {
+    format: String,
+    /// Export these instead of the live system.
+    recordings: Vec<PathBuf>,
+    range: Option<(f64, f64)>,
+}
+
+fn parse_sort(value: &str) -> Result<SortColumn, String> {
+    serde_json::from_value(serde_json::Value::String(value.to_lowercase()))
+        .map_err(|_| format!("unknown sort column `{}`", value))
+}
+
+/// The format `--format` names, or else the one `output`'s extension
+/// implies.
+fn output_format(
+    format: Option<String>,
+    output: Option<&PathBuf>,
+) -> Result<Option<ExportFormat>, String> {
+    match format {
+        Some(name) => name
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("unknown format `{}`", name)),
+        None => Ok(output
+            .and_then(|path| path.extension()?.to_str())
+            .and_then(ExportFormat::from_extension)),
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let export = match args.next().as_deref() {
+        Some("ps") => false,
+        Some("export") => true,
+        Some("baseline") => return parse_baseline(args),
+        Some("diff") => return parse_diff(args),
+        Some(other) => return Err(format!("unknown command `{}`", other)),
+        None => return Err("missing command".to_string()),
+    };
+    let mut query = ProcessQuery::default();
+    let mut format = None;
+    let mut output: Option<PathBuf> = None;
+    let mut history_seconds = 0;
+    let mut exporter = Exporter::default();
+    let mut recordings = Vec::new();
+    let mut from = None;
+    let mut to = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--filter" | "-f" => query.filter = Some(value()?),
+            "--sort" | "-s" if !export => query.sort = parse_sort(&value()?)?,
+            "--asc" if !export => query.direction = SortDirection::Asc,
+            "--limit" | "-n" => {
+                let n = value()?;
+                query.limit = Some(n.parse().map_err(|_| format!("bad limit `{}`", n))?);
+            }
+            "--format" if export => format = Some(value()?),
+            "--output" | "-o" if export => output = Some(PathBuf::from(value()?)),
+            "--history" if export => {
+                let n = value()?;
+                history_seconds = n.parse().map_err(|_| format!("bad duration `{}`", n))?;
+            }
+            "--no-system" if export => exporter.include_system = false,
+            "--no-processes" if export => exporter.include_processes = false,
+            "--recording" | "-r" if export => recordings.push(PathBuf::from(value()?)),
+            "--from" | "--to" if export => {
+                let t = value()?;
+                let t = Some(t.parse().map_err(|_| format!("bad timestamp `{}`", t))?);
+                match arg.as_str() {
+                    "--from" => from = t,
+                    _ => to = t,
+                }
+            }
+            other => return Err(format!("unknown option `{}`", other)),
+        }
+    }
+    if !export {
+        return Ok(Command::Ps(query));
+    }
+    let columnar = format
+        .clone()
+        .or_else(|| Some(output.as_ref()?.extension()?.to_str()?.to_string()))
+        .map(|name| name.to_ascii_lowercase())
+        .filter(|name| COLUMNAR_FORMATS.contains(&name.as_str()));
+    let columnar = match columnar {
+        Some(format) => {
+            if !recordings.is_empty() && query.filter.is_some() {
+                return Err("--filter needs the live system, not --recording".to_string());
+            }
+            let range = match (from, to) {
+                (None, None) => None,
+                (from, to) => Some((
+                    from.unwrap_or(f64::NEG_INFINITY),
+                    to.unwrap_or(f64::INFINITY),
+                )),
+            };
+            Some(ColumnarArgs {
+                format,
+                recordings,
+                range,
+            })
+        }
+        None if !recordings.is_empty() || from.is_some() || to.is_some() => {
+            return Err("--recording, --from and --to need --format parquet or arrow".to_string())
+        }
+        None => {
+            exporter.format = output_format(format, output.as_ref())?.unwrap_or_default();
+            None
+        }
+    };
+    Ok(Command::Export {
+        exporter,
+        query,
+        output,
+        history_seconds,
+        columnar,
+    })
+}
+
+fn parse_baseline(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let command = match args.next().as_deref() {
+        Some("save") => Command::SaveBaseline(args.next().ok_or("save needs a name")?),
+        Some("list") => Command::ListBaselines,
+        Some("delete") => Command::DeleteBaseline(args.next().ok_or("delete needs a name")?),
+        Some(other) => return Err(format!("unknown baseline command `{}`", other)),
+        None => return Err("missing baseline command".to_string()),
+    };
+    match args.next() {
+        Some(extra) => Err(format!("unexpected argument `{}`", extra)),
+        None => Ok(command),
+    }
+}
+
+fn parse_diff(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut name = None;
+    let mut recordings = Vec::new();
+    let mut at = None;
+    let mut top = DEFAULT_TOP;
+    let mut format = None;
+    let mut output: Option<PathBuf> = None;
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--recording" | "-r" => recordings.push(PathBuf::from(value()?)),
+            "--at" => {
+                let t = value()?;
+                at = Some(t.parse().map_err(|_| format!("bad timestamp `{}`", t))?);
+            }
+            "--top" | "-n" => {
+                let n = value()?;
+                top = n.parse().map_err(|_| format!("bad count `{}`", n))?;
+            }
+            "--format" => format = Some(value()?),
+            "--output" | "-o" => output = Some(PathBuf::from(value()?)),
+            other if other.starts_with('-') || name.is_some() => {
+                return Err(format!("unknown option `{}`", other))
+            }
+            _ => name = Some(arg),
+        }
+    }
+    if at.is_some() && recordings.is_empty() {
+        return Err("--at needs --recording".to_string());
+    }
+    Ok(Command::Diff(DiffArgs {
+        name: name.ok_or("diff needs a baseline name")?,
+        recordings,
+        at,
+        top,
+        format: output_format(format, output.as_ref())?.unwrap_or(ExportFormat::Markdown),
+        output,
+    }))
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Print a query error, pointing at the column for filter errors.
+fn report_query_error(query: &ProcessQuery, e: DashboardError) -> ExitCode {
+    match e {
+        DashboardError::InvalidFilter { column, message } => {
+            let source = query.filter.as_deref().unwrap_or_default();
+            eprintln!("rdash: invalid filter: {}", message);
+            eprintln!("  {}", source);
+            eprintln!("  {}^", " ".repeat(column.saturating_sub(1)));
+        }
+        e => eprintln!("rdash: {}", e),
+    }
+    ExitCode::from(2)
+}
+
+fn ps(monitor: &SystemMonitor, query: ProcessQuery) -> ExitCode {
+    let page = match query.run(monitor) {
+        Ok(page) => page,
+        Err(e) => return report_query_error(&query, e),
+    };
+
+    println!(
+        "{:<32} {:>5} {:>7} {:>11} {:>12}",
+        "NAME", "PIDS", "CPU%", "MEMORY", "NET/s"
+    );
+    for p in &page.processes {
+        println!(
+            "{:<32} {:>5} {:>7.1} {:>11} {:>12}",
+            p.name,
+            p.pids.len(),
+            p.cpu_usage,
+            format_bytes(p.memory_usage),
+            format_bytes((p.net_rx_rate + p.net_tx_rate) as u64)
+        );
+    }
+    eprintln!("{} of {} groups", page.total, page.groups);
+    ExitCode::SUCCESS
+}
+
+fn export(
+    monitor: &mut SystemMonitor,
+    exporter: Exporter,
+    query: ProcessQuery,
+    output: Option<PathBuf>,
+    history_seconds: u64,
+    columnar: Option<ColumnarArgs>,
+) -> ExitCode {
+    let mut cpu = Vec::new();
+    let mut memory = Vec::new();
+    let mut load = Vec::new();
+    for i in 0..history_seconds {
+        if i > 0 {
+            std::thread::sleep(Duration::from_secs(1));
+            monitor.refresh();
+        }
+        let now = unix_now();
+        cpu.push((now, monitor.global_cpu_usage() as f64));
+        memory.push((now, monitor.memory_info().0 as f64 / GIB));
+        load.push((now, monitor.load_average().0));
+    }
+
+    let page = match query.run(monitor) {
+        Ok(page) => page,
+        Err(e) => return report_query_error(&query, e),
+    };
+    let mut data = ExportData::capture(monitor);
+    data.snapshot.processes = page.processes;
+    if history_seconds > 0 {
+        data.history = vec![
+            HistorySeries::new("cpu_usage", "%", cpu),
+            HistorySeries::new("memory_used", "GiB", memory),
+            HistorySeries::new("load_1", "", load),
+        ];
+    }
+
+    finish(match columnar {
+        Some(args) => write_columnar(&args, Some(&data), None, output.as_ref()),
+        None => write_output(output.as_ref(), |mut out| exporter.write(&data, &mut out)),
+    })
+}
+
+/// Write `data`'s points, or else those of `args.recordings` with at
+/// most `process_limit` process groups per snapshot.
+#[cfg(feature = "columnar")]
+fn write_columnar(
+    args: &ColumnarArgs,
+    data: Option<&ExportData>,
+    process_limit: Option<usize>,
+    output: Option<&PathBuf>,
+) -> Result<(), DashboardError> {
+    use rust_dashboard_lib::columnar::{export_points, recording_points, ColumnarFormat};
+
+    let format: ColumnarFormat = args.format.parse()?;
+    match data {
+        Some(data) => write_output(output, |mut out| {
+            format.write(export_points(data, args.range), &mut out)
+        }),
+        None => {
+            let replay = Replay::open(&args.recordings)?;
+            let limit = process_limit.unwrap_or(usize::MAX);
+            let points = recording_points(replay.frames(), args.range, limit);
+            write_output(output, |mut out| format.write(points, &mut out))
+        }
+    }
+}
+
+#[cfg(not(feature = "columnar"))]
+fn write_columnar(
+    args: &ColumnarArgs,
+    _data: Option<&ExportData>,
+    _process_limit: Option<usize>,
+    _output: Option<&PathBuf>,
+) -> Result<(), DashboardError> {
+    Err(DashboardError::ExportFailed(format!(
+        "{} export needs rdash built with the `columnar` feature",
+        args.format
+    )))
+}
+
+/// Write to `output`, or to stdout without one.
+fn write_output(
+    output: Option<&PathBuf>,
+    write: impl FnOnce(&mut (dyn Write + Send)) -> Result<(), DashboardError>,
+) -> Result<(), DashboardError> {
+    match output {
+        Some(path) => std::fs::File::create(path)
+            .map_err(|e| DashboardError::ExportFailed(format!("{}: {}", path.display(), e)))
+            .and_then(|file| {
+                let mut file = std::io::BufWriter::new(file);
+                write(&mut file)?;
+                file.flush()
+                    .map_err(|e| DashboardError::ExportFailed(e.to_string()))
+            }),
+        // Parquet writers need a `Send` output, which a stdout lock isn't.
+        None => {
+            let mut out = std::io::BufWriter::new(std::io::stdout());
+            write(&mut out)?;
+            out.flush()
+                .map_err(|e| DashboardError::ExportFailed(e.to_string()))
+        }
+    }
+}
+
+fn finish(result: Result<(), DashboardError>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("rdash: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn list_baselines(store: &BaselineStore) -> Result<(), DashboardError> {
+    let baselines = store.list()?;
+    println!("{:<32} {:<24} HOST", "NAME", "TAKEN (UNIX)");
+    for b in &baselines {
+        println!("{:<32} {:<24.0} {}", b.name, b.timestamp, b.hostname);
+    }
+    eprintln!(
+        "{} baselines in {}",
+        baselines.len(),
+        store.directory().display()
+    );
+    Ok(())
+}
+
+/// Diff against the live system with `monitor`, or else against
+/// `args.recordings`.
+fn diff(
+    store: &BaselineStore,
+    monitor: Option<&SystemMonitor>,
+    args: &DiffArgs,
+) -> Result<(), DashboardError> {
+    let baseline = store.load(&args.name)?;
+    let (snapshot, timestamp) = match monitor {
+        Some(monitor) => (SystemSnapshot::capture(monitor), unix_now()),
+        None => {
+            let replay = Replay::open(&args.recordings)?;
+            let frame = replay.snapshot_at(args.at.unwrap_or(f64::INFINITY))?;
+            (frame.snapshot, frame.timestamp)
+        }
+    };
+    let diff = SnapshotDiff::compare(&baseline, &snapshot, timestamp, args.top);
+    write_output(args.output.as_ref(), |mut out| {
+        diff.write(args.format, &mut out)
+    })
+}
+
+fn main() -> ExitCode {
+    let command = match parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("rdash: {}\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let store = || BaselineStore::open_default();
+    // Commands that don't look at the live system.
+    match &command {
+        Command::ListBaselines => return finish(store().and_then(|s| list_baselines(&s))),
+        Command::DeleteBaseline(name) => return finish(store().and_then(|s| s.delete(name))),
+        Command::Diff(args) if !args.recordings.is_empty() => {
+            return finish(store().and_then(|s| diff(&s, None, args)))
+        }
+        Command::Export {
+            query,
+            output,
+            columnar: Some(args),
+            ..
+        } if !args.recordings.is_empty() => {
+            return finish(write_columnar(args, None, query.limit, output.as_ref()))
+        }
+        _ => {}
+    }
+
+    // CPU usage needs two samples.
+    let mut monitor = SystemMonitor::new();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    monitor.refresh();
+
+    match command {
+        Command::Ps(query) => ps(&monitor, query),
+        Command::Export {
+            exporter,
+            query,
+            output,
+            history_seconds,
+            columnar,
+        } => export(
+            &mut monitor,
+            exporter,
+            query,
+            output,
+            history_seconds,
+            columnar,
+        ),
+        Command::SaveBaseline(name) => finish(store().and_then(|store| {
+            let path = store.save(&Baseline::capture(&name, &monitor)?)?;
+            eprintln!("Saved baseline `{}` to {}", name, path.display());
+            Ok(())
+        })),
+        Command::Diff(args) => finish(store().and_then(|s| diff(&s, Some(&monitor), &args))),
+        Command::ListBaselines | Command::DeleteBaseline(_) => unreachable!(),
+    }
+}
@@ -0,0 +1,305 @@
+//! Parquet and Arrow IPC exports of history and recordings, behind the
+//! `columnar` feature, for analysis in pandas, polars and the like.
+//!
+//! Both formats hold one row per point in "long" form:
+//!
+//! | column      | Parquet                         | Arrow                    |
+//! |-------------|---------------------------------|--------------------------|
+//! | `timestamp` | INT64, TIMESTAMP(MICROS, UTC)   | Timestamp(µs, "UTC")     |
+//! | `series`    | BYTE_ARRAY, STRING              | Utf8                     |
+//! | `value`     | DOUBLE                          | Float64                  |
+//! | `tags`      | MAP<STRING, STRING>             | Map<Utf8, Utf8>          |
+//!
+//! Snapshots become points the way `push::samples` splits them: series
+//! such as `system.cpu_usage` or `disk.used`, tagged with the mount,
+//! interface or process group. History series keep their name and are
+//! tagged with their unit.
+//!
+//! Files are written with the `parquet` and `arrow-ipc` crates: Parquet
+//! files are GZIP-compressed with one row group per `ROWS_PER_GROUP`
+//! rows; Arrow files use the IPC file format (what Feather v2 is) with
+//! one record batch per `ROWS_PER_GROUP` rows. Points are taken from an
+//! iterator a batch at a time, so a long recording is never in memory
+//! all at once.
+
+use crate::error::DashboardError;
+use crate::export::{ExportData, HistorySeries};
+use crate::push::samples;
+use crate::recording::RecordedSnapshot;
+use crate::snapshot::SystemSnapshot;
+use arrow_array::builder::{MapBuilder, MapFieldNames, StringBuilder};
+use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, TimestampMicrosecondArray};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::{Compression, GzipLevel};
+use parquet::file::properties::WriterProperties;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Rows per Parquet row group and per Arrow record batch.
+pub const ROWS_PER_GROUP: usize = 65_536;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColumnarFormat {
+    Parquet,
+    /// The Arrow IPC file format, also read as Feather v2.
+    Arrow,
+}
+
+impl ColumnarFormat {
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "parquet" => Some(Self::Parquet),
+            "arrow" | "feather" | "ipc" => Some(Self::Arrow),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Arrow => "arrow",
+        }
+    }
+
+    /// Write `points` to `out` in this format.
+    pub fn write(
+        self,
+        points: impl IntoIterator<Item = SeriesPoint>,
+        out: &mut (impl Write + Send),
+    ) -> Result<(), DashboardError> {
+        match self {
+            Self::Parquet => write_parquet(points, out),
+            Self::Arrow => write_arrow(points, out),
+        }
+    }
+}
+
+impl FromStr for ColumnarFormat {
+    type Err = DashboardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_extension(s)
+            .ok_or_else(|| DashboardError::ConfigError(format!("unknown columnar format `{}`", s)))
+    }
+}
+
+/// One row: a value of a series at a time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesPoint {
+    /// Seconds since the Unix epoch.
+    pub timestamp: f64,
+    pub series: String,
+    pub value: f64,
+    pub tags: Vec<(String, String)>,
+}
+
+fn in_range(timestamp: f64, range: Option<(f64, f64)>) -> bool {
+    match range {
+        Some((start, end)) => start <= timestamp && timestamp <= end,
+        None => true,
+    }
+}
+
+/// The points of `history` within `range` (inclusive), series by
+/// series. A series' unit, if it has one, is its `unit` tag.
+pub fn history_points(history: &[HistorySeries], range: Option<(f64, f64)>) -> Vec<SeriesPoint> {
+    let mut points = Vec::new();
+    for series in history {
+        let tags: Vec<(String, String)> = match series.unit.as_str() {
+            "" => Vec::new(),
+            unit => vec![("unit".to_string(), unit.to_string())],
+        };
+        points.extend(
+            series
+                .points
+                .iter()
+                .filter(|(t, _)| in_range(*t, range))
+                .map(|&(timestamp, value)| SeriesPoint {
+                    timestamp,
+                    series: series.name.clone(),
+                    value,
+                    tags: tags.clone(),
+                }),
+        );
+    }
+    points
+}
+
+/// The points of one snapshot taken at `timestamp`: system totals, each
+/// disk and interface, and the `process_limit` busiest process groups.
+/// An empty `hostname` leaves out the `host` tag.
+pub fn snapshot_points(
+    snapshot: &SystemSnapshot,
+    timestamp: f64,
+    hostname: &str,
+    process_limit: usize,
+) -> Vec<SeriesPoint> {
+    let mut points = Vec::new();
+    for sample in samples(snapshot, hostname, process_limit) {
+        let tags: Vec<(String, String)> = sample
+            .tags
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect();
+        points.extend(sample.fields.iter().map(|(field, value)| SeriesPoint {
+            timestamp,
+            series: format!("{}.{}", sample.measurement, field),
+            value: *value,
+            tags: tags.clone(),
+        }));
+    }
+    points
+}
+
+/// The points of every recorded snapshot within `range`, in recording
+/// order, taking frames from `frames` only as they're needed.
+/// Recordings don't say which host they came from, so points have no
+/// `host` tag.
+pub fn recording_points(
+    frames: impl IntoIterator<Item = RecordedSnapshot>,
+    range: Option<(f64, f64)>,
+    process_limit: usize,
+) -> impl Iterator<Item = SeriesPoint> {
+    frames
+        .into_iter()
+        .filter(move |frame| in_range(frame.timestamp, range))
+        .flat_map(move |frame| snapshot_points(&frame.snapshot, frame.timestamp, "", process_limit))
+}
+
+/// The points of an export: its history within `range`, then its
+/// snapshot if it was taken within `range`, with every process group
+/// it holds.
+pub fn export_points(
+    data: &ExportData,
+    range: Option<(f64, f64)>,
+) -> impl Iterator<Item = SeriesPoint> {
+    let snapshot = in_range(data.timestamp, range).then(|| {
+        snapshot_points(
+            &data.snapshot,
+            data.timestamp,
+            &data.host.hostname,
+            data.snapshot.processes.len(),
+        )
+    });
+    history_points(&data.history, range)
+        .into_iter()
+        .chain(snapshot.into_iter().flatten())
+}
+
+fn failed(e: impl std::fmt::Display) -> DashboardError {
+    DashboardError::ExportFailed(e.to_string())
+}
+
+/// The columns of both formats; see the table above.
+fn schema() -> SchemaRef {
+    let entries = Fields::from(vec![
+        Field::new("key", DataType::Utf8, false),
+        Field::new("value", DataType::Utf8, false),
+    ]);
+    Arc::new(Schema::new(vec![
+        Field::new(
+            "timestamp",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("series", DataType::Utf8, false),
+        Field::new("value", DataType::Float64, false),
+        Field::new(
+            "tags",
+            DataType::Map(
+                Arc::new(Field::new("entries", DataType::Struct(entries), false)),
+                false,
+            ),
+            false,
+        ),
+    ]))
+}
+
+fn record_batch(schema: &SchemaRef, rows: &[SeriesPoint]) -> Result<RecordBatch, DashboardError> {
+    let micros: Vec<i64> = rows
+        .iter()
+        .map(|row| (row.timestamp * 1e6).round() as i64)
+        .collect();
+    let timestamps = TimestampMicrosecondArray::from(micros).with_timezone("UTC");
+    let series: StringArray = rows.iter().map(|row| Some(row.series.as_str())).collect();
+    let values: Float64Array = rows.iter().map(|row| row.value).collect();
+    let names = MapFieldNames {
+        entry: "entries".to_string(),
+        key: "key".to_string(),
+        value: "value".to_string(),
+    };
+    let mut tags = MapBuilder::new(Some(names), StringBuilder::new(), StringBuilder::new())
+        .with_values_field(Field::new("value", DataType::Utf8, false));
+    for row in rows {
+        for (key, value) in &row.tags {
+            tags.keys().append_value(key);
+            tags.values().append_value(value);
+        }
+        tags.append(true).map_err(failed)?;
+    }
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(timestamps),
+        Arc::new(series),
+        Arc::new(values),
+        Arc::new(tags.finish()),
+    ];
+    RecordBatch::try_new(schema.clone(), columns).map_err(failed)
+}
+
+/// Hand `points` to `write` as record batches of `ROWS_PER_GROUP` rows,
+/// the last one possibly shorter.
+fn write_batches(
+    schema: &SchemaRef,
+    points: impl IntoIterator<Item = SeriesPoint>,
+    mut write: impl FnMut(&RecordBatch) -> Result<(), DashboardError>,
+) -> Result<(), DashboardError> {
+    let mut rows = Vec::new();
+    for point in points {
+        rows.push(point);
+        if rows.len() == ROWS_PER_GROUP {
+            write(&record_batch(schema, &rows)?)?;
+            rows.clear();
+        }
+    }
+    if !rows.is_empty() {
+        write(&record_batch(schema, &rows)?)?;
+    }
+    Ok(())
+}
+
+/// Write `points` as a Parquet file.
+pub fn write_parquet(
+    points: impl IntoIterator<Item = SeriesPoint>,
+    out: &mut (impl Write + Send),
+) -> Result<(), DashboardError> {
+    let schema = schema();
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::GZIP(GzipLevel::default()))
+        .set_max_row_group_size(ROWS_PER_GROUP)
+        .set_created_by(format!(
+            "rust-dashboard version {}",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build();
+    let mut writer = ArrowWriter::try_new(out, schema.clone(), Some(properties)).map_err(failed)?;
+    write_batches(&schema, points, |batch| writer.write(batch).map_err(failed))?;
+    writer.close().map_err(failed)?;
+    Ok(())
+}
+
+/// Write `points` as an Arrow IPC file.
+pub fn write_arrow(
+    points: impl IntoIterator<Item = SeriesPoint>,
+    out: &mut impl Write,
+) -> Result<(), DashboardError> {
+    let schema = schema();
+    let mut writer = FileWriter::try_new(out, &schema).map_err(failed)?;
+    write_batches(&schema, points, |batch| writer.write(batch).map_err(failed))?;
+    writer.finish().map_err(failed)
+}
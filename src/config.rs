@@ -1,6 +1,136 @@
+use crate::alerts::AlertRule;
+use crate::anomaly::AnomalyConfig;
+use crate::forecast::ForecastConfig;
+use crate::leaks::LeakConfig;
+use crate::push::PushConfig;
+use crate::recording::RecordingConfig;
+use crate::remediation::RemediationConfig;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Shortest cadence any subsystem may be configured to. sysinfo needs
+/// at least `MINIMUM_CPU_UPDATE_INTERVAL` (~200 ms) between CPU samples
+/// to produce meaningful usage, and the Tauri loop ticks at 250 ms.
+pub const MIN_SUBSYSTEM_INTERVAL_MS: u64 = 250;
+
+/// Independently scheduled parts of a `SystemMonitor` refresh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Subsystem {
+    Cpu,
+    Memory,
+    Processes,
+    Disks,
+    Networks,
+}
+
+impl Subsystem {
+    pub const ALL: [Subsystem; 5] = [
+        Subsystem::Cpu,
+        Subsystem::Memory,
+        Subsystem::Processes,
+        Subsystem::Disks,
+        Subsystem::Networks,
+    ];
+}
+
+/// Refresh cadence for one subsystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubsystemCadence {
+    /// Milliseconds between refreshes. `None` follows the global
+    /// `refresh_interval_seconds`. Values below
+    /// `MIN_SUBSYSTEM_INTERVAL_MS` are raised to it.
+    pub interval_ms: Option<u64>,
+    /// Skip this subsystem entirely while no visible window renders it.
+    pub only_when_visible: bool,
+}
+
+impl SubsystemCadence {
+    pub fn every_ms(ms: u64) -> Self {
+        Self {
+            interval_ms: Some(ms),
+            only_when_visible: false,
+        }
+    }
+
+    /// Effective interval, given the global one.
+    pub fn interval(&self, global: Duration) -> Duration {
+        match self.interval_ms {
+            Some(ms) => Duration::from_millis(ms.max(MIN_SUBSYSTEM_INTERVAL_MS)),
+            None => global,
+        }
+    }
+}
+
+/// Per-subsystem refresh cadence. The defaults reproduce the original
+/// fixed behaviour: CPU, memory and processes follow the global
+/// interval, networks refresh every 5 s and disks every 60 s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RefreshSchedule {
+    pub cpu: SubsystemCadence,
+    pub memory: SubsystemCadence,
+    pub processes: SubsystemCadence,
+    pub disks: SubsystemCadence,
+    pub networks: SubsystemCadence,
+}
+
+impl Default for RefreshSchedule {
+    fn default() -> Self {
+        Self {
+            cpu: SubsystemCadence::default(),
+            memory: SubsystemCadence::default(),
+            processes: SubsystemCadence::default(),
+            disks: SubsystemCadence::every_ms(60_000),
+            networks: SubsystemCadence::every_ms(5_000),
+        }
+    }
+}
+
+impl RefreshSchedule {
+    pub fn get(&self, subsystem: Subsystem) -> &SubsystemCadence {
+        match subsystem {
+            Subsystem::Cpu => &self.cpu,
+            Subsystem::Memory => &self.memory,
+            Subsystem::Processes => &self.processes,
+            Subsystem::Disks => &self.disks,
+            Subsystem::Networks => &self.networks,
+        }
+    }
+}
+
+/// Adaptive low-overhead behaviour; see `adaptive::AdaptiveScheduler`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdaptiveConfig {
+    /// Master switch. When off the monitor always runs the full schedule.
+    pub enabled: bool,
+    /// Collect only global CPU and memory while no window is visible.
+    pub minimal_when_hidden: bool,
+    /// Interval multiplier applied while running on battery.
+    pub battery_multiplier: u32,
+    /// Global CPU usage (percent) above which the system counts as
+    /// under load.
+    pub load_threshold_percent: f32,
+    /// Interval multiplier applied while the system is under load.
+    /// Stacks with `battery_multiplier`.
+    pub load_multiplier: u32,
+}
+
+impl Default for AdaptiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            minimal_when_hidden: true,
+            battery_multiplier: 2,
+            load_threshold_percent: 85.0,
+            load_multiplier: 2,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -10,6 +140,32 @@ pub struct AppConfig {
     pub window_height: Option<f32>,
     pub window_x: Option<f32>,
     pub window_y: Option<f32>,
+    /// Per-subsystem cadence. Missing from configs written before it
+    /// existed, hence the serde default.
+    #[serde(default)]
+    pub refresh: RefreshSchedule,
+    #[serde(default)]
+    pub adaptive: AdaptiveConfig,
+    #[serde(default)]
+    pub leaks: LeakConfig,
+    #[serde(default)]
+    pub disk_forecast: ForecastConfig,
+    #[serde(default)]
+    pub anomalies: AnomalyConfig,
+    /// Alert rules checked after every refresh; see `alerts::AlertRule`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<AlertRule>,
+    /// Automatic renice/terminate policies; see
+    /// `remediation::RemediationPolicy`.
+    #[serde(default)]
+    pub remediation: RemediationConfig,
+    /// Continuous recording to rotating files; see
+    /// `recording::RecordingConfig`.
+    #[serde(default)]
+    pub recording: RecordingConfig,
+    /// InfluxDB and StatsD exporters; see `push::PushConfig`.
+    #[serde(default)]
+    pub push: PushConfig,
 }
 
 impl Default for AppConfig {
@@ -21,6 +177,15 @@ impl Default for AppConfig {
             window_height: None,
             window_x: None,
             window_y: None,
+            refresh: RefreshSchedule::default(),
+            adaptive: AdaptiveConfig::default(),
+            leaks: LeakConfig::default(),
+            disk_forecast: ForecastConfig::default(),
+            anomalies: AnomalyConfig::default(),
+            alerts: Vec::new(),
+            remediation: RemediationConfig::default(),
+            recording: RecordingConfig::default(),
+            push: PushConfig::default(),
         }
     }
 }
@@ -0,0 +1,329 @@
+use crate::error::DashboardError;
+use crate::snapshot::{
+    DiskInfo, MemoryInfo, MonitorOverhead, NetworkInfo, SelfUsage, SystemSnapshot,
+};
+use crate::system::CombinedProcess;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// CPU change (percentage points) below which a process row counts as
+/// unchanged. Idle processes jitter by a few hundredths per refresh.
+pub const CPU_EPSILON: f32 = 0.1;
+
+/// Memory change (bytes) below which a process row counts as unchanged.
+pub const MEMORY_EPSILON: u64 = 64 * 1024;
+
+/// Network rate change (bytes/sec) below which a process row counts as
+/// unchanged.
+pub const RATE_EPSILON: f64 = 1.0;
+
+/// One message of the incremental snapshot protocol: a full snapshot on
+/// subscribe or resync, then deltas. Sequence numbers are contiguous;
+/// a receiver that sees a gap asks for a new full snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SnapshotMessage {
+    Full { seq: u64, snapshot: SystemSnapshot },
+    Delta(SnapshotDelta),
+}
+
+impl SnapshotMessage {
+    pub fn seq(&self) -> u64 {
+        match self {
+            SnapshotMessage::Full { seq, .. } => *seq,
+            SnapshotMessage::Delta(delta) => delta.seq,
+        }
+    }
+}
+
+/// Changes since sequence number `seq - 1`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDelta {
+    pub seq: u64,
+    pub metrics: MetricsDelta,
+    pub processes: ProcessDelta,
+}
+
+/// Global metrics. The small scalar fields are always sent; the larger
+/// sections only when they changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsDelta {
+    pub cpu_usage: f32,
+    pub self_usage: Option<SelfUsage>,
+    pub uptime_seconds: u64,
+    pub load_average: (f64, f64, f64),
+    pub overhead: MonitorOverhead,
+    #[serde(default)]
+    pub anomalies: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub per_cpu: Option<Vec<f32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemoryInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disks: Option<Vec<DiskInfo>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub networks: Option<Vec<NetworkInfo>>,
+}
+
+/// Process rows keyed by group name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessDelta {
+    pub added: Vec<CombinedProcess>,
+    pub changed: Vec<CombinedProcess>,
+    /// Names of groups that no longer exist.
+    pub removed: Vec<String>,
+}
+
+impl ProcessDelta {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+fn process_differs(known: &CombinedProcess, current: &CombinedProcess) -> bool {
+    (known.cpu_usage - current.cpu_usage).abs() >= CPU_EPSILON
+        || known.memory_usage.abs_diff(current.memory_usage) >= MEMORY_EPSILON
+        || (known.net_rx_rate - current.net_rx_rate).abs() >= RATE_EPSILON
+        || (known.net_tx_rate - current.net_tx_rate).abs() >= RATE_EPSILON
+        || known.connections != current.connections
+        || known.pids != current.pids
+}
+
+/// Turns successive snapshots into `SnapshotMessage`s.
+///
+/// The encoder tracks what receivers already hold rather than the raw
+/// previous snapshot, so rows suppressed as unchanged (within the
+/// epsilons above) can't drift further than one epsilon from the truth.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::delta::{DeltaDecoder, DeltaEncoder};
+/// use rust_dashboard_lib::snapshot::SystemSnapshot;
+/// use rust_dashboard_lib::system::SystemMonitor;
+///
+/// let mut monitor = SystemMonitor::new();
+/// let mut encoder = DeltaEncoder::new();
+/// let mut decoder = DeltaDecoder::new();
+///
+/// let first = encoder.encode(&SystemSnapshot::capture(&monitor));
+/// decoder.apply(first).unwrap();
+/// monitor.refresh();
+/// let delta = encoder.encode(&SystemSnapshot::capture(&monitor));
+/// assert_eq!(decoder.apply(delta).unwrap().processes.len(), monitor.combined_process_list().len());
+/// ```
+#[derive(Debug, Default)]
+pub struct DeltaEncoder {
+    seq: u64,
+    /// Receiver-side state, minus processes.
+    known: Option<SystemSnapshot>,
+    known_processes: HashMap<String, CombinedProcess>,
+}
+
+impl DeltaEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sequence number of the last message produced; 0 before the first.
+    pub fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    /// Encode `snapshot` against what was sent before. The first call
+    /// yields a full snapshot; every later call a delta with the next
+    /// sequence number.
+    pub fn encode(&mut self, snapshot: &SystemSnapshot) -> SnapshotMessage {
+        self.seq += 1;
+        let Some(known) = self.known.as_mut() else {
+            self.known_processes = snapshot
+                .processes
+                .iter()
+                .map(|p| (p.name.clone(), p.clone()))
+                .collect();
+            self.known = Some(SystemSnapshot {
+                processes: Vec::new(),
+                ..snapshot.clone()
+            });
+            return SnapshotMessage::Full {
+                seq: self.seq,
+                snapshot: snapshot.clone(),
+            };
+        };
+
+        fn changed<T: PartialEq + Clone>(known: &mut T, current: &T) -> Option<T> {
+            (known != current).then(|| {
+                *known = current.clone();
+                current.clone()
+            })
+        }
+        let metrics = MetricsDelta {
+            cpu_usage: snapshot.cpu_usage,
+            self_usage: snapshot.self_usage.clone(),
+            uptime_seconds: snapshot.uptime_seconds,
+            load_average: snapshot.load_average,
+            overhead: snapshot.overhead.clone(),
+            anomalies: snapshot.anomalies.clone(),
+            per_cpu: changed(&mut known.per_cpu, &snapshot.per_cpu),
+            memory: changed(&mut known.memory, &snapshot.memory),
+            disks: changed(&mut known.disks, &snapshot.disks),
+            networks: changed(&mut known.networks, &snapshot.networks),
+        };
+        known.cpu_usage = metrics.cpu_usage;
+        known.self_usage = metrics.self_usage.clone();
+        known.uptime_seconds = metrics.uptime_seconds;
+        known.load_average = metrics.load_average;
+        known.overhead = metrics.overhead.clone();
+        known.anomalies = metrics.anomalies.clone();
+
+        let mut processes = ProcessDelta::default();
+        let mut seen = HashSet::with_capacity(snapshot.processes.len());
+        for current in &snapshot.processes {
+            seen.insert(current.name.as_str());
+            match self.known_processes.get_mut(&current.name) {
+                Some(known) if process_differs(known, current) => {
+                    *known = current.clone();
+                    processes.changed.push(current.clone());
+                }
+                Some(_) => {}
+                None => {
+                    self.known_processes
+                        .insert(current.name.clone(), current.clone());
+                    processes.added.push(current.clone());
+                }
+            }
+        }
+        self.known_processes.retain(|name, _| {
+            let keep = seen.contains(name.as_str());
+            if !keep {
+                processes.removed.push(name.clone());
+            }
+            keep
+        });
+        processes.removed.sort();
+
+        SnapshotMessage::Delta(SnapshotDelta {
+            seq: self.seq,
+            metrics,
+            processes,
+        })
+    }
+
+    /// A full snapshot of what receivers currently hold, at the current
+    /// sequence number, for a new subscriber or one resyncing after a
+    /// gap. `None` before the first `encode`.
+    pub fn full(&self) -> Option<SnapshotMessage> {
+        let known = self.known.as_ref()?;
+        Some(SnapshotMessage::Full {
+            seq: self.seq,
+            snapshot: SystemSnapshot {
+                processes: self.known_processes.values().cloned().collect(),
+                ..known.clone()
+            },
+        })
+    }
+}
+
+/// Rebuilds snapshots from `SnapshotMessage`s; the receiving half of
+/// `DeltaEncoder`.
+#[derive(Debug, Default)]
+pub struct DeltaDecoder {
+    seq: Option<u64>,
+    snapshot: Option<SystemSnapshot>,
+    /// Group name -> index in `snapshot.processes`.
+    index: HashMap<String, usize>,
+}
+
+impl DeltaDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sequence number of the last applied message.
+    pub fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+
+    pub fn snapshot(&self) -> Option<&SystemSnapshot> {
+        self.snapshot.as_ref()
+    }
+
+    /// Apply a message and return the resulting snapshot. Deltas at or
+    /// below the current sequence number are already reflected and are
+    /// ignored. A delta that skips ahead — or arrives before any full
+    /// snapshot — returns `DashboardError::SequenceGap`; the caller
+    /// should request a full snapshot and apply that.
+    pub fn apply(&mut self, message: SnapshotMessage) -> Result<&SystemSnapshot, DashboardError> {
+        match message {
+            SnapshotMessage::Full { seq, snapshot } => {
+                self.index = snapshot
+                    .processes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (p.name.clone(), i))
+                    .collect();
+                self.seq = Some(seq);
+                Ok(self.snapshot.insert(snapshot))
+            }
+            SnapshotMessage::Delta(delta) => {
+                let (Some(seq), Some(snapshot)) = (self.seq, self.snapshot.as_mut()) else {
+                    return Err(DashboardError::SequenceGap {
+                        expected: 0,
+                        got: delta.seq,
+                    });
+                };
+                if delta.seq <= seq {
+                    return Ok(snapshot);
+                }
+                if delta.seq != seq + 1 {
+                    return Err(DashboardError::SequenceGap {
+                        expected: seq + 1,
+                        got: delta.seq,
+                    });
+                }
+
+                let m = delta.metrics;
+                snapshot.cpu_usage = m.cpu_usage;
+                snapshot.self_usage = m.self_usage;
+                snapshot.uptime_seconds = m.uptime_seconds;
+                snapshot.load_average = m.load_average;
+                snapshot.overhead = m.overhead;
+                snapshot.anomalies = m.anomalies;
+                if let Some(per_cpu) = m.per_cpu {
+                    snapshot.per_cpu = per_cpu;
+                }
+                if let Some(memory) = m.memory {
+                    snapshot.memory = memory;
+                }
+                if let Some(disks) = m.disks {
+                    snapshot.disks = disks;
+                }
+                if let Some(networks) = m.networks {
+                    snapshot.networks = networks;
+                }
+
+                let p = delta.processes;
+                for name in &p.removed {
+                    if let Some(i) = self.index.remove(name) {
+                        snapshot.processes.swap_remove(i);
+                        if let Some(moved) = snapshot.processes.get(i) {
+                            self.index.insert(moved.name.clone(), i);
+                        }
+                    }
+                }
+                for process in p.changed.into_iter().chain(p.added) {
+                    match self.index.get(&process.name) {
+                        Some(&i) => snapshot.processes[i] = process,
+                        None => {
+                            self.index
+                                .insert(process.name.clone(), snapshot.processes.len());
+                            snapshot.processes.push(process);
+                        }
+                    }
+                }
+
+                self.seq = Some(delta.seq);
+                Ok(snapshot)
+            }
+        }
+    }
+}
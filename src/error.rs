@@ -18,6 +18,36 @@ pub enum DashboardError {
     /// Configuration error
     #[error("Configuration error: {0}")]
     ConfigError(String),
+
+    /// A snapshot delta didn't follow the last applied sequence number;
+    /// the receiver has to resync from a full snapshot
+    #[error("Snapshot sequence gap: expected {expected}, got {got}")]
+    SequenceGap { expected: u64, got: u64 },
+
+    /// A process query had an invalid pattern
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
+    /// A filter expression failed to parse; `column` is 1-based
+    #[error("Invalid filter at column {column}: {message}")]
+    InvalidFilter { column: usize, message: String },
+
+    /// Formatting or writing an export failed
+    #[error("Export failed: {0}")]
+    ExportFailed(String),
+
+    #[error("Recording failed: {0}")]
+    RecordingFailed(String),
+
+    #[error("Replay failed: {0}")]
+    ReplayFailed(String),
+
+    #[error("Push failed: {0}")]
+    PushFailed(String),
+
+    /// No socket table could be read, e.g. on a platform without procfs
+    #[error("Socket table unavailable: {0}")]
+    SocketTableUnavailable(String),
 }
 
 impl<T> From<std::sync::PoisonError<T>> for DashboardError {
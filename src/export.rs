@@ -0,0 +1,768 @@
+//! Exports of a snapshot, history and the process list as JSON, CSV,
+//! NDJSON or a human-readable Markdown/HTML report. Used by the desktop
+//! app's export buttons and by `rdash export`.
+//!
+//! Machine-readable formats keep raw units (bytes, bytes per second,
+//! seconds since the Unix epoch); only CSV and the reports scale
+//! values for reading.
+
+use crate::error::DashboardError;
+use crate::snapshot::{HostInfo, SystemSnapshot};
+use crate::system::{unix_now, CombinedProcess, SystemMonitor};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::io::Write;
+use std::str::FromStr;
+
+const MIB: f64 = 1024.0 * 1024.0;
+const GIB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// One pretty-printed document.
+    #[default]
+    Json,
+    /// One table; see `Exporter` for the columns.
+    Csv,
+    /// One JSON object per line, each tagged with a `type`.
+    Ndjson,
+    Markdown,
+    Html,
+}
+
+impl ExportFormat {
+    /// The format a file name's extension implies, if any.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "md" | "markdown" => Some(Self::Markdown),
+            "html" | "htm" => Some(Self::Html),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+            Self::Markdown => "md",
+            Self::Html => "html",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = DashboardError;
+
+    /// Accepts the same names as `from_extension`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_extension(s)
+            .ok_or_else(|| DashboardError::ConfigError(format!("unknown export format `{}`", s)))
+    }
+}
+
+/// A named series of `(unix seconds, value)` points, e.g. the app's CPU
+/// history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistorySeries {
+    pub name: String,
+    pub unit: String,
+    pub points: Vec<(f64, f64)>,
+}
+
+impl HistorySeries {
+    pub fn new(name: impl Into<String>, unit: impl Into<String>, points: Vec<(f64, f64)>) -> Self {
+        Self {
+            name: name.into(),
+            unit: unit.into(),
+            points,
+        }
+    }
+}
+
+/// Everything an export can contain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportData {
+    /// When the data was taken, in seconds since the Unix epoch.
+    pub timestamp: f64,
+    pub host: HostInfo,
+    /// The processes exported are `snapshot.processes`; replace them
+    /// with a `ProcessQuery` result to export a filtered list.
+    pub snapshot: SystemSnapshot,
+    #[serde(default)]
+    pub history: Vec<HistorySeries>,
+}
+
+impl ExportData {
+    /// The monitor's current state, without history.
+    pub fn capture(monitor: &SystemMonitor) -> Self {
+        Self {
+            timestamp: unix_now(),
+            host: HostInfo::current(monitor),
+            snapshot: SystemSnapshot::capture(monitor),
+            history: Vec::new(),
+        }
+    }
+}
+
+/// Formats `ExportData`. Sections can be left out, history limited to a
+/// time range and the process list to the busiest processes.
+///
+/// CSV rows share the columns `Type, Name, CPU Usage %, Memory MB, PIDs,
+/// Disk Used GB, Disk Total GB, Full In Hours, RX KB/s, TX KB/s,
+/// Timestamp, Value`; each row type fills the ones that apply.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::export::{ExportData, ExportFormat, Exporter};
+/// use rust_dashboard_lib::system::SystemMonitor;
+///
+/// let data = ExportData::capture(&SystemMonitor::new());
+/// let report = Exporter {
+///     process_limit: Some(10),
+///     ..Exporter::new(ExportFormat::Markdown)
+/// }
+/// .export(&data)
+/// .unwrap();
+/// assert!(report.contains("## Processes"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Exporter {
+    pub format: ExportFormat,
+    /// Host, CPU, memory, load, disks and networks.
+    pub include_system: bool,
+    pub include_processes: bool,
+    pub include_history: bool,
+    /// Only history points with `start <= timestamp <= end`.
+    pub history_range: Option<(f64, f64)>,
+    /// Export only the N processes using the most CPU.
+    pub process_limit: Option<usize>,
+}
+
+impl Default for Exporter {
+    fn default() -> Self {
+        Self {
+            format: ExportFormat::Json,
+            include_system: true,
+            include_processes: true,
+            include_history: true,
+            history_range: None,
+            process_limit: None,
+        }
+    }
+}
+
+impl Exporter {
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            ..Default::default()
+        }
+    }
+
+    /// Format `data` as a string.
+    pub fn export(&self, data: &ExportData) -> Result<String, DashboardError> {
+        let mut out = Vec::new();
+        self.write(data, &mut out)?;
+        String::from_utf8(out).map_err(|e| DashboardError::ExportFailed(e.to_string()))
+    }
+
+    /// Format `data` into `out`.
+    pub fn write(&self, data: &ExportData, out: &mut impl Write) -> Result<(), DashboardError> {
+        let view = View::new(self, data);
+        match self.format {
+            ExportFormat::Json => write_json(&view, out),
+            ExportFormat::Csv => write_csv(&view, out),
+            ExportFormat::Ndjson => write_ndjson(&view, out),
+            ExportFormat::Markdown => {
+                let report = render_markdown(&report_tables(&view));
+                out.write_all(report.as_bytes()).map_err(failed)
+            }
+            ExportFormat::Html => {
+                let report = render_html(&report_tables(&view));
+                out.write_all(report.as_bytes()).map_err(failed)
+            }
+        }
+    }
+}
+
+fn failed(e: impl std::fmt::Display) -> DashboardError {
+    DashboardError::ExportFailed(e.to_string())
+}
+
+/// The parts of `ExportData` an `Exporter` selected.
+struct View<'a> {
+    data: &'a ExportData,
+    system: bool,
+    processes: Option<Vec<&'a CombinedProcess>>,
+    history: Option<Vec<SeriesView<'a>>>,
+}
+
+#[derive(Serialize)]
+struct SeriesView<'a> {
+    name: &'a str,
+    unit: &'a str,
+    points: Vec<(f64, f64)>,
+}
+
+impl<'a> View<'a> {
+    fn new(exporter: &Exporter, data: &'a ExportData) -> Self {
+        let processes = exporter.include_processes.then(|| {
+            let mut processes: Vec<&CombinedProcess> = data.snapshot.processes.iter().collect();
+            if let Some(limit) = exporter.process_limit {
+                processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+                processes.truncate(limit);
+            }
+            processes
+        });
+        let history = exporter.include_history.then(|| {
+            data.history
+                .iter()
+                .map(|series| SeriesView {
+                    name: &series.name,
+                    unit: &series.unit,
+                    points: series
+                        .points
+                        .iter()
+                        .copied()
+                        .filter(|&(t, _)| match exporter.history_range {
+                            Some((start, end)) => t >= start && t <= end,
+                            None => true,
+                        })
+                        .collect(),
+                })
+                .collect()
+        });
+        Self {
+            data,
+            system: exporter.include_system,
+            processes,
+            history,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SystemView<'a> {
+    cpu_usage: f32,
+    per_cpu: &'a [f32],
+    load_average: (f64, f64, f64),
+    uptime_seconds: u64,
+    memory: &'a crate::snapshot::MemoryInfo,
+    disks: &'a [crate::snapshot::DiskInfo],
+    networks: &'a [crate::snapshot::NetworkInfo],
+    anomalies: &'a [String],
+}
+
+impl<'a> SystemView<'a> {
+    fn new(snapshot: &'a SystemSnapshot) -> Self {
+        Self {
+            cpu_usage: snapshot.cpu_usage,
+            per_cpu: &snapshot.per_cpu,
+            load_average: snapshot.load_average,
+            uptime_seconds: snapshot.uptime_seconds,
+            memory: &snapshot.memory,
+            disks: &snapshot.disks,
+            networks: &snapshot.networks,
+            anomalies: &snapshot.anomalies,
+        }
+    }
+}
+
+fn write_json(view: &View, out: &mut impl Write) -> Result<(), DashboardError> {
+    #[derive(Serialize)]
+    struct Document<'a> {
+        timestamp: f64,
+        host: &'a HostInfo,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        system: Option<SystemView<'a>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        processes: Option<&'a [&'a CombinedProcess]>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        history: Option<&'a [SeriesView<'a>]>,
+    }
+    let document = Document {
+        timestamp: view.data.timestamp,
+        host: &view.data.host,
+        system: view.system.then(|| SystemView::new(&view.data.snapshot)),
+        processes: view.processes.as_deref(),
+        history: view.history.as_deref(),
+    };
+    serde_json::to_writer_pretty(&mut *out, &document).map_err(failed)?;
+    writeln!(out).map_err(failed)
+}
+
+fn to_json(value: &impl Serialize) -> Result<serde_json::Value, DashboardError> {
+    serde_json::to_value(value).map_err(failed)
+}
+
+fn write_ndjson(view: &View, out: &mut impl Write) -> Result<(), DashboardError> {
+    let timestamp = view.data.timestamp;
+    let mut line = |kind: &str, at: f64, value: serde_json::Value| -> Result<(), DashboardError> {
+        let mut record = serde_json::Map::new();
+        record.insert("type".to_string(), kind.into());
+        record.insert("timestamp".to_string(), at.into());
+        match value {
+            serde_json::Value::Object(fields) => record.extend(fields),
+            other => {
+                record.insert("value".to_string(), other);
+            }
+        }
+        serde_json::to_writer(&mut *out, &record).map_err(failed)?;
+        writeln!(out).map_err(failed)
+    };
+
+    line("host", timestamp, to_json(&view.data.host)?)?;
+    if view.system {
+        let snapshot = &view.data.snapshot;
+        line(
+            "system",
+            timestamp,
+            serde_json::json!({
+                "cpu_usage": snapshot.cpu_usage,
+                "per_cpu": snapshot.per_cpu,
+                "load_average": snapshot.load_average,
+                "uptime_seconds": snapshot.uptime_seconds,
+                "memory": snapshot.memory,
+                "anomalies": snapshot.anomalies,
+            }),
+        )?;
+        for disk in &snapshot.disks {
+            line("disk", timestamp, to_json(disk)?)?;
+        }
+        for network in &snapshot.networks {
+            line("network", timestamp, to_json(network)?)?;
+        }
+    }
+    for process in view.processes.iter().flatten() {
+        line("process", timestamp, to_json(*process)?)?;
+    }
+    for series in view.history.iter().flatten() {
+        for &(at, value) in &series.points {
+            line(
+                "history",
+                at,
+                serde_json::json!({ "series": series.name, "unit": series.unit, "value": value }),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+const CSV_HEADER: [&str; 12] = [
+    "Type",
+    "Name",
+    "CPU Usage %",
+    "Memory MB",
+    "PIDs",
+    "Disk Used GB",
+    "Disk Total GB",
+    "Full In Hours",
+    "RX KB/s",
+    "TX KB/s",
+    "Timestamp",
+    "Value",
+];
+
+/// Text cell, neutralised so spreadsheets don't evaluate it as a
+/// formula.
+pub(crate) fn text_cell(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn write_csv(view: &View, out: &mut impl Write) -> Result<(), DashboardError> {
+    let mut writer = csv::Writer::from_writer(out);
+    writer.write_record(CSV_HEADER).map_err(failed)?;
+    // Each row names its type and subject, then fills the columns (by
+    // index into `CSV_HEADER`) that apply to it.
+    let mut row = |kind: &str, name: &str, cells: &[(usize, String)]| {
+        let mut record = vec![String::new(); CSV_HEADER.len()];
+        record[0] = kind.to_string();
+        record[1] = text_cell(name);
+        for (column, value) in cells {
+            record[*column] = value.clone();
+        }
+        writer.write_record(&record).map_err(failed)
+    };
+    let at = (10, format!("{:.3}", view.data.timestamp));
+
+    if view.system {
+        let snapshot = &view.data.snapshot;
+        let memory = &snapshot.memory;
+        let (load1, load5, load15) = snapshot.load_average;
+        let cpu = (2, format!("{:.2}", snapshot.cpu_usage));
+        row("System", "CPU", &[cpu, at.clone()])?;
+        let mb = |bytes: u64| (3, format!("{:.0}", bytes as f64 / MIB));
+        row("System", "Memory", &[mb(memory.used), at.clone()])?;
+        row("System", "Swap", &[mb(memory.swap_used), at.clone()])?;
+        for (label, load) in [("Load 1m", load1), ("Load 5m", load5), ("Load 15m", load15)] {
+            row("System", label, &[(11, format!("{:.2}", load)), at.clone()])?;
+        }
+        let uptime = (11, snapshot.uptime_seconds.to_string());
+        row("System", "Uptime Seconds", &[uptime, at.clone()])?;
+        for disk in &snapshot.disks {
+            let full_in = disk
+                .full_in_seconds
+                .map(|s| format!("{:.1}", s / 3600.0))
+                .unwrap_or_default();
+            row(
+                "Disk",
+                &disk.mount_point,
+                &[
+                    (5, format!("{:.2}", disk.used as f64 / GIB)),
+                    (6, format!("{:.2}", disk.total as f64 / GIB)),
+                    (7, full_in),
+                    at.clone(),
+                ],
+            )?;
+        }
+        for network in &snapshot.networks {
+            row(
+                "Network",
+                &network.interface,
+                &[
+                    (8, format!("{:.2}", network.rx_rate / 1024.0)),
+                    (9, format!("{:.2}", network.tx_rate / 1024.0)),
+                    at.clone(),
+                ],
+            )?;
+        }
+    }
+    for p in view.processes.iter().flatten() {
+        let pids: Vec<String> = p.pids.iter().map(u32::to_string).collect();
+        row(
+            "Process",
+            &p.name,
+            &[
+                (2, format!("{:.2}", p.cpu_usage)),
+                (3, format!("{:.0}", p.memory_usage as f64 / MIB)),
+                (4, pids.join(";")),
+                (8, format!("{:.2}", p.net_rx_rate / 1024.0)),
+                (9, format!("{:.2}", p.net_tx_rate / 1024.0)),
+                at.clone(),
+            ],
+        )?;
+    }
+    for series in view.history.iter().flatten() {
+        let name = if series.unit.is_empty() {
+            series.name.to_string()
+        } else {
+            format!("{} ({})", series.name, series.unit)
+        };
+        for &(t, value) in &series.points {
+            row(
+                "History",
+                &name,
+                &[(10, format!("{:.3}", t)), (11, value.to_string())],
+            )?;
+        }
+    }
+    writer.flush().map_err(failed)
+}
+
+/// A titled table in a Markdown/HTML report.
+pub(crate) struct Table {
+    pub(crate) title: String,
+    pub(crate) headers: Vec<&'static str>,
+    pub(crate) rows: Vec<Vec<String>>,
+}
+
+fn report_tables(view: &View) -> (String, Vec<Table>) {
+    let data = view.data;
+    let host = &data.host;
+    let snapshot = &data.snapshot;
+    let title = if host.hostname.is_empty() {
+        "System report".to_string()
+    } else {
+        format!("System report: {}", host.hostname)
+    };
+    let mut tables = Vec::new();
+    let mut summary = vec![vec!["Generated".to_string(), format_utc(data.timestamp)]];
+
+    if view.system {
+        let memory = &snapshot.memory;
+        let (load1, load5, load15) = snapshot.load_average;
+        summary.extend([
+            vec![
+                "OS".to_string(),
+                format!("{} {}", host.os, host.os_version)
+                    .trim()
+                    .to_string(),
+            ],
+            vec!["Kernel".to_string(), host.kernel_version.clone()],
+            vec![
+                "CPU".to_string(),
+                format!("{} ({} cores)", host.cpu_brand, host.cpu_count),
+            ],
+            vec![
+                "Uptime".to_string(),
+                format_duration(snapshot.uptime_seconds as f64),
+            ],
+            vec![
+                "CPU usage".to_string(),
+                format!("{:.1}%", snapshot.cpu_usage),
+            ],
+            vec![
+                "Load average".to_string(),
+                format!("{:.2} {:.2} {:.2}", load1, load5, load15),
+            ],
+            vec![
+                "Memory".to_string(),
+                format!(
+                    "{} of {}",
+                    format_bytes(memory.used),
+                    format_bytes(memory.total)
+                ),
+            ],
+            vec![
+                "Swap".to_string(),
+                format!(
+                    "{} of {}",
+                    format_bytes(memory.swap_used),
+                    format_bytes(memory.swap_total)
+                ),
+            ],
+        ]);
+        if !snapshot.anomalies.is_empty() {
+            summary.push(vec!["Anomalous".to_string(), snapshot.anomalies.join(", ")]);
+        }
+    }
+    tables.push(Table {
+        title: "Summary".to_string(),
+        headers: vec!["", ""],
+        rows: summary,
+    });
+
+    if view.system {
+        tables.push(Table {
+            title: "Disks".to_string(),
+            headers: vec![
+                "Mount",
+                "Device",
+                "Filesystem",
+                "Used",
+                "Total",
+                "Used %",
+                "Full in",
+            ],
+            rows: snapshot
+                .disks
+                .iter()
+                .map(|d| {
+                    let pct = if d.total > 0 {
+                        d.used as f64 / d.total as f64 * 100.0
+                    } else {
+                        0.0
+                    };
+                    vec![
+                        d.mount_point.clone(),
+                        d.name.clone(),
+                        d.filesystem.clone(),
+                        format_bytes(d.used),
+                        format_bytes(d.total),
+                        format!("{:.1}%", pct),
+                        d.full_in_seconds.map(format_duration).unwrap_or_default(),
+                    ]
+                })
+                .collect(),
+        });
+        tables.push(Table {
+            title: "Networks".to_string(),
+            headers: vec!["Interface", "State", "RX/s", "TX/s", "Addresses"],
+            rows: snapshot
+                .networks
+                .iter()
+                .map(|n| {
+                    vec![
+                        n.interface.clone(),
+                        format!("{:?}", n.link_state).to_lowercase(),
+                        format_bytes(n.rx_rate as u64),
+                        format_bytes(n.tx_rate as u64),
+                        n.ipv4.join(", "),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    if let Some(processes) = &view.processes {
+        tables.push(Table {
+            title: "Processes".to_string(),
+            headers: vec!["Name", "PIDs", "CPU %", "Memory", "Net/s"],
+            rows: processes
+                .iter()
+                .map(|p| {
+                    vec![
+                        p.name.clone(),
+                        p.pids.len().to_string(),
+                        format!("{:.1}", p.cpu_usage),
+                        format_bytes(p.memory_usage),
+                        format_bytes((p.net_rx_rate + p.net_tx_rate) as u64),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    if let Some(history) = view.history.as_ref().filter(|h| !h.is_empty()) {
+        tables.push(Table {
+            title: "History".to_string(),
+            headers: vec!["Series", "Points", "From", "To", "Min", "Mean", "Max"],
+            rows: history
+                .iter()
+                .map(|series| {
+                    let values = series.points.iter().map(|&(_, v)| v);
+                    let count = series.points.len();
+                    let (min, max, sum) = values.fold(
+                        (f64::INFINITY, f64::NEG_INFINITY, 0.0),
+                        |(min, max, sum), v| (min.min(v), max.max(v), sum + v),
+                    );
+                    let stat = |v: f64| {
+                        if count == 0 {
+                            String::new()
+                        } else {
+                            format!("{:.2} {}", v, series.unit).trim_end().to_string()
+                        }
+                    };
+                    let at =
+                        |i: Option<&(f64, f64)>| i.map(|&(t, _)| format_utc(t)).unwrap_or_default();
+                    vec![
+                        series.name.to_string(),
+                        count.to_string(),
+                        at(series.points.first()),
+                        at(series.points.last()),
+                        stat(min),
+                        stat(sum / count.max(1) as f64),
+                        stat(max),
+                    ]
+                })
+                .collect(),
+        });
+    }
+    (title, tables)
+}
+
+pub(crate) fn render_markdown((title, tables): &(String, Vec<Table>)) -> String {
+    let cell = |s: &str| s.replace('|', "\\|").replace('\n', " ");
+    let mut out = format!("# {}\n", cell(title));
+    for table in tables {
+        let _ = write!(out, "\n## {}\n\n", table.title);
+        if table.rows.is_empty() {
+            out.push_str("None.\n");
+            continue;
+        }
+        let _ = writeln!(out, "| {} |", table.headers.join(" | "));
+        let _ = writeln!(out, "|{}", "---|".repeat(table.headers.len()));
+        for row in &table.rows {
+            let cells: Vec<String> = row.iter().map(|c| cell(c)).collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+    }
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+pub(crate) fn render_html((title, tables): &(String, Vec<Table>)) -> String {
+    let title = escape_html(title);
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>\nbody {{ font-family: system-ui, sans-serif; margin: 2em; color: #1d1d1f; }}\n\
+         table {{ border-collapse: collapse; margin-bottom: 1.5em; }}\n\
+         th, td {{ border: 1px solid #d2d2d7; padding: 4px 10px; text-align: left; }}\n\
+         th {{ background: #f5f5f7; }}\n</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+    for table in tables {
+        let _ = writeln!(out, "<h2>{}</h2>", escape_html(&table.title));
+        if table.rows.is_empty() {
+            out.push_str("<p>None.</p>\n");
+            continue;
+        }
+        out.push_str("<table>\n");
+        if table.headers.iter().any(|h| !h.is_empty()) {
+            out.push_str("<tr>");
+            for header in &table.headers {
+                let _ = write!(out, "<th>{}</th>", escape_html(header));
+            }
+            out.push_str("</tr>\n");
+        }
+        for row in &table.rows {
+            out.push_str("<tr>");
+            for value in row {
+                let _ = write!(out, "<td>{}</td>", escape_html(value));
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// "2d 3h", "4h 12m", "7m".
+fn format_duration(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as u64;
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
+
+/// Unix seconds as `YYYY-MM-DD HH:MM:SS UTC`.
+pub(crate) fn format_utc(timestamp: f64) -> String {
+    let [year, month, day, hour, minute, second] = utc_fields(timestamp);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year, month, day, hour, minute, second
+    )
+}
+
+/// Unix seconds as `[year, month, day, hour, minute, second]` in UTC.
+pub(crate) fn utc_fields(timestamp: f64) -> [i64; 6] {
+    let secs = timestamp.max(0.0) as i64;
+    let (days, rem) = (secs.div_euclid(86400), secs.rem_euclid(86400));
+    // Civil-from-days, after Howard Hinnant's date algorithms.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    [year, month, day, rem / 3600, rem / 60 % 60, rem % 60]
+}
@@ -0,0 +1,935 @@
+//! A small expression language for selecting processes or disks, e.g.
+//! `user == "postgres" && cpu > 20 && cmd ~ "worker"`.
+//!
+//! Process fields, evaluated against a combined process group:
+//!
+//! | Field                  | Type   | Meaning                                  |
+//! |------------------------|--------|------------------------------------------|
+//! | `name`                 | string | Group (process) name                     |
+//! | `cmd`, `cmdline`       | string | Command line of any PID in the group     |
+//! | `user`                 | string | Owner of any PID in the group            |
+//! | `pid`                  | number | Any PID in the group                     |
+//! | `pids`                 | number | Number of PIDs                           |
+//! | `cpu`                  | number | Summed CPU usage, percent                |
+//! | `mem`, `memory`        | number | Summed memory, bytes                     |
+//! | `rx`, `tx`, `net`      | number | Estimated network rate, bytes/sec        |
+//! | `conns`, `connections` | number | Open TCP/UDP connections                 |
+//! | `mem_growth`           | number | Memory trend, bytes/hour (`leaks`)       |
+//! | `leak`                 | flag   | Flagged as a suspected leak              |
+//!
+//! Disk fields, evaluated against a mount (`snapshot::DiskInfo`):
+//!
+//! | Field                  | Type   | Meaning                                  |
+//! |------------------------|--------|------------------------------------------|
+//! | `mount`                | string | Mount point                              |
+//! | `device`               | string | Device name                              |
+//! | `fs`                   | string | File system                              |
+//! | `used`, `avail`, `size`| number | Space, bytes                             |
+//! | `used_pct`             | number | Used space, percent                      |
+//! | `full_in`              | number | Seconds until full (`forecast`)          |
+//! | `disk_growth`          | number | Usage trend, bytes/hour                  |
+//!
+//! A filter tests either processes or disks, never both; see `Scope`.
+//! A mount that isn't filling up is never full, so `full_in < 3d` skips
+//! it.
+//!
+//! `anomaly(<series>)` is true while the latest point of a system-wide
+//! series is anomalous (see `anomaly::SYSTEM_SERIES`), e.g.
+//! `anomaly(cpu_usage) && cpu > 50`. It works in either scope, or on
+//! its own.
+//!
+//! Comparisons are `==`, `!=`, `<`, `<=`, `>`, `>=`, plus `~` and `!~` for
+//! regex matches on string fields. Numbers take an optional binary unit
+//! (`512K`, `1.5GB`, `2GiB`), `%`, or a time unit (`90s`, `30min`, `12h`,
+//! `3d`, `2w`); `m` means MiB, not minutes. Strings are quoted with `"` or `'`;
+//! a bare word is accepted as a string too. Combine with `&&`/`and`,
+//! `||`/`or`, `!`/`not` and parentheses. Flags stand alone: `leak`,
+//! `!leak`. For "any PID" fields, `!=` and
+//! `!~` mean no PID matches.
+
+use crate::anomaly::SYSTEM_SERIES;
+use crate::error::DashboardError;
+use crate::snapshot::DiskInfo;
+use crate::system::{CombinedProcess, SystemMonitor};
+use regex::Regex;
+use std::fmt;
+
+/// What a filter selects, decided by the fields it uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Processes,
+    Disks,
+    /// Only system-wide terms like `anomaly(cpu_usage)`; the filter
+    /// matches every process and disk or none.
+    System,
+}
+
+impl Scope {
+    fn noun(self) -> &'static str {
+        match self {
+            Scope::Processes => "process",
+            Scope::Disks => "disk",
+            Scope::System => "system",
+        }
+    }
+}
+
+/// Fields a filter can test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Cmdline,
+    User,
+    Pid,
+    Pids,
+    Cpu,
+    Memory,
+    Rx,
+    Tx,
+    Net,
+    Connections,
+    MemGrowth,
+    Leak,
+    Mount,
+    Device,
+    Filesystem,
+    Used,
+    Available,
+    Size,
+    UsedPct,
+    FullIn,
+    DiskGrowth,
+}
+
+const FIELD_NAMES: &str = "name, cmd, user, pid, pids, cpu, mem, rx, tx, net, conns, mem_growth, \
+     leak; for disks: mount, device, fs, used, avail, size, used_pct, full_in, disk_growth; \
+     or anomaly(<series>)";
+
+impl Field {
+    fn parse(word: &str) -> Option<Field> {
+        Some(match word.to_ascii_lowercase().as_str() {
+            "name" => Field::Name,
+            "cmd" | "cmdline" | "command" => Field::Cmdline,
+            "user" => Field::User,
+            "pid" => Field::Pid,
+            "pids" => Field::Pids,
+            "cpu" => Field::Cpu,
+            "mem" | "memory" => Field::Memory,
+            "rx" => Field::Rx,
+            "tx" => Field::Tx,
+            "net" => Field::Net,
+            "conns" | "connections" => Field::Connections,
+            "mem_growth" => Field::MemGrowth,
+            "leak" => Field::Leak,
+            "mount" => Field::Mount,
+            "device" => Field::Device,
+            "fs" | "filesystem" => Field::Filesystem,
+            "used" => Field::Used,
+            "avail" | "available" => Field::Available,
+            "size" | "total" => Field::Size,
+            "used_pct" => Field::UsedPct,
+            "full_in" => Field::FullIn,
+            "disk_growth" => Field::DiskGrowth,
+            _ => return None,
+        })
+    }
+
+    fn scope(self) -> Scope {
+        match self {
+            Field::Mount
+            | Field::Device
+            | Field::Filesystem
+            | Field::Used
+            | Field::Available
+            | Field::Size
+            | Field::UsedPct
+            | Field::FullIn
+            | Field::DiskGrowth => Scope::Disks,
+            _ => Scope::Processes,
+        }
+    }
+
+    fn is_text(self) -> bool {
+        matches!(
+            self,
+            Field::Name
+                | Field::Cmdline
+                | Field::User
+                | Field::Mount
+                | Field::Device
+                | Field::Filesystem
+        )
+    }
+
+    /// True/false fields, used without a comparison.
+    fn is_flag(self) -> bool {
+        matches!(self, Field::Leak)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Match,
+    NotMatch,
+}
+
+impl CompareOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Match => "~",
+            CompareOp::NotMatch => "!~",
+        }
+    }
+
+    /// `!=` and `!~` are evaluated as the negation of their positive
+    /// form, so "any PID" fields read naturally.
+    fn negated(self) -> bool {
+        matches!(self, CompareOp::Ne | CompareOp::NotMatch)
+    }
+
+    fn compare(self, left: f64, right: f64) -> bool {
+        match self {
+            CompareOp::Eq | CompareOp::Ne => left == right,
+            CompareOp::Lt => left < right,
+            CompareOp::Le => left <= right,
+            CompareOp::Gt => left > right,
+            CompareOp::Ge => left >= right,
+            CompareOp::Match | CompareOp::NotMatch => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Number(f64),
+    Text(String),
+    Pattern(Regex),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    /// `anomaly(series)`.
+    Anomaly(&'static str),
+    Compare {
+        field: Field,
+        op: CompareOp,
+        operand: Operand,
+    },
+}
+
+/// A parsed filter expression. See the module docs for the syntax.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::filter::Filter;
+/// use rust_dashboard_lib::system::SystemMonitor;
+///
+/// let filter = Filter::parse("cpu >= 0 && name ~ '.'").unwrap();
+/// let monitor = SystemMonitor::new();
+/// let matching = monitor
+///     .combined_process_list()
+///     .iter()
+///     .filter(|p| filter.matches(p, &monitor))
+///     .count();
+/// assert_eq!(matching, monitor.combined_process_list().len());
+///
+/// let err = Filter::parse("cpu > ").unwrap_err();
+/// assert!(err.to_string().contains("column 6"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Filter {
+    source: String,
+    expr: Expr,
+    scope: Scope,
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Filter {
+    /// Parse `source`. Errors are `DashboardError::InvalidFilter` with
+    /// the 1-based column of the offending token.
+    pub fn parse(source: &str) -> Result<Filter, DashboardError> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            source,
+            tokens,
+            pos: 0,
+            scope: None,
+            depth: 0,
+        };
+        let expr = parser.or()?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.error_at(
+                token.start,
+                format!("unexpected {} after a complete expression", token.kind),
+            ));
+        }
+        Ok(Filter {
+            source: source.to_string(),
+            expr,
+            scope: parser.scope.unwrap_or(Scope::System),
+        })
+    }
+
+    /// The expression as written.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the filter tests processes or disks.
+    pub fn scope(&self) -> Scope {
+        self.scope
+    }
+
+    /// Whether `process` satisfies the filter. `monitor` supplies the
+    /// per-PID user and command line. Disk filters match no process.
+    pub fn matches(&self, process: &CombinedProcess, monitor: &SystemMonitor) -> bool {
+        self.scope != Scope::Disks
+            && eval(&self.expr, monitor, &|field, op, operand| {
+                compare_process(field, op, operand, process, monitor)
+            })
+    }
+
+    /// Whether the mount `disk` satisfies the filter. Process filters
+    /// match no disk.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_dashboard_lib::filter::Filter;
+    /// use rust_dashboard_lib::snapshot::DiskInfo;
+    /// use rust_dashboard_lib::system::SystemMonitor;
+    ///
+    /// let disk = DiskInfo {
+    ///     name: "sda1".to_string(),
+    ///     filesystem: "ext4".to_string(),
+    ///     mount_point: "/".to_string(),
+    ///     used: 90,
+    ///     available: 10,
+    ///     total: 100,
+    ///     growth_bytes_per_hour: Some(1.0),
+    ///     full_in_seconds: Some(36_000.0),
+    /// };
+    /// let filter = Filter::parse("mount == / && full_in < 1d && used_pct >= 90").unwrap();
+    /// assert!(filter.matches_disk(&disk, &SystemMonitor::new()));
+    /// ```
+    pub fn matches_disk(&self, disk: &DiskInfo, monitor: &SystemMonitor) -> bool {
+        self.scope != Scope::Processes
+            && eval(&self.expr, monitor, &|field, op, operand| {
+                compare_disk(field, op, operand, disk)
+            })
+    }
+
+    /// Whether a `Scope::System` filter holds right now. Filters on
+    /// process or disk fields need a subject and return false.
+    pub fn matches_system(&self, monitor: &SystemMonitor) -> bool {
+        self.scope == Scope::System && eval(&self.expr, monitor, &|_, _, _| false)
+    }
+}
+
+fn eval(
+    expr: &Expr,
+    monitor: &SystemMonitor,
+    compare: &dyn Fn(Field, CompareOp, &Operand) -> bool,
+) -> bool {
+    match expr {
+        Expr::And(a, b) => eval(a, monitor, compare) && eval(b, monitor, compare),
+        Expr::Or(a, b) => eval(a, monitor, compare) || eval(b, monitor, compare),
+        Expr::Not(e) => !eval(e, monitor, compare),
+        Expr::Anomaly(series) => monitor.anomalies.is_anomalous(series),
+        Expr::Compare { field, op, operand } => compare(*field, *op, operand),
+    }
+}
+
+fn compare_process(
+    field: Field,
+    op: CompareOp,
+    operand: &Operand,
+    p: &CombinedProcess,
+    monitor: &SystemMonitor,
+) -> bool {
+    let positive = match (field, operand) {
+        (Field::Name, _) => text_matches(&p.name, operand),
+        (Field::User, _) => p
+            .pids
+            .iter()
+            .filter_map(|&pid| monitor.process_user(pid))
+            .any(|user| text_matches(user, operand)),
+        (Field::Cmdline, _) => p
+            .pids
+            .iter()
+            .filter_map(|&pid| monitor.command_line(pid))
+            .any(|cmd| text_matches(&cmd, operand)),
+        (Field::Pid, Operand::Number(n)) => {
+            let cmp = if op.negated() { CompareOp::Eq } else { op };
+            p.pids.iter().any(|&pid| cmp.compare(pid as f64, *n))
+        }
+        (_, Operand::Number(n)) => {
+            let value = match field {
+                Field::Pids => p.pids.len() as f64,
+                Field::Cpu => p.cpu_usage as f64,
+                Field::Memory => p.memory_usage as f64,
+                Field::Rx => p.net_rx_rate,
+                Field::Tx => p.net_tx_rate,
+                Field::Net => p.net_rx_rate + p.net_tx_rate,
+                Field::MemGrowth => monitor.leaks.growth_rate(&p.name).unwrap_or(0.0),
+                Field::Leak => monitor.leaks.suspect(&p.name).is_some() as u8 as f64,
+                Field::Connections => p.connections as f64,
+                _ => return false,
+            };
+            op.compare(value, *n)
+        }
+        _ => false,
+    };
+    positive != op.negated()
+}
+
+fn compare_disk(field: Field, op: CompareOp, operand: &Operand, disk: &DiskInfo) -> bool {
+    let positive = match (field, operand) {
+        (Field::Mount, _) => text_matches(&disk.mount_point, operand),
+        (Field::Device, _) => text_matches(&disk.name, operand),
+        (Field::Filesystem, _) => text_matches(&disk.filesystem, operand),
+        (_, Operand::Number(n)) => {
+            let value = match field {
+                Field::Used => disk.used as f64,
+                Field::Available => disk.available as f64,
+                Field::Size => disk.total as f64,
+                Field::UsedPct if disk.total > 0 => disk.used as f64 / disk.total as f64 * 100.0,
+                Field::UsedPct => 0.0,
+                Field::FullIn => disk.full_in_seconds.unwrap_or(f64::INFINITY),
+                Field::DiskGrowth => disk.growth_bytes_per_hour.unwrap_or(0.0),
+                _ => return false,
+            };
+            op.compare(value, *n)
+        }
+        _ => false,
+    };
+    positive != op.negated()
+}
+
+fn text_matches(value: &str, operand: &Operand) -> bool {
+    match operand {
+        Operand::Text(text) => value == text,
+        Operand::Pattern(re) => re.is_match(value),
+        Operand::Number(_) => false,
+    }
+}
+
+/// regex's syntax errors span several lines with their own caret; keep
+/// just the final "error: ..." line, since ours points at the string.
+fn regex_error(err: &regex::Error) -> String {
+    let text = err.to_string();
+    let last = text.lines().last().unwrap_or_default();
+    format!("invalid regex: {}", last.trim_start_matches("error: "))
+}
+
+// --- Tokens ---
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Word(String),
+    Str(String),
+    Number(f64),
+    Op(CompareOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::Word(w) => write!(f, "`{}`", w),
+            TokenKind::Str(s) => write!(f, "string \"{}\"", s),
+            TokenKind::Number(n) => write!(f, "number {}", n),
+            TokenKind::Op(op) => write!(f, "`{}`", op.symbol()),
+            TokenKind::And => f.write_str("`&&`"),
+            TokenKind::Or => f.write_str("`||`"),
+            TokenKind::Not => f.write_str("`!`"),
+            TokenKind::LParen => f.write_str("`(`"),
+            TokenKind::RParen => f.write_str("`)`"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// Byte offset into the source.
+    start: usize,
+}
+
+fn column(source: &str, byte: usize) -> usize {
+    source[..byte.min(source.len())].chars().count() + 1
+}
+
+fn invalid(source: &str, byte: usize, message: impl Into<String>) -> DashboardError {
+    DashboardError::InvalidFilter {
+        column: column(source, byte),
+        message: message.into(),
+    }
+}
+
+/// Multiplier for a number suffix, binary for byte units and seconds
+/// for time units.
+fn unit_multiplier(unit: &str) -> Option<f64> {
+    Some(match unit.to_ascii_lowercase().as_str() {
+        "" | "b" | "%" | "s" | "sec" => 1.0,
+        "min" => 60.0,
+        "h" | "hr" => 3600.0,
+        "d" => 86400.0,
+        "w" => 7.0 * 86400.0,
+        "k" | "kb" | "kib" => 1024.0,
+        "m" | "mb" | "mib" => 1024.0 * 1024.0,
+        "g" | "gb" | "gib" => 1024.0 * 1024.0 * 1024.0,
+        "t" | "tb" | "tib" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    })
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, DashboardError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let two: String = source[start..].chars().take(2).collect();
+        let (kind, len) = match two.as_str() {
+            "&&" => (TokenKind::And, 2),
+            "||" => (TokenKind::Or, 2),
+            "==" => (TokenKind::Op(CompareOp::Eq), 2),
+            "!=" => (TokenKind::Op(CompareOp::Ne), 2),
+            "<=" => (TokenKind::Op(CompareOp::Le), 2),
+            ">=" => (TokenKind::Op(CompareOp::Ge), 2),
+            "!~" => (TokenKind::Op(CompareOp::NotMatch), 2),
+            _ => match c {
+                '=' => (TokenKind::Op(CompareOp::Eq), 1),
+                '<' => (TokenKind::Op(CompareOp::Lt), 1),
+                '>' => (TokenKind::Op(CompareOp::Gt), 1),
+                '~' => (TokenKind::Op(CompareOp::Match), 1),
+                '!' => (TokenKind::Not, 1),
+                '(' => (TokenKind::LParen, 1),
+                ')' => (TokenKind::RParen, 1),
+                '&' | '|' => {
+                    return Err(invalid(
+                        source,
+                        start,
+                        format!("`{}` on its own isn't an operator; use `{}{}`", c, c, c),
+                    ))
+                }
+                '"' | '\'' => {
+                    chars.next();
+                    let mut text = String::new();
+                    let mut closed = false;
+                    while let Some((_, ch)) = chars.next() {
+                        match ch {
+                            '\\' => match chars.next() {
+                                Some((_, escaped)) => text.push(escaped),
+                                None => break,
+                            },
+                            ch if ch == c => {
+                                closed = true;
+                                break;
+                            }
+                            ch => text.push(ch),
+                        }
+                    }
+                    if !closed {
+                        return Err(invalid(source, start, "unterminated string"));
+                    }
+                    tokens.push(Token {
+                        kind: TokenKind::Str(text),
+                        start,
+                    });
+                    continue;
+                }
+                c if c.is_ascii_digit() || c == '.' => {
+                    let mut end = start;
+                    while let Some(&(i, ch)) = chars.peek() {
+                        if ch.is_ascii_digit() || ch == '.' {
+                            end = i + ch.len_utf8();
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    let digits = &source[start..end];
+                    let number: f64 = digits
+                        .parse()
+                        .map_err(|_| invalid(source, start, format!("bad number `{}`", digits)))?;
+                    let unit_start = end;
+                    while let Some(&(i, ch)) = chars.peek() {
+                        if ch.is_ascii_alphabetic() || ch == '%' {
+                            end = i + ch.len_utf8();
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    let unit = &source[unit_start..end];
+                    let multiplier = unit_multiplier(unit).ok_or_else(|| {
+                        invalid(
+                            source,
+                            unit_start,
+                            format!(
+                                "unknown unit `{}` (use K, M, G, T, %, s, min, h, d or w)",
+                                unit
+                            ),
+                        )
+                    })?;
+                    tokens.push(Token {
+                        kind: TokenKind::Number(number * multiplier),
+                        start,
+                    });
+                    continue;
+                }
+                c if c.is_alphanumeric() || c == '_' || c == '-' || c == '/' => {
+                    let mut end = start;
+                    while let Some(&(i, ch)) = chars.peek() {
+                        if ch.is_alphanumeric() || matches!(ch, '_' | '-' | '/' | '.' | ':') {
+                            end = i + ch.len_utf8();
+                            chars.next();
+                        } else {
+                            break;
+                        }
+                    }
+                    let word = &source[start..end];
+                    let kind = match word.to_ascii_lowercase().as_str() {
+                        "and" => TokenKind::And,
+                        "or" => TokenKind::Or,
+                        "not" => TokenKind::Not,
+                        _ => TokenKind::Word(word.to_string()),
+                    };
+                    tokens.push(Token { kind, start });
+                    continue;
+                }
+                other => {
+                    return Err(invalid(
+                        source,
+                        start,
+                        format!("unexpected character `{}`", other),
+                    ))
+                }
+            },
+        };
+        for _ in 0..len {
+            chars.next();
+        }
+        tokens.push(Token { kind, start });
+    }
+    Ok(tokens)
+}
+
+// --- Parser ---
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Scope of the first field seen; later fields must share it.
+    scope: Option<Scope>,
+    /// `!` and `(` currently open around the parse position.
+    depth: usize,
+}
+
+/// Deepest `!`/`(` nesting a filter may have. The parser recurses once
+/// per level, so unbounded nesting would overflow the stack.
+const MAX_NESTING: usize = 64;
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn error_at(&self, byte: usize, message: impl Into<String>) -> DashboardError {
+        invalid(self.source, byte, message)
+    }
+
+    fn error_at_end(&self, message: impl Into<String>) -> DashboardError {
+        invalid(self.source, self.source.trim_end().len(), message)
+    }
+
+    /// Parse one nested level opened by the token at `start`.
+    fn nested<T>(
+        &mut self,
+        start: usize,
+        parse: impl FnOnce(&mut Self) -> Result<T, DashboardError>,
+    ) -> Result<T, DashboardError> {
+        if self.depth >= MAX_NESTING {
+            return Err(self.error_at(
+                start,
+                format!("filters can nest `!` and `(` at most {} deep", MAX_NESTING),
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn or(&mut self) -> Result<Expr, DashboardError> {
+        let mut left = self.and()?;
+        while matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::Or,
+                ..
+            })
+        ) {
+            self.next();
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, DashboardError> {
+        let mut left = self.unary()?;
+        while matches!(
+            self.peek(),
+            Some(Token {
+                kind: TokenKind::And,
+                ..
+            })
+        ) {
+            self.next();
+            left = Expr::And(Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, DashboardError> {
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::Not,
+                start,
+            }) => Ok(Expr::Not(Box::new(self.nested(start, Self::unary)?))),
+            Some(Token {
+                kind: TokenKind::LParen,
+                start,
+            }) => {
+                let inner = self.nested(start, Self::or)?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(inner),
+                    Some(token) => Err(self.error_at(
+                        token.start,
+                        format!(
+                            "expected `)` to close the `(` at column {}, found {}",
+                            column(self.source, start),
+                            token.kind
+                        ),
+                    )),
+                    None => Err(self.error_at(start, "unclosed `(`")),
+                }
+            }
+            Some(Token {
+                kind: TokenKind::Word(word),
+                start,
+            }) if word.eq_ignore_ascii_case("anomaly") => self.anomaly(start),
+            Some(Token {
+                kind: TokenKind::Word(word),
+                start,
+            }) => self.comparison(&word, start),
+            Some(token) => Err(self.error_at(
+                token.start,
+                format!("expected a field ({}), found {}", FIELD_NAMES, token.kind),
+            )),
+            None => Err(self.error_at_end(format!(
+                "expected a field ({}) but the filter ended",
+                FIELD_NAMES
+            ))),
+        }
+    }
+
+    /// `anomaly(series)`, after the `anomaly` word at `start`.
+    fn anomaly(&mut self, start: usize) -> Result<Expr, DashboardError> {
+        let series_names = SYSTEM_SERIES.join(", ");
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::LParen,
+                ..
+            }) => {}
+            Some(token) => {
+                return Err(self.error_at(
+                    token.start,
+                    format!("expected `(` after `anomaly`, found {}", token.kind),
+                ))
+            }
+            None => return Err(self.error_at_end("expected `(` after `anomaly`")),
+        }
+        let series = match self.next() {
+            Some(Token {
+                kind: TokenKind::Word(name) | TokenKind::Str(name),
+                start,
+            }) => SYSTEM_SERIES
+                .iter()
+                .copied()
+                .find(|s| s.eq_ignore_ascii_case(&name))
+                .ok_or_else(|| {
+                    self.error_at(
+                        start,
+                        format!("unknown series `{}` (series: {})", name, series_names),
+                    )
+                })?,
+            Some(token) => {
+                return Err(self.error_at(
+                    token.start,
+                    format!("expected a series ({}), found {}", series_names, token.kind),
+                ))
+            }
+            None => {
+                return Err(self.error_at_end(format!(
+                    "expected a series ({}) but the filter ended",
+                    series_names
+                )))
+            }
+        };
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::RParen,
+                ..
+            }) => Ok(Expr::Anomaly(series)),
+            Some(token) => Err(self.error_at(
+                token.start,
+                format!("expected `)` to close `anomaly(`, found {}", token.kind),
+            )),
+            None => Err(self.error_at(start, "unclosed `anomaly(`")),
+        }
+    }
+
+    fn comparison(&mut self, word: &str, start: usize) -> Result<Expr, DashboardError> {
+        let field = Field::parse(word).ok_or_else(|| {
+            self.error_at(
+                start,
+                format!("unknown field `{}` (fields: {})", word, FIELD_NAMES),
+            )
+        })?;
+        let scope = *self.scope.get_or_insert(field.scope());
+        if field.scope() != scope {
+            return Err(self.error_at(
+                start,
+                format!(
+                    "`{}` is a {} field, but this filter already tests {} fields",
+                    word,
+                    field.scope().noun(),
+                    scope.noun()
+                ),
+            ));
+        }
+        if field.is_flag() {
+            if let Some(Token {
+                kind: TokenKind::Op(_),
+                start,
+            }) = self.peek()
+            {
+                return Err(self.error_at(
+                    *start,
+                    format!(
+                        "`{}` is a flag; use `{}` or `!{}` without a comparison",
+                        word, word, word
+                    ),
+                ));
+            }
+            return Ok(Expr::Compare {
+                field,
+                op: CompareOp::Eq,
+                operand: Operand::Number(1.0),
+            });
+        }
+        let (op, op_start) = match self.next() {
+            Some(Token {
+                kind: TokenKind::Op(op),
+                start,
+            }) => (op, start),
+            Some(token) => {
+                return Err(self.error_at(
+                    token.start,
+                    format!(
+                        "expected a comparison after `{}`, found {}",
+                        word, token.kind
+                    ),
+                ))
+            }
+            None => {
+                return Err(self.error_at_end(format!(
+                    "expected a comparison (==, !=, <, >, ~ ...) after `{}`",
+                    word
+                )))
+            }
+        };
+
+        let value = self.next().ok_or_else(|| {
+            self.error_at_end(format!("expected a value after `{}`", op.symbol()))
+        })?;
+        let operand = match (field.is_text(), op, value.kind) {
+            (
+                true,
+                CompareOp::Match | CompareOp::NotMatch,
+                TokenKind::Str(p) | TokenKind::Word(p),
+            ) => Operand::Pattern(
+                Regex::new(&p).map_err(|e| self.error_at(value.start, regex_error(&e)))?,
+            ),
+            (true, CompareOp::Eq | CompareOp::Ne, TokenKind::Str(s) | TokenKind::Word(s)) => {
+                Operand::Text(s)
+            }
+            (true, CompareOp::Eq | CompareOp::Ne, TokenKind::Number(n)) => {
+                // `user == 1000`-style bare numbers compare as text.
+                Operand::Text(n.to_string())
+            }
+            (true, _, _) => {
+                return Err(self.error_at(
+                    op_start,
+                    format!("`{}` is text; compare it with ==, !=, ~ or !~", word),
+                ))
+            }
+            (false, CompareOp::Match | CompareOp::NotMatch, _) => {
+                return Err(self.error_at(
+                    op_start,
+                    format!(
+                        "`{}` is a number; `{}` only works on text fields",
+                        word,
+                        op.symbol()
+                    ),
+                ))
+            }
+            (false, _, TokenKind::Number(n)) => Operand::Number(n),
+            (false, _, kind) => {
+                return Err(self.error_at(
+                    value.start,
+                    format!("`{}` is a number; expected a number, found {}", word, kind),
+                ))
+            }
+        };
+        Ok(Expr::Compare { field, op, operand })
+    }
+}
@@ -0,0 +1,178 @@
+use crate::stats::trend;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+const SECONDS_PER_HOUR: f64 = 3600.0;
+
+/// How disk usage is sampled and when a trend is trusted enough to
+/// forecast from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForecastConfig {
+    pub enabled: bool,
+    /// Seconds between usage samples. Disks only refresh every minute
+    /// by default, so samples are taken at the first disk refresh after
+    /// this has passed.
+    pub sample_interval_seconds: u64,
+    /// Sliding window the trend is fitted over.
+    pub window_hours: u64,
+    /// History a mount needs before it gets a forecast.
+    pub min_history_minutes: u64,
+    /// How well a straight line has to explain the samples (R², 0–1).
+    /// Disks that fill and get cleaned up in bursts score low.
+    pub min_r_squared: f64,
+}
+
+impl Default for ForecastConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sample_interval_seconds: 300,
+            window_hours: 24,
+            min_history_minutes: 30,
+            min_r_squared: 0.5,
+        }
+    }
+}
+
+/// Where a mount's usage is heading.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DiskForecast {
+    pub mount_point: String,
+    /// Usage trend in bytes per hour; negative while the disk is
+    /// emptying.
+    pub growth_bytes_per_hour: f64,
+    /// Seconds until the disk is full at the current trend. `None` when
+    /// usage isn't growing or the trend is too noisy to extrapolate.
+    pub seconds_until_full: Option<f64>,
+    pub r_squared: f64,
+    /// Seconds of history the fit covers.
+    pub window_seconds: f64,
+}
+
+/// Samples used space per mount at a coarse interval and fits a robust
+/// trend, extrapolating to when the mount runs out of space.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::forecast::{DiskForecaster, ForecastConfig};
+///
+/// const GIB: u64 = 1024 * 1024 * 1024;
+/// let mut forecaster = DiskForecaster::new(ForecastConfig::default());
+/// for hour in 0..4u64 {
+///     // 100 GiB disk gaining 1 GiB an hour.
+///     let used = (50 + hour) * GIB;
+///     let disk = ("sda1".to_string(), "ext4".to_string(), "/".to_string(), used, 100 * GIB - used, 100 * GIB);
+///     forecaster.sample(&[disk], hour as f64 * 3600.0);
+/// }
+/// let forecast = forecaster.forecast("/").unwrap();
+/// let hours = forecast.seconds_until_full.unwrap() / 3600.0;
+/// assert!((hours - 47.0).abs() < 0.01);
+/// ```
+#[derive(Debug, Default)]
+pub struct DiskForecaster {
+    config: ForecastConfig,
+    series: HashMap<String, VecDeque<(f64, u64)>>,
+    forecasts: HashMap<String, DiskForecast>,
+    last_sample: Option<f64>,
+}
+
+impl DiskForecaster {
+    pub fn new(config: ForecastConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> &ForecastConfig {
+        &self.config
+    }
+
+    /// Change settings. History is kept unless forecasting is turned
+    /// off.
+    pub fn set_config(&mut self, config: ForecastConfig) {
+        if !config.enabled {
+            self.series.clear();
+            self.forecasts.clear();
+        }
+        self.config = config;
+    }
+
+    /// The forecast for `mount_point`, once it has `min_history_minutes`
+    /// of samples.
+    pub fn forecast(&self, mount_point: &str) -> Option<&DiskForecast> {
+        self.forecasts.get(mount_point)
+    }
+
+    /// Every mount with a forecast, soonest full first.
+    pub fn forecasts(&self) -> Vec<&DiskForecast> {
+        let mut all: Vec<&DiskForecast> = self.forecasts.values().collect();
+        all.sort_by(|a, b| {
+            let key = |f: &DiskForecast| f.seconds_until_full.unwrap_or(f64::INFINITY);
+            key(a)
+                .total_cmp(&key(b))
+                .then_with(|| a.mount_point.cmp(&b.mount_point))
+        });
+        all
+    }
+
+    /// Record used space per mount if `sample_interval_seconds` has
+    /// passed since the last sample, then refit. `disks` is
+    /// `SystemMonitor::disk_info` output; `timestamp` is seconds since
+    /// the Unix epoch. Returns whether a sample was taken.
+    pub fn sample(
+        &mut self,
+        disks: &[(String, String, String, u64, u64, u64)],
+        timestamp: f64,
+    ) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+        if let Some(last) = self.last_sample {
+            if timestamp - last < self.config.sample_interval_seconds as f64 {
+                return false;
+            }
+        }
+        self.last_sample = Some(timestamp);
+
+        let window = (self.config.window_hours * 3600) as f64;
+        let min_history = (self.config.min_history_minutes * 60) as f64;
+        // Unmounted disks drop their series.
+        let mut series = HashMap::with_capacity(disks.len());
+        let mut forecasts = HashMap::new();
+        for (_, _, mount_point, used, available, _) in disks {
+            let mut points = self.series.remove(mount_point).unwrap_or_default();
+            points.push_back((timestamp, *used));
+            while points.front().is_some_and(|&(t, _)| timestamp - t > window) {
+                points.pop_front();
+            }
+            let span = points.front().map_or(0.0, |&(t, _)| timestamp - t);
+            if span >= min_history {
+                let xy: Vec<(f64, f64)> = points
+                    .iter()
+                    .map(|&(t, u)| (t - timestamp, u as f64))
+                    .collect();
+                if let Some(fit) = trend(&xy) {
+                    let seconds_until_full = (fit.slope > 0.0
+                        && fit.r_squared >= self.config.min_r_squared)
+                        .then(|| *available as f64 / fit.slope);
+                    forecasts.insert(
+                        mount_point.clone(),
+                        DiskForecast {
+                            mount_point: mount_point.clone(),
+                            growth_bytes_per_hour: fit.slope * SECONDS_PER_HOUR,
+                            seconds_until_full,
+                            r_squared: fit.r_squared,
+                            window_seconds: fit.span,
+                        },
+                    );
+                }
+            }
+            series.insert(mount_point.clone(), points);
+        }
+        self.series = series;
+        self.forecasts = forecasts;
+        true
+    }
+}
@@ -0,0 +1,198 @@
+use crate::stats::{trend, Trend};
+use crate::system::CombinedProcess;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+const SECONDS_PER_HOUR: f64 = 3600.0;
+const MIB: f64 = 1024.0 * 1024.0;
+/// Most groups tracked at once, the largest first. Each refit is
+/// quadratic in the samples in the window, so this keeps a sample's cost
+/// from growing with the number of processes.
+const MAX_GROUPS: usize = 100;
+
+/// Thresholds for flagging a process group as a suspected leak.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeakConfig {
+    pub enabled: bool,
+    /// Seconds between memory samples. Leaks show over hours, so this
+    /// is much coarser than the refresh interval.
+    pub sample_interval_seconds: u64,
+    /// Sliding window the trend is fitted over.
+    pub window_minutes: u64,
+    /// History a group needs before it can be flagged, so short-lived
+    /// processes warming up aren't reported.
+    pub min_history_minutes: u64,
+    /// Growth rate above which a group is flagged, in MiB per hour.
+    pub min_growth_mib_per_hour: f64,
+    /// How well a straight line has to explain the samples (R², 0–1).
+    /// Sawtooth patterns from garbage collection or caches score low.
+    pub min_r_squared: f64,
+    /// Memory a group needs before it's tracked, in MiB. Once tracked it
+    /// stays tracked, however small it gets, until it exits.
+    pub min_memory_mib: f64,
+}
+
+impl Default for LeakConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sample_interval_seconds: 30,
+            window_minutes: 60,
+            min_history_minutes: 20,
+            min_growth_mib_per_hour: 20.0,
+            min_r_squared: 0.8,
+            min_memory_mib: 32.0,
+        }
+    }
+}
+
+/// A process group whose memory has grown steadily over the window.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuspectedLeak {
+    pub name: String,
+    /// Memory at the latest sample, bytes.
+    pub memory: u64,
+    pub growth_bytes_per_hour: f64,
+    pub r_squared: f64,
+    /// Seconds of history the fit covers.
+    pub window_seconds: f64,
+}
+
+/// Samples group memory at a coarse interval and fits a robust trend
+/// per group, flagging groups that grow faster than
+/// `LeakConfig::min_growth_mib_per_hour` with a steady (high R²) line.
+/// Series follow group names, so they survive PID changes. Only groups
+/// above `LeakConfig::min_memory_mib` are tracked, and at most the
+/// largest hundred of those.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::leaks::{LeakConfig, LeakDetector};
+/// use rust_dashboard_lib::system::CombinedProcess;
+///
+/// let mut detector = LeakDetector::new(LeakConfig::default());
+/// for minute in 0..60u64 {
+///     let daemon = CombinedProcess {
+///         name: "leaky".to_string(),
+///         memory_usage: (100 + minute) * 1024 * 1024,
+///         pids: vec![42],
+///         ..Default::default()
+///     };
+///     detector.sample(&[daemon], minute as f64 * 60.0);
+/// }
+/// assert_eq!(detector.suspects()[0].name, "leaky");
+/// ```
+#[derive(Debug, Default)]
+pub struct LeakDetector {
+    config: LeakConfig,
+    series: HashMap<String, VecDeque<(f64, u64)>>,
+    trends: HashMap<String, Trend>,
+    suspects: Vec<SuspectedLeak>,
+    last_sample: Option<f64>,
+}
+
+impl LeakDetector {
+    pub fn new(config: LeakConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    pub fn config(&self) -> &LeakConfig {
+        &self.config
+    }
+
+    /// Change thresholds. History is kept, so new thresholds apply at
+    /// the next sample.
+    pub fn set_config(&mut self, config: LeakConfig) {
+        if !config.enabled {
+            self.series.clear();
+            self.trends.clear();
+            self.suspects.clear();
+        }
+        self.config = config;
+    }
+
+    /// Groups currently flagged, fastest growing first.
+    pub fn suspects(&self) -> &[SuspectedLeak] {
+        &self.suspects
+    }
+
+    pub fn suspect(&self, name: &str) -> Option<&SuspectedLeak> {
+        self.suspects.iter().find(|s| s.name == name)
+    }
+
+    /// Memory growth of a group in bytes per hour, once it has
+    /// `min_history_minutes` of samples.
+    pub fn growth_rate(&self, name: &str) -> Option<f64> {
+        self.trends.get(name).map(|t| t.slope * SECONDS_PER_HOUR)
+    }
+
+    /// Record each group's memory if `sample_interval_seconds` has
+    /// passed since the last sample, then refit. `timestamp` is seconds
+    /// since the Unix epoch. Returns whether a sample was taken.
+    pub fn sample(&mut self, processes: &[CombinedProcess], timestamp: f64) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+        if let Some(last) = self.last_sample {
+            if timestamp - last < self.config.sample_interval_seconds as f64 {
+                return false;
+            }
+        }
+        self.last_sample = Some(timestamp);
+
+        let window = (self.config.window_minutes * 60) as f64;
+        let min_history = (self.config.min_history_minutes * 60) as f64;
+        let floor = self.config.min_memory_mib * MIB;
+        let mut tracked: Vec<&CombinedProcess> = processes
+            .iter()
+            .filter(|p| p.memory_usage as f64 >= floor || self.series.contains_key(&p.name))
+            .collect();
+        if tracked.len() > MAX_GROUPS {
+            tracked.select_nth_unstable_by(MAX_GROUPS, |a, b| b.memory_usage.cmp(&a.memory_usage));
+            tracked.truncate(MAX_GROUPS);
+        }
+        // Groups that exited, or were crowded out, drop their series.
+        let mut series = HashMap::with_capacity(tracked.len());
+        let mut trends = HashMap::new();
+        let mut suspects = Vec::new();
+        for process in tracked {
+            let mut points = self.series.remove(&process.name).unwrap_or_default();
+            points.push_back((timestamp, process.memory_usage));
+            while points.front().is_some_and(|&(t, _)| timestamp - t > window) {
+                points.pop_front();
+            }
+            let span = points.front().map_or(0.0, |&(t, _)| timestamp - t);
+            if span >= min_history {
+                let xy: Vec<(f64, f64)> = points
+                    .iter()
+                    .map(|&(t, m)| (t - timestamp, m as f64))
+                    .collect();
+                if let Some(fit) = trend(&xy) {
+                    let per_hour = fit.slope * SECONDS_PER_HOUR;
+                    if per_hour >= self.config.min_growth_mib_per_hour * MIB
+                        && fit.r_squared >= self.config.min_r_squared
+                    {
+                        suspects.push(SuspectedLeak {
+                            name: process.name.clone(),
+                            memory: process.memory_usage,
+                            growth_bytes_per_hour: per_hour,
+                            r_squared: fit.r_squared,
+                            window_seconds: fit.span,
+                        });
+                    }
+                    trends.insert(process.name.clone(), fit);
+                }
+            }
+            series.insert(process.name.clone(), points);
+        }
+        suspects.sort_by(|a, b| b.growth_bytes_per_hour.total_cmp(&a.growth_bytes_per_hour));
+        self.series = series;
+        self.trends = trends;
+        self.suspects = suspects;
+        true
+    }
+}
@@ -1,3 +1,30 @@
+pub mod actions;
+pub mod adaptive;
+pub mod alerts;
+pub mod anomaly;
+pub mod baseline;
+#[cfg(feature = "columnar")]
+pub mod columnar;
 pub mod config;
+pub mod delta;
 pub mod error;
+pub mod export;
+pub mod filter;
+pub mod forecast;
+pub mod leaks;
+pub mod network;
+#[cfg(feature = "otlp")]
+pub mod otlp;
+pub mod process_index;
+pub mod process_net;
+pub mod push;
+pub mod query;
+pub mod recording;
+pub mod remediation;
+pub mod replay;
+pub mod service;
+pub mod snapshot;
+pub mod sockets;
+pub mod stats;
 pub mod system;
+pub mod watch;
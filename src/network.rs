@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Root of the Linux sysfs network class. Other platforms don't have it;
+/// `read_link_info` then falls back to `LinkInfo::default()`.
+pub const SYSFS_NET_ROOT: &str = "/sys/class/net";
+
+/// Operational state of a network interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkState {
+    Up,
+    Down,
+    /// The kernel doesn't report a state (loopback, some virtual devices,
+    /// or a platform without sysfs).
+    #[default]
+    Unknown,
+}
+
+impl LinkState {
+    /// Parse the contents of `/sys/class/net/<iface>/operstate`.
+    ///
+    /// `dormant` and `lowerlayerdown` are treated as down: the interface
+    /// can't pass traffic in either case, which is what the panel needs
+    /// to show.
+    pub fn from_operstate(s: &str) -> Self {
+        match s.trim() {
+            "up" => LinkState::Up,
+            "down" | "dormant" | "lowerlayerdown" | "notpresent" => LinkState::Down,
+            _ => LinkState::Unknown,
+        }
+    }
+}
+
+/// Link-level information read from sysfs. sysinfo doesn't expose
+/// operstate, speed or drop counters, so these come from the kernel
+/// directly and are cached on `SystemMonitor` at each network refresh.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkInfo {
+    pub link_state: LinkState,
+    /// Negotiated speed in Mbit/s. `None` when the link is down or the
+    /// driver doesn't report one (the kernel returns -1 or EINVAL).
+    pub speed_mbps: Option<u64>,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+/// Full per-interface details for the network panel.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InterfaceDetails {
+    pub interface: String,
+    /// MAC address as `aa:bb:cc:dd:ee:ff`; empty when unspecified
+    /// (loopback, tunnels).
+    pub mac_address: String,
+    /// IPv4 addresses in CIDR notation.
+    pub ipv4: Vec<String>,
+    /// IPv6 addresses in CIDR notation.
+    pub ipv6: Vec<String>,
+    pub mtu: u64,
+    pub link_state: LinkState,
+    pub speed_mbps: Option<u64>,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: u64,
+    pub tx_dropped: u64,
+}
+
+/// Emitted when an interface's link state changes between two network
+/// refreshes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkEvent {
+    pub interface: String,
+    pub previous: LinkState,
+    pub current: LinkState,
+}
+
+/// Read link information for `iface` under a sysfs-style `root`
+/// (normally `SYSFS_NET_ROOT`). Missing or unparseable files fall back
+/// to the `LinkInfo` defaults rather than failing — a virtual device
+/// without a `speed` file is still worth showing.
+pub fn read_link_info(root: &Path, iface: &str) -> LinkInfo {
+    let dir = root.join(iface);
+    let read = |rel: &str| fs::read_to_string(dir.join(rel)).ok();
+    let read_u64 = |rel: &str| read(rel).and_then(|s| s.trim().parse::<u64>().ok());
+
+    let link_state = read("operstate")
+        .map(|s| LinkState::from_operstate(&s))
+        .unwrap_or_default();
+    // `speed` is -1 for links without a negotiated speed, which fails the
+    // u64 parse and lands on None as intended.
+    let speed_mbps = read_u64("speed").filter(|&s| s > 0);
+
+    LinkInfo {
+        link_state,
+        speed_mbps,
+        rx_dropped: read_u64("statistics/rx_dropped").unwrap_or(0),
+        tx_dropped: read_u64("statistics/tx_dropped").unwrap_or(0),
+    }
+}
+
+/// Compare link states from two refreshes and return one event per
+/// interface whose state changed. Interfaces that appear for the first
+/// time are reported as a transition from `Unknown` only if they come
+/// up; interfaces that disappear are reported as going down.
+pub fn diff_link_states(
+    previous: &HashMap<String, LinkState>,
+    current: &HashMap<String, LinkState>,
+) -> Vec<LinkEvent> {
+    let mut events = Vec::new();
+    for (iface, &now) in current {
+        let before = previous.get(iface).copied();
+        match before {
+            Some(before) if before != now => events.push(LinkEvent {
+                interface: iface.clone(),
+                previous: before,
+                current: now,
+            }),
+            None if now == LinkState::Up => events.push(LinkEvent {
+                interface: iface.clone(),
+                previous: LinkState::Unknown,
+                current: now,
+            }),
+            _ => {}
+        }
+    }
+    for (iface, &before) in previous {
+        if !current.contains_key(iface) && before != LinkState::Down {
+            events.push(LinkEvent {
+                interface: iface.clone(),
+                previous: before,
+                current: LinkState::Down,
+            });
+        }
+    }
+    // HashMap iteration order is random; sort so consumers (and tests)
+    // see a stable order.
+    events.sort_by(|a, b| a.interface.cmp(&b.interface));
+    events
+}
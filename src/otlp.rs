@@ -0,0 +1,361 @@
+//! OpenTelemetry metrics over OTLP/HTTP, behind the `otlp` feature.
+//!
+//! Snapshots are mapped to the OTel semantic conventions for host and
+//! process metrics (`system.cpu.utilization`, `system.memory.usage`,
+//! `system.filesystem.usage`, `system.network.io`, `process.memory.usage`,
+//! ...) and sent as an `ExportMetricsServiceRequest` in OTLP's JSON
+//! encoding, which collectors accept on the same `/v1/metrics` endpoint
+//! as protobuf. A `push::PushTarget::Otlp` target sends them on the push
+//! interval.
+
+use crate::push::{busiest, PushConfig};
+use crate::snapshot::{DiskInfo, NetworkInfo, SystemSnapshot};
+use serde_json::{json, Value};
+
+/// Appended to a collector endpoint that doesn't already end with it.
+pub const METRICS_PATH: &str = "/v1/metrics";
+
+/// `AGGREGATION_TEMPORALITY_CUMULATIVE`.
+const CUMULATIVE: u8 = 2;
+
+/// The URL metrics are posted to: `endpoint` itself if it already ends
+/// with `/v1/metrics`, like `OTEL_EXPORTER_OTLP_METRICS_ENDPOINT`, or
+/// with it appended, like `OTEL_EXPORTER_OTLP_ENDPOINT`.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::otlp::metrics_url;
+///
+/// assert_eq!(metrics_url("http://localhost:4318"), "http://localhost:4318/v1/metrics");
+/// assert_eq!(metrics_url("http://c:4318/v1/metrics"), "http://c:4318/v1/metrics");
+/// ```
+pub fn metrics_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim_end_matches('/');
+    match endpoint.ends_with(METRICS_PATH) {
+        true => endpoint.to_string(),
+        false => format!("{}{}", endpoint, METRICS_PATH),
+    }
+}
+
+fn attributes(pairs: &[(&str, &str)]) -> Value {
+    pairs
+        .iter()
+        .map(|(key, value)| json!({ "key": key, "value": { "stringValue": value } }))
+        .collect()
+}
+
+/// Unix seconds as the decimal string OTLP JSON uses for fixed64.
+fn nanos(seconds: f64) -> String {
+    ((seconds * 1e9).round().max(0.0) as u64).to_string()
+}
+
+/// Collects the metrics of one request with a shared timestamp.
+struct Metrics {
+    time: String,
+    /// Boot time, the start of every cumulative series.
+    start: String,
+    metrics: Vec<Value>,
+}
+
+impl Metrics {
+    fn gauge(&mut self, name: &str, unit: &str, description: &str, points: Vec<(f64, Value)>) {
+        let points: Vec<Value> = points
+            .into_iter()
+            .filter(|(value, _)| value.is_finite())
+            .map(|(value, attributes)| {
+                json!({ "timeUnixNano": self.time, "asDouble": value, "attributes": attributes })
+            })
+            .collect();
+        if !points.is_empty() {
+            self.metrics.push(json!({
+                "name": name,
+                "unit": unit,
+                "description": description,
+                "gauge": { "dataPoints": points },
+            }));
+        }
+    }
+
+    /// A cumulative integer sum: a counter when `monotonic`, otherwise
+    /// an up-down counter such as a usage in bytes.
+    fn sum(
+        &mut self,
+        name: &str,
+        unit: &str,
+        description: &str,
+        monotonic: bool,
+        points: Vec<(u64, Value)>,
+    ) {
+        if points.is_empty() {
+            return;
+        }
+        let points: Vec<Value> = points
+            .into_iter()
+            .map(|(value, attributes)| {
+                json!({
+                    "startTimeUnixNano": self.start,
+                    "timeUnixNano": self.time,
+                    // int64 is a string in OTLP JSON.
+                    "asInt": value.to_string(),
+                    "attributes": attributes,
+                })
+            })
+            .collect();
+        self.metrics.push(json!({
+            "name": name,
+            "unit": unit,
+            "description": description,
+            "sum": {
+                "aggregationTemporality": CUMULATIVE,
+                "isMonotonic": monotonic,
+                "dataPoints": points,
+            },
+        }));
+    }
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    match total {
+        0 => f64::NAN,
+        _ => part as f64 / total as f64,
+    }
+}
+
+/// The `ExportMetricsServiceRequest` for `snapshot`, taken at
+/// `timestamp` (Unix seconds) on `hostname`. `config.tags` become
+/// resource attributes; `config.process_limit` busiest process groups
+/// are included, named by `process.executable.name`.
+pub fn metrics_request(
+    snapshot: &SystemSnapshot,
+    hostname: &str,
+    config: &PushConfig,
+    timestamp: f64,
+) -> Value {
+    let os_type = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    let mut resource = vec![
+        ("service.name", "rust-dashboard"),
+        ("service.version", env!("CARGO_PKG_VERSION")),
+        ("host.name", hostname),
+        ("os.type", os_type),
+    ];
+    for (key, value) in &config.tags {
+        if !resource.iter().any(|(own, _)| own == key) {
+            resource.push((key, value));
+        }
+    }
+
+    let mut m = Metrics {
+        time: nanos(timestamp),
+        start: nanos(timestamp - snapshot.uptime_seconds as f64),
+        metrics: Vec::new(),
+    };
+    let none = || attributes(&[]);
+
+    let cpus = snapshot.per_cpu.len().max(1);
+    m.gauge(
+        "system.cpu.utilization",
+        "1",
+        "Fraction of CPU time in use across all logical CPUs",
+        vec![(snapshot.cpu_usage as f64 / 100.0, none())],
+    );
+    m.sum(
+        "system.cpu.logical.count",
+        "{cpu}",
+        "Logical CPUs",
+        false,
+        vec![(cpus as u64, none())],
+    );
+    let (load_1, load_5, load_15) = snapshot.load_average;
+    for (name, value) in [("1m", load_1), ("5m", load_5), ("15m", load_15)] {
+        m.gauge(
+            &format!("system.cpu.load_average.{}", name),
+            "{thread}",
+            "Load average",
+            vec![(value, none())],
+        );
+    }
+
+    let memory = &snapshot.memory;
+    let state = |state: &str| attributes(&[("system.memory.state", state)]);
+    m.sum(
+        "system.memory.usage",
+        "By",
+        "Memory in use and free",
+        false,
+        vec![(memory.used, state("used")), (memory.free, state("free"))],
+    );
+    m.sum(
+        "system.memory.limit",
+        "By",
+        "Total memory",
+        false,
+        vec![(memory.total, none())],
+    );
+    m.gauge(
+        "system.memory.utilization",
+        "1",
+        "Fraction of memory in use",
+        vec![(ratio(memory.used, memory.total), state("used"))],
+    );
+    if memory.swap_total > 0 {
+        let state = |state: &str| attributes(&[("system.paging.state", state)]);
+        m.sum(
+            "system.paging.usage",
+            "By",
+            "Swap in use and free",
+            false,
+            vec![
+                (memory.swap_used, state("used")),
+                (
+                    memory.swap_total.saturating_sub(memory.swap_used),
+                    state("free"),
+                ),
+            ],
+        );
+    }
+    m.gauge(
+        "system.uptime",
+        "s",
+        "Time since boot",
+        vec![(snapshot.uptime_seconds as f64, none())],
+    );
+    m.sum(
+        "system.process.count",
+        "{process}",
+        "Running processes",
+        false,
+        vec![(
+            snapshot.processes.iter().map(|p| p.pids.len() as u64).sum(),
+            none(),
+        )],
+    );
+
+    let disk = |d: &DiskInfo, state: Option<&str>| {
+        let mut pairs = vec![
+            ("system.device", d.name.as_str()),
+            ("system.filesystem.mountpoint", d.mount_point.as_str()),
+            ("system.filesystem.type", d.filesystem.as_str()),
+        ];
+        pairs.extend(state.map(|state| ("system.filesystem.state", state)));
+        attributes(&pairs)
+    };
+    m.sum(
+        "system.filesystem.usage",
+        "By",
+        "Filesystem space used and free",
+        false,
+        snapshot
+            .disks
+            .iter()
+            .flat_map(|d| {
+                [
+                    (d.used, disk(d, Some("used"))),
+                    (d.available, disk(d, Some("free"))),
+                ]
+            })
+            .collect(),
+    );
+    m.sum(
+        "system.filesystem.limit",
+        "By",
+        "Filesystem size",
+        false,
+        snapshot
+            .disks
+            .iter()
+            .map(|d| (d.total, disk(d, None)))
+            .collect(),
+    );
+    m.gauge(
+        "system.filesystem.utilization",
+        "1",
+        "Fraction of filesystem space used",
+        snapshot
+            .disks
+            .iter()
+            .map(|d| (ratio(d.used, d.total), disk(d, None)))
+            .collect(),
+    );
+
+    let net = |interface: &str, direction: &str| {
+        attributes(&[
+            ("network.interface.name", interface),
+            ("network.io.direction", direction),
+        ])
+    };
+    let counters = |rx: fn(&NetworkInfo) -> u64, tx: fn(&NetworkInfo) -> u64| {
+        snapshot
+            .networks
+            .iter()
+            .flat_map(|n| {
+                [
+                    (rx(n), net(&n.interface, "receive")),
+                    (tx(n), net(&n.interface, "transmit")),
+                ]
+            })
+            .collect::<Vec<_>>()
+    };
+    m.sum(
+        "system.network.io",
+        "By",
+        "Bytes received and transmitted",
+        true,
+        counters(|n| n.rx_bytes, |n| n.tx_bytes),
+    );
+    m.sum(
+        "system.network.packet.count",
+        "{packet}",
+        "Packets received and transmitted",
+        true,
+        counters(|n| n.rx_packets, |n| n.tx_packets),
+    );
+    m.sum(
+        "system.network.errors",
+        "{error}",
+        "Receive and transmit errors",
+        true,
+        counters(|n| n.rx_errors, |n| n.tx_errors),
+    );
+    m.sum(
+        "system.network.packet.dropped",
+        "{packet}",
+        "Packets dropped on receive and transmit",
+        true,
+        counters(|n| n.rx_dropped, |n| n.tx_dropped),
+    );
+
+    let busiest = busiest(snapshot, config.process_limit);
+    let group = |name: &str| attributes(&[("process.executable.name", name)]);
+    m.gauge(
+        "process.cpu.utilization",
+        "1",
+        "Fraction of all CPUs used by the process group's instances",
+        busiest
+            .iter()
+            .map(|p| (p.cpu_usage as f64 / (100.0 * cpus as f64), group(&p.name)))
+            .collect(),
+    );
+    m.sum(
+        "process.memory.usage",
+        "By",
+        "Resident memory of the process group's instances",
+        false,
+        busiest
+            .iter()
+            .map(|p| (p.memory_usage, group(&p.name)))
+            .collect(),
+    );
+
+    json!({
+        "resourceMetrics": [{
+            "resource": { "attributes": attributes(&resource) },
+            "scopeMetrics": [{
+                "scope": { "name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION") },
+                "metrics": m.metrics,
+            }],
+        }],
+    })
+}
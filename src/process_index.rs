@@ -0,0 +1,295 @@
+use crate::system::CombinedProcess;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// One process as read at a refresh, with its network estimate (zero
+/// when there is none). Borrows the name so unchanged processes cost no
+/// allocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessSample<'a> {
+    pub pid: u32,
+    pub name: Cow<'a, str>,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    pub net_rx_rate: f64,
+    pub net_tx_rate: f64,
+    pub connections: u32,
+}
+
+/// What one `ProcessIndex::update` touched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexUpdate {
+    /// PIDs seen for the first time, or under a new name.
+    pub added: usize,
+    /// Known PIDs whose usage changed.
+    pub changed: usize,
+    /// PIDs that disappeared, or were renamed away from their group.
+    pub exited: usize,
+}
+
+/// Usage last counted for a PID, so a change can be applied to its
+/// group as a difference.
+#[derive(Debug)]
+struct PidEntry {
+    /// Position of the PID's group in `ProcessIndex::list`.
+    group: usize,
+    cpu_usage: f32,
+    memory: u64,
+    net_rx_rate: f64,
+    net_tx_rate: f64,
+    connections: u32,
+    seen: u64,
+}
+
+impl PidEntry {
+    fn same_usage(&self, sample: &ProcessSample<'_>) -> bool {
+        self.cpu_usage == sample.cpu_usage
+            && self.memory == sample.memory
+            && self.net_rx_rate == sample.net_rx_rate
+            && self.net_tx_rate == sample.net_tx_rate
+            && self.connections == sample.connections
+    }
+}
+
+/// Sums for one group, kept in f64 so repeated add/subtract of per-PID
+/// CPU doesn't drift visibly before it's rounded into the f32 row.
+#[derive(Debug, Default)]
+struct GroupSums {
+    cpu_usage: f64,
+    net_rx_rate: f64,
+    net_tx_rate: f64,
+}
+
+/// Process list combined by name, maintained incrementally across
+/// refreshes.
+///
+/// `group_processes` rebuilds the list from scratch, allocating a map
+/// and cloning every name each time. The index instead keeps PIDs and
+/// groups between updates: a PID whose usage didn't change costs one
+/// map lookup, a changed one adjusts its group's sums, and only new,
+/// renamed or exited PIDs touch group membership or allocate.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::process_index::{ProcessIndex, ProcessSample};
+///
+/// let sample = |pid, cpu| ProcessSample {
+///     pid,
+///     name: "worker".into(),
+///     cpu_usage: cpu,
+///     memory: 1024,
+///     net_rx_rate: 0.0,
+///     net_tx_rate: 0.0,
+///     connections: 0,
+/// };
+/// let mut index = ProcessIndex::new();
+/// index.update([sample(10, 1.0), sample(11, 2.0)]);
+/// let update = index.update([sample(10, 1.0), sample(11, 4.0)]);
+/// assert_eq!(update.changed, 1);
+/// assert_eq!(index.processes()[0].cpu_usage, 5.0);
+/// ```
+#[derive(Debug, Default)]
+pub struct ProcessIndex {
+    generation: u64,
+    by_pid: HashMap<u32, PidEntry>,
+    /// Group name -> position in `list` and `sums`.
+    by_name: HashMap<String, usize>,
+    list: Vec<CombinedProcess>,
+    sums: Vec<GroupSums>,
+}
+
+impl ProcessIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Combined rows, one per process name, each with its PIDs sorted.
+    /// Row order is stable between updates except where groups were
+    /// added or removed.
+    pub fn processes(&self) -> &[CombinedProcess] {
+        &self.list
+    }
+
+    /// Number of PIDs indexed.
+    pub fn pid_count(&self) -> usize {
+        self.by_pid.len()
+    }
+
+    /// Bring the index in line with a complete listing of current
+    /// processes. PIDs missing from `samples` are treated as exited.
+    pub fn update<'a>(
+        &mut self,
+        samples: impl IntoIterator<Item = ProcessSample<'a>>,
+    ) -> IndexUpdate {
+        self.generation += 1;
+        let generation = self.generation;
+        let mut update = IndexUpdate::default();
+
+        for sample in samples {
+            match self.by_pid.get_mut(&sample.pid) {
+                Some(entry) if self.list[entry.group].name == sample.name => {
+                    entry.seen = generation;
+                    if entry.same_usage(&sample) {
+                        continue;
+                    }
+                    let pos = entry.group;
+                    let sums = &mut self.sums[pos];
+                    sums.cpu_usage += sample.cpu_usage as f64 - entry.cpu_usage as f64;
+                    sums.net_rx_rate += sample.net_rx_rate - entry.net_rx_rate;
+                    sums.net_tx_rate += sample.net_tx_rate - entry.net_tx_rate;
+                    let row = &mut self.list[pos];
+                    row.memory_usage = row.memory_usage - entry.memory + sample.memory;
+                    row.connections = row.connections - entry.connections + sample.connections;
+                    Self::write_sums(row, sums);
+
+                    entry.cpu_usage = sample.cpu_usage;
+                    entry.memory = sample.memory;
+                    entry.net_rx_rate = sample.net_rx_rate;
+                    entry.net_tx_rate = sample.net_tx_rate;
+                    entry.connections = sample.connections;
+                    update.changed += 1;
+                }
+                Some(_) => {
+                    // Same PID under a new name (exec): move it across.
+                    self.detach(sample.pid);
+                    update.exited += 1;
+                    self.attach(&sample, generation);
+                    update.added += 1;
+                }
+                None => {
+                    self.attach(&sample, generation);
+                    update.added += 1;
+                }
+            }
+        }
+
+        let exited: Vec<u32> = self
+            .by_pid
+            .iter()
+            .filter(|(_, entry)| entry.seen != generation)
+            .map(|(&pid, _)| pid)
+            .collect();
+        update.exited += exited.len();
+        for pid in exited {
+            self.detach(pid);
+        }
+        update
+    }
+
+    fn write_sums(row: &mut CombinedProcess, sums: &mut GroupSums) {
+        // Differences of equal values can leave -0.0 or a hair below
+        // zero behind; never show negative usage.
+        sums.cpu_usage = sums.cpu_usage.max(0.0);
+        sums.net_rx_rate = sums.net_rx_rate.max(0.0);
+        sums.net_tx_rate = sums.net_tx_rate.max(0.0);
+        row.cpu_usage = sums.cpu_usage as f32;
+        row.net_rx_rate = sums.net_rx_rate;
+        row.net_tx_rate = sums.net_tx_rate;
+    }
+
+    fn attach(&mut self, sample: &ProcessSample<'_>, generation: u64) {
+        let group = match self.by_name.get(sample.name.as_ref()) {
+            Some(&pos) => pos,
+            None => {
+                let pos = self.list.len();
+                self.by_name.insert(sample.name.clone().into_owned(), pos);
+                self.list.push(CombinedProcess {
+                    name: sample.name.clone().into_owned(),
+                    ..Default::default()
+                });
+                self.sums.push(GroupSums::default());
+                pos
+            }
+        };
+        let pos = group;
+        let row = &mut self.list[pos];
+        let sums = &mut self.sums[pos];
+        if let Err(at) = row.pids.binary_search(&sample.pid) {
+            row.pids.insert(at, sample.pid);
+        }
+        row.memory_usage += sample.memory;
+        row.connections += sample.connections;
+        sums.cpu_usage += sample.cpu_usage as f64;
+        sums.net_rx_rate += sample.net_rx_rate;
+        sums.net_tx_rate += sample.net_tx_rate;
+        Self::write_sums(row, sums);
+
+        self.by_pid.insert(
+            sample.pid,
+            PidEntry {
+                group,
+                cpu_usage: sample.cpu_usage,
+                memory: sample.memory,
+                net_rx_rate: sample.net_rx_rate,
+                net_tx_rate: sample.net_tx_rate,
+                connections: sample.connections,
+                seen: generation,
+            },
+        );
+    }
+
+    /// Remove a PID and take its usage out of its group, dropping the
+    /// group once it has no PIDs left.
+    fn detach(&mut self, pid: u32) {
+        let Some(entry) = self.by_pid.remove(&pid) else {
+            return;
+        };
+        let pos = entry.group;
+        let row = &mut self.list[pos];
+        if let Ok(at) = row.pids.binary_search(&pid) {
+            row.pids.remove(at);
+        }
+        if !row.pids.is_empty() {
+            let sums = &mut self.sums[pos];
+            row.memory_usage -= entry.memory;
+            row.connections -= entry.connections;
+            sums.cpu_usage -= entry.cpu_usage as f64;
+            sums.net_rx_rate -= entry.net_rx_rate;
+            sums.net_tx_rate -= entry.net_tx_rate;
+            Self::write_sums(row, sums);
+            return;
+        }
+
+        let removed = self.list.swap_remove(pos);
+        self.sums.swap_remove(pos);
+        self.by_name.remove(&removed.name);
+        // The last group moved into the hole; repoint it and its PIDs.
+        if let Some(moved) = self.list.get(pos) {
+            if let Some(at) = self.by_name.get_mut(&moved.name) {
+                *at = pos;
+            }
+            for pid in &moved.pids {
+                if let Some(entry) = self.by_pid.get_mut(pid) {
+                    entry.group = pos;
+                }
+            }
+        }
+    }
+}
+
+/// Combine `samples` by name from scratch. Equivalent to a fresh
+/// `ProcessIndex` updated once, up to row order and float rounding;
+/// kept as the reference the index is tested and benchmarked against.
+pub fn group_processes<'a>(
+    samples: impl IntoIterator<Item = ProcessSample<'a>>,
+) -> Vec<CombinedProcess> {
+    let mut map: HashMap<String, CombinedProcess> = HashMap::new();
+    for sample in samples {
+        let name = sample.name.into_owned();
+        let entry = map.entry(name.clone()).or_insert_with(|| CombinedProcess {
+            name,
+            ..Default::default()
+        });
+        entry.cpu_usage += sample.cpu_usage;
+        entry.memory_usage += sample.memory;
+        entry.pids.push(sample.pid);
+        entry.net_rx_rate += sample.net_rx_rate;
+        entry.net_tx_rate += sample.net_tx_rate;
+        entry.connections += sample.connections;
+    }
+    let mut list: Vec<CombinedProcess> = map.into_values().collect();
+    for process in &mut list {
+        process.pids.sort_unstable();
+    }
+    list
+}
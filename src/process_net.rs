@@ -0,0 +1,297 @@
+use crate::sockets::{self, SocketEntry, SocketProtocol, SocketState};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How a process's network figures were derived. Per-process traffic
+/// isn't something the kernel accounts for without eBPF or netfilter
+/// hooks, so every number here is an estimate and the UI says which kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NetAttribution {
+    /// The process has its own network namespace (container, sandbox);
+    /// the namespace's interface counters are split evenly across its
+    /// member processes.
+    Namespace,
+    /// Host-namespace process; host throughput is split in proportion to
+    /// its share of active inet connections.
+    Connections,
+    /// Nothing could be attributed.
+    #[default]
+    None,
+}
+
+/// Estimated network usage for one process.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProcessNetUsage {
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    /// Active (non-listening) TCP/UDP sockets owned by the process.
+    pub connections: u32,
+    /// Network namespace inode, when readable.
+    pub netns: Option<u64>,
+    pub attribution: NetAttribution,
+}
+
+/// Parse a `net:[4026531840]` namespace symlink.
+fn read_ns_link(path: &Path) -> Option<u64> {
+    let target = fs::read_link(path).ok()?;
+    target
+        .to_str()?
+        .strip_prefix("net:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Read the network namespace inode of `pid` from `<proc_root>/<pid>/ns/net`.
+pub fn netns_inode(proc_root: &Path, pid: u32) -> Option<u64> {
+    read_ns_link(&proc_root.join(pid.to_string()).join("ns/net"))
+}
+
+/// The host network namespace: PID 1's, falling back to our own when
+/// PID 1 isn't readable (e.g. hardened procfs with `hidepid`).
+pub fn host_netns(proc_root: &Path) -> Option<u64> {
+    netns_inode(proc_root, 1).or_else(|| read_ns_link(&proc_root.join("self/ns/net")))
+}
+
+/// Parse `/proc/<pid>/net/dev` and return total `(rx_bytes, tx_bytes)`
+/// across all interfaces except loopback.
+pub fn parse_net_dev(contents: &str) -> (u64, u64) {
+    let mut rx = 0u64;
+    let mut tx = 0u64;
+    // Two header lines, then `iface: rx_bytes rx_packets ... tx_bytes ...`
+    for line in contents.lines().skip(2) {
+        let Some((iface, stats)) = line.split_once(':') else {
+            continue;
+        };
+        if iface.trim() == "lo" {
+            continue;
+        }
+        let fields: Vec<u64> = stats
+            .split_whitespace()
+            .filter_map(|f| f.parse().ok())
+            .collect();
+        if fields.len() >= 9 {
+            rx = rx.saturating_add(fields[0]);
+            tx = tx.saturating_add(fields[8]);
+        }
+    }
+    (rx, tx)
+}
+
+/// Count active inet sockets per PID. Listening and unconnected sockets
+/// don't carry traffic of their own, so they're excluded.
+pub fn active_connections(entries: &[SocketEntry]) -> HashMap<u32, u32> {
+    let mut counts = HashMap::new();
+    for entry in entries {
+        if entry.protocol == SocketProtocol::Unix {
+            continue;
+        }
+        if matches!(
+            entry.state,
+            SocketState::Listen | SocketState::Unconnected | SocketState::TimeWait
+        ) {
+            continue;
+        }
+        if let Some(pid) = entry.pid {
+            *counts.entry(pid).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Samples after which a PID's file descriptors are all resolved again;
+/// see `PidFds`.
+const FULL_RESCAN_EVERY: u32 = 12;
+
+/// The socket inode behind each open file descriptor of a PID (`None`
+/// for anything else), kept between samples so only descriptors not seen
+/// before, or whose socket has since closed, are read again. One closed
+/// and reopened as a socket under the same number between two samples is
+/// missed until the next full rescan, at most `FULL_RESCAN_EVERY` samples
+/// later.
+#[derive(Default)]
+struct PidFds {
+    fds: HashMap<OsString, Option<u64>>,
+    samples: u32,
+}
+
+/// Stateful sampler that turns namespace counters and connection counts
+/// into per-process rate estimates. Keeps the previous counter reading
+/// for each non-host namespace so rates can be derived on the next call,
+/// and the sockets of each PID still alive so its file descriptors
+/// needn't all be resolved every time.
+pub struct ProcessNetSampler {
+    proc_root: PathBuf,
+    prev_ns_totals: HashMap<u64, (u64, u64)>,
+    fds: HashMap<u32, PidFds>,
+}
+
+impl Default for ProcessNetSampler {
+    fn default() -> Self {
+        Self::new(sockets::PROC_ROOT)
+    }
+}
+
+impl ProcessNetSampler {
+    pub fn new(proc_root: impl Into<PathBuf>) -> Self {
+        Self {
+            proc_root: proc_root.into(),
+            prev_ns_totals: HashMap::new(),
+            fds: HashMap::new(),
+        }
+    }
+
+    /// Forget everything kept between samples, for when sampling stops
+    /// for a while: the next sample starts afresh instead of deriving
+    /// rates from stale counters.
+    pub fn reset(&mut self) {
+        self.prev_ns_totals.clear();
+        self.fds.clear();
+    }
+
+    /// Map each socket in `live` that one of `pids` holds to the lowest
+    /// such PID. PIDs not in `pids` have exited and are forgotten.
+    fn socket_owners(&mut self, pids: &[u32], live: &HashSet<u64>) -> HashMap<u64, u32> {
+        let mut owners: HashMap<u64, u32> = HashMap::new();
+        let mut previous = std::mem::take(&mut self.fds);
+        for &pid in pids {
+            let mut known = previous.remove(&pid).unwrap_or_default();
+            if known.samples >= FULL_RESCAN_EVERY {
+                known = PidFds::default();
+            }
+            let Ok(entries) = fs::read_dir(self.proc_root.join(pid.to_string()).join("fd")) else {
+                continue;
+            };
+            let mut fds = HashMap::with_capacity(known.fds.len());
+            for entry in entries.flatten() {
+                let fd = entry.file_name();
+                let inode = match known.fds.get(&fd) {
+                    Some(&Some(inode)) if live.contains(&inode) => Some(inode),
+                    Some(None) => None,
+                    _ => match fs::read_link(entry.path()) {
+                        Ok(target) => sockets::socket_inode(&target),
+                        // Closed since the directory was listed.
+                        Err(_) => continue,
+                    },
+                };
+                if let Some(inode) = inode.filter(|inode| live.contains(inode)) {
+                    let owner = owners.entry(inode).or_insert(pid);
+                    *owner = (*owner).min(pid);
+                }
+                fds.insert(fd, inode);
+            }
+            self.fds.insert(
+                pid,
+                PidFds {
+                    fds,
+                    samples: known.samples + 1,
+                },
+            );
+        }
+        owners
+    }
+
+    /// Estimate per-process usage for `pids`.
+    ///
+    /// `host_rates` is the `(rx, tx)` throughput of the host interfaces
+    /// in bytes/sec and `interval` the time since the previous call; the
+    /// first call for a namespace yields zero rates since there is no
+    /// previous reading yet.
+    pub fn sample(
+        &mut self,
+        pids: &[u32],
+        host_rates: (f64, f64),
+        interval: Duration,
+    ) -> HashMap<u32, ProcessNetUsage> {
+        let mut usage: HashMap<u32, ProcessNetUsage> = HashMap::new();
+        let mut socket_entries = sockets::read_socket_entries(&self.proc_root).unwrap_or_default();
+        let live = socket_entries.iter().map(|entry| entry.inode).collect();
+        let owners = self.socket_owners(pids, &live);
+        for entry in &mut socket_entries {
+            entry.pid = owners.get(&entry.inode).copied();
+        }
+        let connections = active_connections(&socket_entries);
+        let host_ns = host_netns(&self.proc_root);
+
+        // Group PIDs by namespace. Processes whose namespace can't be read
+        // (other users' processes without privileges) are assumed to be in
+        // the host namespace — by far the common case.
+        let mut by_ns: HashMap<Option<u64>, Vec<u32>> = HashMap::new();
+        for &pid in pids {
+            let ns = netns_inode(&self.proc_root, pid).filter(|ns| Some(*ns) != host_ns);
+            by_ns.entry(ns).or_default().push(pid);
+        }
+
+        let dt = interval.as_secs_f64().max(0.1);
+        let mut seen_ns = HashMap::new();
+        for (ns, members) in &by_ns {
+            match ns {
+                Some(ns) => {
+                    let totals = members.iter().find_map(|pid| {
+                        fs::read_to_string(self.proc_root.join(pid.to_string()).join("net/dev"))
+                            .ok()
+                            .map(|c| parse_net_dev(&c))
+                    });
+                    let (rx_rate, tx_rate) = match (totals, self.prev_ns_totals.get(ns)) {
+                        (Some((rx, tx)), Some(&(prev_rx, prev_tx))) => (
+                            rx.saturating_sub(prev_rx) as f64 / dt,
+                            tx.saturating_sub(prev_tx) as f64 / dt,
+                        ),
+                        _ => (0.0, 0.0),
+                    };
+                    if let Some(totals) = totals {
+                        seen_ns.insert(*ns, totals);
+                    }
+                    let share = members.len() as f64;
+                    for &pid in members {
+                        usage.insert(
+                            pid,
+                            ProcessNetUsage {
+                                rx_rate: rx_rate / share,
+                                tx_rate: tx_rate / share,
+                                connections: connections.get(&pid).copied().unwrap_or(0),
+                                netns: Some(*ns),
+                                attribution: NetAttribution::Namespace,
+                            },
+                        );
+                    }
+                }
+                None => {
+                    let total: u32 = members.iter().filter_map(|pid| connections.get(pid)).sum();
+                    for &pid in members {
+                        let conns = connections.get(&pid).copied().unwrap_or(0);
+                        let (rx_rate, tx_rate, attribution) = if total > 0 && conns > 0 {
+                            let share = conns as f64 / total as f64;
+                            (
+                                host_rates.0 * share,
+                                host_rates.1 * share,
+                                NetAttribution::Connections,
+                            )
+                        } else {
+                            (0.0, 0.0, NetAttribution::None)
+                        };
+                        usage.insert(
+                            pid,
+                            ProcessNetUsage {
+                                rx_rate,
+                                tx_rate,
+                                connections: conns,
+                                netns: host_ns,
+                                attribution,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+        // Forget namespaces that disappeared so a recycled inode doesn't
+        // compute a bogus delta against a stale reading.
+        self.prev_ns_totals = seen_ns;
+        usage
+    }
+}
@@ -0,0 +1,585 @@
+//! Pushing snapshots to a metrics backend: InfluxDB line protocol over
+//! HTTP or UDP, and StatsD (or DogStatsD) gauges over UDP.
+//!
+//! Each snapshot becomes a set of `Sample`s: one for the system, one per
+//! disk, network interface and busiest process group. Samples carry
+//! tags (`host`, `mount`, `interface`, `group`) plus any from
+//! `PushConfig::tags`. InfluxDB and DogStatsD receive the tags as tags;
+//! plain StatsD has none, so their values become part of the metric
+//! name instead. With the `otlp` feature, snapshots can also go to an
+//! OpenTelemetry collector; see `otlp`.
+
+use crate::actions::http_post;
+use crate::error::DashboardError;
+use crate::snapshot::SystemSnapshot;
+use crate::system::CombinedProcess;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Fits an Ethernet frame with IPv6 and UDP headers.
+pub const DEFAULT_MAX_PACKET_BYTES: usize = 1432;
+
+/// Where `[[push.targets]]` send samples.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PushTarget {
+    /// InfluxDB's HTTP write endpoint: `/api/v2/write?org=..&bucket=..`
+    /// for 2.x, or `/write?db=..` for 1.x. Use `https://` whenever a
+    /// token is set, so it isn't sent in the clear.
+    InfluxHttp {
+        url: String,
+        /// Sent as `Authorization: Token ..` (InfluxDB 2.x).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        token: Option<String>,
+    },
+    /// InfluxDB's UDP listener, `host:port`.
+    InfluxUdp { address: String },
+    /// A StatsD daemon, `host:port`. `dogstatsd` sends tags in the
+    /// DogStatsD `|#key:value` extension instead of the metric name.
+    Statsd {
+        address: String,
+        #[serde(default)]
+        dogstatsd: bool,
+    },
+    /// An OpenTelemetry collector's OTLP/HTTP endpoint, e.g.
+    /// `https://otel.example.com:4318`; see `otlp::metrics_url`. Needs
+    /// the `otlp` feature.
+    Otlp {
+        endpoint: String,
+        /// Extra request headers, e.g. for authentication. Send these
+        /// to an `https://` endpoint so they aren't readable in transit.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+impl PushTarget {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            PushTarget::InfluxHttp { url, .. } => crate::actions::check_http_url("InfluxDB", url),
+            PushTarget::InfluxUdp { address } | PushTarget::Statsd { address, .. } => {
+                match address.rsplit_once(':') {
+                    Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(()),
+                    _ => Err(format!("`{}`: expected host:port", address)),
+                }
+            }
+            #[cfg(feature = "otlp")]
+            PushTarget::Otlp { endpoint, headers } => {
+                // Caught here rather than as a failed push later.
+                let bad = |text: &str| text.contains(['\r', '\n']);
+                if let Some((name, _)) = headers.iter().find(|(name, value)| {
+                    name.is_empty() || name.contains(':') || bad(name) || bad(value)
+                }) {
+                    return Err(format!("OTLP `{}`: invalid header `{}`", endpoint, name));
+                }
+                let url = crate::otlp::metrics_url(endpoint);
+                crate::actions::check_http_url("OTLP", &url)
+            }
+            #[cfg(not(feature = "otlp"))]
+            PushTarget::Otlp { endpoint, .. } => Err(format!(
+                "OTLP `{}`: built without the `otlp` feature",
+                endpoint
+            )),
+        }
+    }
+
+    /// Short label for logs and errors.
+    pub fn describe(&self) -> String {
+        match self {
+            PushTarget::InfluxHttp { url, .. } => format!("InfluxDB {}", url),
+            PushTarget::InfluxUdp { address } => format!("InfluxDB udp://{}", address),
+            PushTarget::Statsd {
+                address,
+                dogstatsd: true,
+            } => format!("DogStatsD {}", address),
+            PushTarget::Statsd { address, .. } => format!("StatsD {}", address),
+            PushTarget::Otlp { endpoint, .. } => format!("OTLP {}", endpoint),
+        }
+    }
+}
+
+/// `[push]` in `config.toml`. Pushing is off while `targets` is empty.
+///
+/// ```toml
+/// [push]
+/// interval_seconds = 10
+/// tags = { env = "prod" }
+///
+/// [[push.targets]]
+/// type = "influx_http"
+/// url = "https://influx.example.com/api/v2/write?org=ops&bucket=hosts"
+/// token = "..."
+///
+/// [[push.targets]]
+/// type = "statsd"
+/// address = "127.0.0.1:8125"
+/// dogstatsd = true
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushConfig {
+    pub interval_seconds: u64,
+    /// Prepended to measurement (InfluxDB) and metric (StatsD) names.
+    pub prefix: String,
+    /// Added to every sample, e.g. `env = "prod"`.
+    pub tags: BTreeMap<String, String>,
+    /// Process groups pushed, busiest first; 0 pushes none.
+    pub process_limit: usize,
+    /// UDP datagrams are filled with lines up to this size.
+    pub max_packet_bytes: usize,
+    pub targets: Vec<PushTarget>,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            interval_seconds: 10,
+            prefix: "rdash".to_string(),
+            tags: BTreeMap::new(),
+            process_limit: 10,
+            max_packet_bytes: DEFAULT_MAX_PACKET_BYTES,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl PushConfig {
+    pub fn validate(&self) -> Result<(), DashboardError> {
+        let invalid = |message: String| Err(DashboardError::ConfigError(message));
+        if self.interval_seconds == 0 {
+            return invalid("push interval must be at least 1 second".to_string());
+        }
+        if self.max_packet_bytes < 64 {
+            return invalid("push max_packet_bytes must be at least 64".to_string());
+        }
+        if let Some(key) = self.tags.keys().find(|key| key.is_empty()) {
+            return invalid(format!("push tag `{}` needs a name", key));
+        }
+        for target in &self.targets {
+            target
+                .validate()
+                .or_else(|e| invalid(format!("push target {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+/// One point: a measurement with its tags and numeric fields. `host`
+/// comes first, then the tag identifying the sample within its
+/// measurement, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub measurement: &'static str,
+    pub tags: Vec<(&'static str, String)>,
+    pub fields: Vec<(&'static str, f64)>,
+}
+
+/// The `limit` process groups using the most CPU, then memory.
+pub(crate) fn busiest(snapshot: &SystemSnapshot, limit: usize) -> Vec<&CombinedProcess> {
+    let mut busiest: Vec<_> = snapshot.processes.iter().collect();
+    busiest.sort_by(|a, b| {
+        b.cpu_usage
+            .total_cmp(&a.cpu_usage)
+            .then(b.memory_usage.cmp(&a.memory_usage))
+    });
+    busiest.truncate(limit);
+    busiest
+}
+
+/// The samples pushed for `snapshot`: `system`, then one `disk` per
+/// mount, one `net` per interface and one `process` for each of the
+/// `process_limit` busiest process groups.
+pub fn samples(snapshot: &SystemSnapshot, hostname: &str, process_limit: usize) -> Vec<Sample> {
+    let host = || ("host", hostname.to_string());
+    let memory = &snapshot.memory;
+    let (load_1, load_5, load_15) = snapshot.load_average;
+    let mut samples = vec![Sample {
+        measurement: "system",
+        tags: vec![host()],
+        fields: vec![
+            ("cpu_usage", snapshot.cpu_usage as f64),
+            ("memory_used", memory.used as f64),
+            ("memory_available", memory.available as f64),
+            ("memory_total", memory.total as f64),
+            ("swap_used", memory.swap_used as f64),
+            ("swap_total", memory.swap_total as f64),
+            ("load_1", load_1),
+            ("load_5", load_5),
+            ("load_15", load_15),
+            ("uptime_seconds", snapshot.uptime_seconds as f64),
+            ("process_groups", snapshot.processes.len() as f64),
+        ],
+    }];
+    samples.extend(snapshot.disks.iter().map(|disk| Sample {
+        measurement: "disk",
+        tags: vec![
+            host(),
+            ("mount", disk.mount_point.clone()),
+            ("device", disk.name.clone()),
+            ("fs", disk.filesystem.clone()),
+        ],
+        fields: vec![
+            ("used", disk.used as f64),
+            ("available", disk.available as f64),
+            ("total", disk.total as f64),
+        ],
+    }));
+    samples.extend(snapshot.networks.iter().map(|net| Sample {
+        measurement: "net",
+        tags: vec![host(), ("interface", net.interface.clone())],
+        fields: vec![
+            ("rx_rate", net.rx_rate),
+            ("tx_rate", net.tx_rate),
+            ("rx_bytes", net.rx_bytes as f64),
+            ("tx_bytes", net.tx_bytes as f64),
+            ("rx_errors", net.rx_errors as f64),
+            ("tx_errors", net.tx_errors as f64),
+            ("rx_dropped", net.rx_dropped as f64),
+            ("tx_dropped", net.tx_dropped as f64),
+        ],
+    }));
+    samples.extend(
+        busiest(snapshot, process_limit)
+            .into_iter()
+            .map(|p| Sample {
+                measurement: "process",
+                tags: vec![host(), ("group", p.name.clone())],
+                fields: vec![
+                    ("cpu_usage", p.cpu_usage as f64),
+                    ("memory", p.memory_usage as f64),
+                    ("instances", p.pids.len() as f64),
+                    ("net_rx_rate", p.net_rx_rate),
+                    ("net_tx_rate", p.net_tx_rate),
+                ],
+            }),
+    );
+    samples
+}
+
+fn prefixed(prefix: &str, name: &str, separator: char) -> String {
+    match prefix {
+        "" => name.to_string(),
+        _ => format!("{}{}{}", prefix, separator, name),
+    }
+}
+
+/// The sample's tags followed by the configured ones, skipping empty
+/// values (InfluxDB rejects them) and configured tags the sample
+/// already has.
+fn all_tags<'a>(sample: &'a Sample, config: &'a PushConfig) -> Vec<(&'a str, &'a str)> {
+    let own = sample.tags.iter().map(|(k, v)| (*k, v.as_str()));
+    let extra = config
+        .tags
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .filter(|(k, _)| !sample.tags.iter().any(|(own, _)| own == k));
+    own.chain(extra).filter(|(_, v)| !v.is_empty()).collect()
+}
+
+fn influx_escape(text: &str, special: &[char]) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        // Line protocol can't carry line breaks at all.
+        escaped.push(if c == '\n' || c == '\r' { ' ' } else { c });
+    }
+    escaped
+}
+
+/// InfluxDB line protocol, one line per sample, e.g.
+/// `rdash_disk,host=db1,mount=/data used=1024,total=4096 1700000000000000000`.
+/// Non-finite fields are dropped; `timestamp` is in Unix seconds.
+pub fn influx_lines(samples: &[Sample], config: &PushConfig, timestamp: f64) -> Vec<String> {
+    let nanos = (timestamp * 1e9).round() as i64;
+    samples
+        .iter()
+        .filter_map(|sample| {
+            let fields: Vec<String> = sample
+                .fields
+                .iter()
+                .filter(|(_, value)| value.is_finite())
+                .map(|(key, value)| format!("{}={}", influx_escape(key, &[',', '=', ' ']), value))
+                .collect();
+            if fields.is_empty() {
+                return None;
+            }
+            let mut line = influx_escape(
+                &prefixed(&config.prefix, sample.measurement, '_'),
+                &[',', ' '],
+            );
+            for (key, value) in all_tags(sample, config) {
+                line.push(',');
+                line.push_str(&influx_escape(key, &[',', '=', ' ']));
+                line.push('=');
+                line.push_str(&influx_escape(value, &[',', '=', ' ']));
+            }
+            Some(format!("{} {} {}", line, fields.join(","), nanos))
+        })
+        .collect()
+}
+
+/// A metric name segment: `/mnt/data` becomes `mnt_data`, `/` becomes
+/// `root`.
+fn statsd_segment(text: &str) -> String {
+    let cleaned: String = text
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect();
+    match cleaned.trim_matches('_') {
+        "" => "root".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// A DogStatsD tag key or value: no separators of the datagram format.
+fn dogstatsd_tag(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            ',' | '|' | '#' | ':' | '@' | '\n' | '\r' => '_',
+            _ => c,
+        })
+        .collect()
+}
+
+/// StatsD gauges, one line per field. Plain StatsD puts the host and
+/// the identifying tag in the name (`rdash.db1.disk.mnt_data.used:1024|g`); DogStatsD keeps
+/// them as tags (`rdash.disk.used:1024|g|#host:db1,mount:/mnt/data`).
+pub fn statsd_lines(samples: &[Sample], config: &PushConfig, dogstatsd: bool) -> Vec<String> {
+    let mut lines = Vec::new();
+    for sample in samples {
+        let tags = all_tags(sample, config);
+        let (base, suffix) = if dogstatsd {
+            let tags: Vec<String> = tags
+                .iter()
+                .map(|(k, v)| format!("{}:{}", dogstatsd_tag(k), dogstatsd_tag(v)))
+                .collect();
+            (
+                sample.measurement.to_string(),
+                format!("|#{}", tags.join(",")),
+            )
+        } else {
+            // Host, measurement and the tag telling samples of one
+            // measurement apart (mount, interface, group). The rest would
+            // make every name longer without identifying anything.
+            let mut segments = Vec::new();
+            if let Some((_, host)) = sample.tags.first().filter(|(_, host)| !host.is_empty()) {
+                segments.push(statsd_segment(host));
+            }
+            segments.push(sample.measurement.to_string());
+            if let Some((_, identity)) = sample.tags.get(1) {
+                segments.push(statsd_segment(identity));
+            }
+            (segments.join("."), String::new())
+        };
+        for (field, value) in &sample.fields {
+            if value.is_finite() {
+                lines.push(format!(
+                    "{}:{}|g{}",
+                    prefixed(&config.prefix, &format!("{}.{}", base, field), '.'),
+                    value,
+                    suffix
+                ));
+            }
+        }
+    }
+    lines
+}
+
+/// Joins lines with newlines into datagrams of at most `max_bytes`; a
+/// line longer than that goes out on its own.
+pub fn packets(lines: &[String], max_bytes: usize) -> Vec<String> {
+    let mut packets = Vec::new();
+    let mut current = String::new();
+    for line in lines {
+        if !current.is_empty() && current.len() + 1 + line.len() > max_bytes {
+            packets.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(line);
+    }
+    if !current.is_empty() {
+        packets.push(current);
+    }
+    packets
+}
+
+fn send_udp(address: &str, packets: &[String]) -> Result<(), String> {
+    let addr: SocketAddr = address
+        .to_socket_addrs()
+        .map_err(|e| format!("cannot resolve {}: {}", address, e))?
+        .next()
+        .ok_or_else(|| format!("cannot resolve {}", address))?;
+    let local = if addr.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(local).map_err(|e| e.to_string())?;
+    for packet in packets {
+        socket
+            .send_to(packet.as_bytes(), addr)
+            .map_err(|e| format!("{}: {}", address, e))?;
+    }
+    Ok(())
+}
+
+/// Sends snapshots to every configured target, synchronously.
+pub struct Pusher {
+    config: PushConfig,
+    hostname: String,
+}
+
+impl Pusher {
+    pub fn new(config: PushConfig, hostname: impl Into<String>) -> Result<Self, DashboardError> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            hostname: hostname.into(),
+        })
+    }
+
+    pub fn config(&self) -> &PushConfig {
+        &self.config
+    }
+
+    /// Push `snapshot`, taken at `timestamp` (Unix seconds), to every
+    /// target. A failing target doesn't stop the others; their errors
+    /// are returned together.
+    pub fn push(&self, snapshot: &SystemSnapshot, timestamp: f64) -> Result<(), DashboardError> {
+        let config = &self.config;
+        let samples = samples(snapshot, &self.hostname, config.process_limit);
+        let mut errors = Vec::new();
+        for target in &config.targets {
+            let result = match target {
+                PushTarget::InfluxHttp { url, token } => {
+                    let mut body = influx_lines(&samples, config, timestamp).join("\n");
+                    body.push('\n');
+                    let auth = token.as_ref().map(|token| format!("Token {}", token));
+                    let mut headers = vec![("Content-Type", "text/plain; charset=utf-8")];
+                    if let Some(auth) = &auth {
+                        headers.push(("Authorization", auth));
+                    }
+                    http_post("InfluxDB", url, &headers, body.as_bytes())
+                        .map_err(|e| e.into_message())
+                }
+                PushTarget::InfluxUdp { address } => send_udp(
+                    address,
+                    &packets(
+                        &influx_lines(&samples, config, timestamp),
+                        config.max_packet_bytes,
+                    ),
+                ),
+                PushTarget::Statsd { address, dogstatsd } => send_udp(
+                    address,
+                    &packets(
+                        &statsd_lines(&samples, config, *dogstatsd),
+                        config.max_packet_bytes,
+                    ),
+                ),
+                #[cfg(feature = "otlp")]
+                PushTarget::Otlp { endpoint, headers } => {
+                    let request =
+                        crate::otlp::metrics_request(snapshot, &self.hostname, config, timestamp);
+                    let mut all = vec![("Content-Type", "application/json")];
+                    all.extend(headers.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+                    http_post(
+                        "OTLP",
+                        &crate::otlp::metrics_url(endpoint),
+                        &all,
+                        request.to_string().as_bytes(),
+                    )
+                    .map_err(|e| e.into_message())
+                }
+                // `Pusher::new` rejects these.
+                #[cfg(not(feature = "otlp"))]
+                PushTarget::Otlp { .. } => unreachable!("validated"),
+            };
+            if let Err(e) = result {
+                errors.push(format!("{}: {}", target.describe(), e));
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(DashboardError::PushFailed(errors.join("; "))),
+        }
+    }
+}
+
+/// Pushes at most once per `interval_seconds` from a background thread,
+/// so a slow or unreachable backend never delays a refresh. A snapshot
+/// offered while the previous push is still running is skipped.
+pub struct PushExporter {
+    interval: Duration,
+    last: Option<Instant>,
+    jobs: SyncSender<(Arc<SystemSnapshot>, f64)>,
+}
+
+impl PushExporter {
+    /// Start the background thread. Fails with `PushFailed` if the
+    /// thread can't be spawned.
+    pub fn start(pusher: Pusher) -> Result<Self, DashboardError> {
+        let interval = Duration::from_secs(pusher.config().interval_seconds);
+        let (jobs, queue) = mpsc::sync_channel::<(Arc<SystemSnapshot>, f64)>(1);
+        thread::Builder::new()
+            .name("push-exporter".to_string())
+            .spawn(move || {
+                let mut failing = None;
+                for (snapshot, timestamp) in queue {
+                    match pusher.push(&snapshot, timestamp) {
+                        Ok(()) => {
+                            if failing.take().is_some() {
+                                log::info!("Push recovered");
+                            }
+                        }
+                        Err(e) => {
+                            let message = e.to_string();
+                            // Once per distinct error, not once per interval.
+                            if failing.as_ref() != Some(&message) {
+                                log::warn!("{}", message);
+                                failing = Some(message);
+                            }
+                        }
+                    }
+                }
+            })
+            .map_err(|e| {
+                DashboardError::PushFailed(format!("cannot start the push exporter: {}", e))
+            })?;
+        Ok(Self {
+            interval,
+            last: None,
+            jobs,
+        })
+    }
+
+    /// Queue `snapshot` if a push is due at `now`. Returns whether it
+    /// was queued.
+    pub fn offer(&mut self, snapshot: &Arc<SystemSnapshot>, now: Instant, timestamp: f64) -> bool {
+        if matches!(self.last, Some(last) if now.duration_since(last) < self.interval) {
+            return false;
+        }
+        match self.jobs.try_send((snapshot.clone(), timestamp)) {
+            Ok(()) => {
+                self.last = Some(now);
+                true
+            }
+            Err(TrySendError::Full(_)) => {
+                log::debug!("Previous push still running; skipping this one");
+                false
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+}
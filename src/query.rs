@@ -0,0 +1,190 @@
+use crate::error::DashboardError;
+use crate::filter::{Filter, Scope};
+use crate::system::{CombinedProcess, SystemMonitor};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Column a process query sorts by. Names match the frontend's
+/// `SortColumn`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortColumn {
+    Name,
+    #[default]
+    Cpu,
+    Memory,
+    /// Receive plus transmit rate.
+    Network,
+    /// Number of PIDs in the group.
+    Pids,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Filter, sort and page the combined process list in the library, so
+/// callers that show a handful of rows don't have to receive them all.
+/// The default query returns every group, busiest CPU first.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::query::ProcessQuery;
+/// use rust_dashboard_lib::system::SystemMonitor;
+///
+/// let monitor = SystemMonitor::new();
+/// let top = ProcessQuery::top_by_cpu(5).run(&monitor).unwrap();
+/// assert!(top.processes.len() <= 5);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessQuery {
+    pub sort: SortColumn,
+    pub direction: SortDirection,
+    /// Regex matched against the group name. Prefix with `(?i)` for a
+    /// case-insensitive match.
+    pub name: Option<String>,
+    /// Regex matched against each PID's command line; a group matches
+    /// if any of its PIDs does.
+    pub cmdline: Option<String>,
+    /// Exact user name; a group matches if any of its PIDs runs as it.
+    pub user: Option<String>,
+    /// Filter expression (see `filter::Filter`), applied on top of the
+    /// other filters.
+    pub filter: Option<String>,
+    /// Minimum summed CPU usage, in percent.
+    pub min_cpu: f32,
+    /// Minimum summed memory, in bytes.
+    pub min_memory: u64,
+    /// Matching rows to skip, after sorting.
+    pub offset: usize,
+    /// Maximum rows to return. `None` returns all remaining.
+    pub limit: Option<usize>,
+}
+
+/// One page of a `ProcessQuery` result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessPage {
+    /// Groups matching the filters, before `offset` and `limit`.
+    pub total: usize,
+    /// Groups in the unfiltered list.
+    pub groups: usize,
+    pub processes: Vec<CombinedProcess>,
+}
+
+fn compile(field: &str, pattern: &Option<String>) -> Result<Option<Regex>, DashboardError> {
+    pattern
+        .as_deref()
+        .map(|p| {
+            Regex::new(p)
+                .map_err(|e| DashboardError::InvalidQuery(format!("{} pattern: {}", field, e)))
+        })
+        .transpose()
+}
+
+/// Case-insensitive name order without allocating lowercase copies.
+fn cmp_names(a: &str, b: &str) -> Ordering {
+    a.chars()
+        .flat_map(char::to_lowercase)
+        .cmp(b.chars().flat_map(char::to_lowercase))
+        .then_with(|| a.cmp(b))
+}
+
+/// Sort `processes` by `column`. Ties fall back to name order so pages
+/// stay stable between refreshes.
+pub fn sort_processes(
+    processes: &mut [&CombinedProcess],
+    column: SortColumn,
+    direction: SortDirection,
+) {
+    processes.sort_by(|a, b| {
+        let by_column = match column {
+            SortColumn::Name => cmp_names(&a.name, &b.name),
+            SortColumn::Cpu => a.cpu_usage.total_cmp(&b.cpu_usage),
+            SortColumn::Memory => a.memory_usage.cmp(&b.memory_usage),
+            SortColumn::Network => {
+                (a.net_rx_rate + a.net_tx_rate).total_cmp(&(b.net_rx_rate + b.net_tx_rate))
+            }
+            SortColumn::Pids => a.pids.len().cmp(&b.pids.len()),
+        };
+        let by_column = match direction {
+            SortDirection::Asc => by_column,
+            SortDirection::Desc => by_column.reverse(),
+        };
+        by_column.then_with(|| cmp_names(&a.name, &b.name))
+    });
+}
+
+impl ProcessQuery {
+    /// The `n` busiest groups by CPU.
+    pub fn top_by_cpu(n: usize) -> Self {
+        ProcessQuery {
+            limit: Some(n),
+            ..Default::default()
+        }
+    }
+
+    /// Evaluate the query against the monitor's current process list.
+    /// Returns `DashboardError::InvalidQuery` if a pattern doesn't
+    /// compile, or `DashboardError::InvalidFilter` if the filter
+    /// expression doesn't parse or tests disk fields.
+    pub fn run(&self, monitor: &SystemMonitor) -> Result<ProcessPage, DashboardError> {
+        let name = compile("name", &self.name)?;
+        let cmdline = compile("cmdline", &self.cmdline)?;
+        let filter = self.filter.as_deref().map(Filter::parse).transpose()?;
+        if filter.as_ref().is_some_and(|f| f.scope() == Scope::Disks) {
+            return Err(DashboardError::InvalidFilter {
+                column: 1,
+                message: "disk fields can't select processes".to_string(),
+            });
+        }
+
+        let all = monitor.combined_process_list();
+        let mut matched: Vec<&CombinedProcess> = all
+            .iter()
+            .filter(|p| p.cpu_usage >= self.min_cpu && p.memory_usage >= self.min_memory)
+            .filter(|p| match &name {
+                Some(re) => re.is_match(&p.name),
+                None => true,
+            })
+            .filter(|p| match &self.user {
+                Some(user) => p
+                    .pids
+                    .iter()
+                    .any(|&pid| monitor.process_user(pid) == Some(user.as_str())),
+                None => true,
+            })
+            .filter(|p| match &cmdline {
+                Some(re) => p.pids.iter().any(|&pid| {
+                    monitor
+                        .command_line(pid)
+                        .is_some_and(|cmd| re.is_match(&cmd))
+                }),
+                None => true,
+            })
+            .filter(|p| match &filter {
+                Some(filter) => filter.matches(p, monitor),
+                None => true,
+            })
+            .collect();
+
+        sort_processes(&mut matched, self.sort, self.direction);
+        let total = matched.len();
+        let processes = matched
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        Ok(ProcessPage {
+            total,
+            groups: all.len(),
+            processes,
+        })
+    }
+}
@@ -0,0 +1,497 @@
+//! Continuous recording of snapshots to rotating files, so a dashboard
+//! left running overnight can be inspected (or replayed) the next day.
+//!
+//! Files are named `rdash-YYYYMMDD-HHMMSS.<ext>` after the UTC time they
+//! were opened. A file is closed once it reaches `max_file_bytes` or
+//! `max_file_age_seconds`, then gzipped in the background when
+//! `compress` is set; the oldest recordings beyond `max_files` are
+//! deleted.
+
+use crate::error::DashboardError;
+use crate::export::utc_fields;
+use crate::snapshot::SystemSnapshot;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordingFormat {
+    /// One `RecordedSnapshot` per line, with the full process list.
+    /// Recordings in this format can be replayed.
+    #[default]
+    Ndjson,
+    /// One row of system totals per snapshot; see `CSV_HEADER`.
+    Csv,
+}
+
+impl RecordingFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Ndjson => "ndjson",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// `[recording]` in `config.toml`.
+///
+/// ```toml
+/// [recording]
+/// enabled = true
+/// every_nth = 5
+/// max_file_bytes = 104857600
+/// stop_after_seconds = 43200
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingConfig {
+    /// Start recording when the app starts.
+    pub enabled: bool,
+    /// Where recordings go. Defaults to `default_directory()`.
+    pub directory: Option<PathBuf>,
+    pub format: RecordingFormat,
+    /// Record every Nth snapshot.
+    pub every_nth: u32,
+    pub max_file_bytes: u64,
+    pub max_file_age_seconds: u64,
+    /// Gzip files once they're rotated out.
+    pub compress: bool,
+    /// Recordings kept in the directory; 0 keeps all of them.
+    pub max_files: usize,
+    /// Stop on its own after this long, e.g. to record one night.
+    pub stop_after_seconds: Option<u64>,
+}
+
+impl Default for RecordingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            format: RecordingFormat::Ndjson,
+            every_nth: 1,
+            max_file_bytes: 64 * 1024 * 1024,
+            max_file_age_seconds: 3600,
+            compress: true,
+            max_files: 168,
+            stop_after_seconds: None,
+        }
+    }
+}
+
+impl RecordingConfig {
+    /// `recordings` under the platform's local data directory.
+    pub fn default_directory() -> Option<PathBuf> {
+        Some(
+            dirs::data_local_dir()?
+                .join("rust-dashboard")
+                .join("recordings"),
+        )
+    }
+
+    /// The configured directory, or the default one.
+    pub fn resolved_directory(&self) -> Result<PathBuf, DashboardError> {
+        self.directory
+            .clone()
+            .or_else(Self::default_directory)
+            .ok_or_else(|| {
+                DashboardError::ConfigError("cannot determine a recording directory".to_string())
+            })
+    }
+}
+
+/// One line of an NDJSON recording.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedSnapshot {
+    /// Seconds since the Unix epoch.
+    pub timestamp: f64,
+    pub snapshot: SystemSnapshot,
+}
+
+/// Columns of a CSV recording. Sizes are bytes and rates bytes per
+/// second; disk columns are summed over all mounts.
+pub const CSV_HEADER: [&str; 13] = [
+    "Timestamp",
+    "CPU Usage %",
+    "Memory Used",
+    "Memory Total",
+    "Swap Used",
+    "Load 1m",
+    "Load 5m",
+    "Load 15m",
+    "Disk Used",
+    "Disk Total",
+    "RX Rate",
+    "TX Rate",
+    "Process Groups",
+];
+
+/// Where a recording stands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordingStatus {
+    pub active: bool,
+    pub directory: PathBuf,
+    pub format: RecordingFormat,
+    /// File being written, if one is open.
+    pub file: Option<PathBuf>,
+    /// When recording started, in seconds since the Unix epoch.
+    pub started_at: f64,
+    pub snapshots_written: u64,
+    pub files_written: u64,
+    /// Why the recording stopped, if it failed.
+    pub error: Option<String>,
+}
+
+struct OpenFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    opened_at: f64,
+    bytes: u64,
+}
+
+/// Appends snapshots to rotating files per a `RecordingConfig`.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::recording::{Recorder, RecordingConfig};
+/// use rust_dashboard_lib::snapshot::SystemSnapshot;
+/// use rust_dashboard_lib::system::{unix_now, SystemMonitor};
+///
+/// let dir = std::env::temp_dir().join("rdash-recording-doctest");
+/// let mut recorder = Recorder::new(
+///     RecordingConfig {
+///         directory: Some(dir.clone()),
+///         compress: false,
+///         ..Default::default()
+///     },
+///     unix_now(),
+/// )
+/// .unwrap();
+/// let snapshot = SystemSnapshot::capture(&SystemMonitor::new());
+/// recorder.record(&snapshot, unix_now()).unwrap();
+/// let status = recorder.finish();
+/// assert_eq!(status.snapshots_written, 1);
+/// # std::fs::remove_dir_all(dir).unwrap();
+/// ```
+pub struct Recorder {
+    config: RecordingConfig,
+    directory: PathBuf,
+    started_at: f64,
+    /// Snapshots still to skip before the next one is written.
+    skip: u32,
+    written: u64,
+    files: u64,
+    current: Option<OpenFile>,
+    /// Rotated files being gzipped, by path.
+    compressing: Vec<(PathBuf, JoinHandle<()>)>,
+}
+
+impl Recorder {
+    /// Check `config` and create the recording directory. `now` is in
+    /// seconds since the Unix epoch.
+    pub fn new(config: RecordingConfig, now: f64) -> Result<Self, DashboardError> {
+        if config.every_nth == 0 {
+            return Err(DashboardError::ConfigError(
+                "recording every_nth must be at least 1".to_string(),
+            ));
+        }
+        if config.max_file_bytes == 0 || config.max_file_age_seconds == 0 {
+            return Err(DashboardError::ConfigError(
+                "recording max_file_bytes and max_file_age_seconds must be positive".to_string(),
+            ));
+        }
+        let directory = config.resolved_directory()?;
+        fs::create_dir_all(&directory).map_err(|e| {
+            DashboardError::RecordingFailed(format!("{}: {}", directory.display(), e))
+        })?;
+        Ok(Self {
+            config,
+            directory,
+            started_at: now,
+            skip: 0,
+            written: 0,
+            files: 0,
+            current: None,
+            compressing: Vec::new(),
+        })
+    }
+
+    pub fn config(&self) -> &RecordingConfig {
+        &self.config
+    }
+
+    pub fn status(&self) -> RecordingStatus {
+        RecordingStatus {
+            active: true,
+            directory: self.directory.clone(),
+            format: self.config.format,
+            file: self.current.as_ref().map(|f| f.path.clone()),
+            started_at: self.started_at,
+            snapshots_written: self.written,
+            files_written: self.files,
+            error: None,
+        }
+    }
+
+    /// Whether `stop_after_seconds` has passed at `now`.
+    pub fn expired(&self, now: f64) -> bool {
+        match self.config.stop_after_seconds {
+            Some(limit) => now - self.started_at >= limit as f64,
+            None => false,
+        }
+    }
+
+    /// Append `snapshot`, taken at `timestamp`, unless `every_nth` skips
+    /// it, rotating the file first if it's full or old enough.
+    pub fn record(
+        &mut self,
+        snapshot: &SystemSnapshot,
+        timestamp: f64,
+    ) -> Result<(), DashboardError> {
+        if self.skip > 0 {
+            self.skip -= 1;
+            return Ok(());
+        }
+        self.skip = self.config.every_nth - 1;
+
+        let line = match self.config.format {
+            RecordingFormat::Ndjson => ndjson_line(snapshot, timestamp).map_err(failed)?,
+            RecordingFormat::Csv => csv_row(snapshot, timestamp).map_err(failed)?,
+        };
+
+        let rotate = match &self.current {
+            Some(file) => {
+                file.bytes >= self.config.max_file_bytes
+                    || timestamp - file.opened_at >= self.config.max_file_age_seconds as f64
+            }
+            None => true,
+        };
+        if rotate {
+            self.close_current();
+            self.open(timestamp).map_err(|e| {
+                DashboardError::RecordingFailed(format!("{}: {}", self.directory.display(), e))
+            })?;
+        }
+
+        let file = self.current.as_mut().expect("file opened above");
+        file.writer
+            .write_all(&line)
+            .and_then(|()| file.writer.flush())
+            .map_err(|e| {
+                DashboardError::RecordingFailed(format!("{}: {}", file.path.display(), e))
+            })?;
+        file.bytes += line.len() as u64;
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Close and (if configured) compress the current file, waiting for
+    /// any compression still running. Gzipping a large file takes a
+    /// while; a UI or refresh loop should use `finish_in_background`.
+    pub fn finish(self) -> RecordingStatus {
+        let (status, pending) = self.finish_in_background();
+        pending.wait();
+        status
+    }
+
+    /// Close the current file and hand it to a background thread for
+    /// compression, like a rotated one, without waiting. The returned
+    /// `PendingCompression` covers it and any rotated file still being
+    /// compressed.
+    pub fn finish_in_background(mut self) -> (RecordingStatus, PendingCompression) {
+        let mut status = self.status();
+        self.close_current();
+        status.active = false;
+        status.file = None;
+        let handles = self.compressing.drain(..).map(|(_, h)| h).collect();
+        (status, PendingCompression { handles })
+    }
+
+    fn open(&mut self, timestamp: f64) -> io::Result<()> {
+        let [year, month, day, hour, minute, second] = utc_fields(timestamp);
+        let stem = format!(
+            "rdash-{:04}{:02}{:02}-{:02}{:02}{:02}",
+            year, month, day, hour, minute, second
+        );
+        let extension = self.config.format.extension();
+        let mut path = self.directory.join(format!("{}.{}", stem, extension));
+        let mut n = 1;
+        while path.exists() || gz_path(&path).exists() {
+            path = self.directory.join(format!("{}-{}.{}", stem, n, extension));
+            n += 1;
+        }
+
+        let mut writer = BufWriter::new(File::create(&path)?);
+        let mut bytes = 0;
+        if self.config.format == RecordingFormat::Csv {
+            let header = format!("{}\n", CSV_HEADER.join(","));
+            writer.write_all(header.as_bytes())?;
+            bytes = header.len() as u64;
+        }
+        self.current = Some(OpenFile {
+            path,
+            writer,
+            opened_at: timestamp,
+            bytes,
+        });
+        self.files += 1;
+        self.prune();
+        Ok(())
+    }
+
+    fn close_current(&mut self) {
+        let Some(mut file) = self.current.take() else {
+            return;
+        };
+        if let Err(e) = file.writer.flush() {
+            log::warn!("Cannot flush {}: {}", file.path.display(), e);
+        }
+        drop(file.writer);
+        if !self.config.compress {
+            return;
+        }
+        self.compressing.retain(|(_, handle)| !handle.is_finished());
+        let path = file.path;
+        let compressing = path.clone();
+        let handle = thread::spawn(move || compress_logged(&compressing));
+        self.compressing.push((path, handle));
+    }
+
+    /// Delete the oldest recordings beyond `max_files`. Names sort by
+    /// the time they were opened.
+    fn prune(&self) {
+        if self.config.max_files == 0 {
+            return;
+        }
+        let mut recordings = list_recordings(&self.directory);
+        recordings.retain(|path| {
+            let plain = path.with_extension("");
+            !self
+                .compressing
+                .iter()
+                .any(|(p, _)| p == path || *p == plain)
+        });
+        let excess = recordings.len().saturating_sub(self.config.max_files);
+        for path in recordings.into_iter().take(excess) {
+            if let Err(e) = fs::remove_file(&path) {
+                log::warn!("Cannot remove old recording {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if let Some(file) = &mut self.current {
+            let _ = file.writer.flush();
+        }
+    }
+}
+
+/// Files a finished `Recorder` is still compressing.
+pub struct PendingCompression {
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl PendingCompression {
+    pub fn is_finished(&self) -> bool {
+        self.handles.iter().all(JoinHandle::is_finished)
+    }
+
+    /// Block until every file is compressed.
+    pub fn wait(self) {
+        for handle in self.handles {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Recordings in `directory`, oldest first, compressed or not.
+pub fn list_recordings(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            let name = name.strip_suffix(".gz").unwrap_or(name);
+            name.starts_with("rdash-") && (name.ends_with(".ndjson") || name.ends_with(".csv"))
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn failed(e: impl std::fmt::Display) -> DashboardError {
+    DashboardError::RecordingFailed(e.to_string())
+}
+
+fn gz_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".gz");
+    PathBuf::from(name)
+}
+
+fn compress_logged(path: &Path) {
+    if let Err(e) = compress(path) {
+        log::warn!("Cannot compress {}: {}", path.display(), e);
+    }
+}
+
+/// Gzip `path` to `path.gz` and remove the original.
+fn compress(path: &Path) -> io::Result<()> {
+    let target = gz_path(path);
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(
+        BufWriter::new(File::create(&target)?),
+        Compression::default(),
+    );
+    io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.flush()?;
+    fs::remove_file(path)
+}
+
+fn ndjson_line(snapshot: &SystemSnapshot, timestamp: f64) -> Result<Vec<u8>, serde_json::Error> {
+    #[derive(Serialize)]
+    struct Line<'a> {
+        timestamp: f64,
+        snapshot: &'a SystemSnapshot,
+    }
+    let mut line = serde_json::to_vec(&Line {
+        timestamp,
+        snapshot,
+    })?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+fn csv_row(snapshot: &SystemSnapshot, timestamp: f64) -> Result<Vec<u8>, csv::Error> {
+    let memory = &snapshot.memory;
+    let (load1, load5, load15) = snapshot.load_average;
+    let disk_used: u64 = snapshot.disks.iter().map(|d| d.used).sum();
+    let disk_total: u64 = snapshot.disks.iter().map(|d| d.total).sum();
+    let rx: f64 = snapshot.networks.iter().map(|n| n.rx_rate).sum();
+    let tx: f64 = snapshot.networks.iter().map(|n| n.tx_rate).sum();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record([
+        format!("{:.3}", timestamp),
+        format!("{:.2}", snapshot.cpu_usage),
+        memory.used.to_string(),
+        memory.total.to_string(),
+        memory.swap_used.to_string(),
+        format!("{:.2}", load1),
+        format!("{:.2}", load5),
+        format!("{:.2}", load15),
+        disk_used.to_string(),
+        disk_total.to_string(),
+        format!("{:.0}", rx),
+        format!("{:.0}", tx),
+        snapshot.processes.len().to_string(),
+    ])?;
+    writer.into_inner().map_err(|e| e.into_error().into())
+}
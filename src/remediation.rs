@@ -0,0 +1,460 @@
+//! Automatic remediation of runaway processes: policies that renice,
+//! terminate or kill a process once a filter condition has held on it
+//! for long enough. Dry run is the default, protected processes are
+//! never touched, and every decision is kept in an audit trail.
+
+use crate::error::DashboardError;
+use crate::filter::{Filter, Scope};
+use crate::system::{unix_now, CombinedProcess, SystemMonitor};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Process names no policy acts on, whatever `protected` says: init
+/// systems, session and display servers, remote access and the
+/// dashboard itself.
+pub const BUILTIN_PROTECTED: &[&str] = &[
+    "systemd",
+    "init",
+    "launchd",
+    "kernel_task",
+    "kthreadd",
+    "WindowServer",
+    "loginwindow",
+    "sshd",
+    "Xorg",
+    "Xwayland",
+    "gnome-shell",
+    "kwin_wayland",
+    "kwin_x11",
+    "dbus-daemon",
+    "csrss.exe",
+    "wininit.exe",
+    "winlogon.exe",
+    "lsass.exe",
+    "services.exe",
+    "rdash",
+    "rust-dashboard",
+];
+
+/// Audit entries kept in memory for `RemediationEngine::log`.
+const LOG_CAPACITY: usize = 200;
+
+fn default_nice() -> i32 {
+    10
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RemediationAction {
+    /// Lower the process's priority to the policy's `nice` value.
+    Renice,
+    /// SIGTERM: ask the process to exit.
+    Terminate,
+    /// SIGKILL: end it immediately.
+    Kill,
+}
+
+/// One policy. The condition is a `filter::Filter` over process fields,
+/// checked per process rather than per name group, so `mem > 8G` means
+/// one process using 8 GiB.
+///
+/// ```toml
+/// [[remediation.policies]]
+/// name = "busy node"
+/// condition = "name == node && cpu > 95"
+/// for_seconds = 600
+/// action = "renice"
+/// nice = 15
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemediationPolicy {
+    pub name: String,
+    pub condition: String,
+    /// How long the condition has to hold on a process before acting.
+    #[serde(default)]
+    pub for_seconds: u64,
+    pub action: RemediationAction,
+    /// Nice value for `renice`, -20 to 19.
+    #[serde(default = "default_nice")]
+    pub nice: i32,
+}
+
+/// `[remediation]` in `config.toml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemediationConfig {
+    /// Record what would be done without doing it. On by default so a
+    /// new policy can be checked against the audit trail first.
+    pub dry_run: bool,
+    /// Names never acted on, in addition to `BUILTIN_PROTECTED`.
+    pub protected: Vec<String>,
+    /// Only act on processes owned by the user running the dashboard.
+    pub only_own_processes: bool,
+    /// File the audit trail is appended to, one JSON object per line.
+    pub audit_log: Option<PathBuf>,
+    pub policies: Vec<RemediationPolicy>,
+}
+
+impl Default for RemediationConfig {
+    fn default() -> Self {
+        Self {
+            dry_run: true,
+            protected: Vec::new(),
+            only_own_processes: true,
+            audit_log: None,
+            policies: Vec::new(),
+        }
+    }
+}
+
+impl RemediationConfig {
+    /// `self` as received from somewhere less trusted than `config.toml`,
+    /// like the desktop app's webview, held to the safeguards of
+    /// `configured` (the `[remediation]` section loaded from it). The
+    /// audit trail stays where `configured` keeps it and its protected
+    /// names stay protected. Turning off `dry_run` or `only_own_processes`
+    /// is refused with `DashboardError::ConfigError` unless `configured`
+    /// already has them off, so only the config file can arm policies or
+    /// point them at other users' processes.
+    pub fn restricted_to(mut self, configured: &RemediationConfig) -> Result<Self, DashboardError> {
+        let refuse = |setting: &str| {
+            Err(DashboardError::ConfigError(format!(
+                "remediation: {} can only be turned off in config.toml",
+                setting
+            )))
+        };
+        if !self.dry_run && configured.dry_run {
+            return refuse("dry_run");
+        }
+        if !self.only_own_processes && configured.only_own_processes {
+            return refuse("only_own_processes");
+        }
+        self.audit_log = configured.audit_log.clone();
+        for name in &configured.protected {
+            if !self.protected.contains(name) {
+                self.protected.push(name.clone());
+            }
+        }
+        Ok(self)
+    }
+}
+
+/// What came of a policy matching a process.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RemediationOutcome {
+    Applied,
+    DryRun,
+    /// A safeguard stopped the action.
+    Protected {
+        reason: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+/// One audit trail entry, also published as
+/// `ServiceEvent::Remediation`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemediationEvent {
+    /// Seconds since the Unix epoch.
+    pub timestamp: f64,
+    pub policy: String,
+    pub pid: u32,
+    pub name: String,
+    pub user: Option<String>,
+    pub action: RemediationAction,
+    /// Target nice value, for `renice`.
+    pub nice: Option<i32>,
+    pub outcome: RemediationOutcome,
+    pub message: String,
+}
+
+struct ActivePolicy {
+    policy: RemediationPolicy,
+    filter: Filter,
+    /// When the condition started holding, per (pid, start time).
+    matching_since: HashMap<(u32, u64), Instant>,
+    /// Processes already handled, so each is acted on once per policy.
+    handled: HashSet<(u32, u64)>,
+}
+
+/// Checks remediation policies after each refresh and acts on the
+/// processes that have matched long enough.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::remediation::{
+///     RemediationAction, RemediationConfig, RemediationEngine, RemediationOutcome,
+///     RemediationPolicy,
+/// };
+/// use rust_dashboard_lib::system::SystemMonitor;
+/// use std::time::Instant;
+///
+/// let mut monitor = SystemMonitor::new();
+/// let mut engine = RemediationEngine::new(RemediationConfig {
+///     policies: vec![RemediationPolicy {
+///         name: "hog".to_string(),
+///         condition: "cpu > 95".to_string(),
+///         for_seconds: 600,
+///         action: RemediationAction::Terminate,
+///         nice: 10,
+///     }],
+///     ..Default::default()
+/// })
+/// .unwrap();
+/// // Dry run by default: events only report what would happen.
+/// for event in engine.evaluate(&mut monitor, Instant::now()) {
+///     assert_ne!(event.outcome, RemediationOutcome::Applied);
+/// }
+/// ```
+#[derive(Default)]
+pub struct RemediationEngine {
+    config: RemediationConfig,
+    policies: Vec<ActivePolicy>,
+    log: VecDeque<RemediationEvent>,
+}
+
+impl RemediationEngine {
+    /// Compile the policies. Returns `DashboardError::ConfigError`
+    /// naming the first policy whose condition doesn't parse, isn't
+    /// about processes, or whose nice value is out of range.
+    pub fn new(config: RemediationConfig) -> Result<Self, DashboardError> {
+        let policies = config
+            .policies
+            .iter()
+            .map(|policy| {
+                let invalid = |e: String| {
+                    DashboardError::ConfigError(format!(
+                        "remediation policy `{}`: {}",
+                        policy.name, e
+                    ))
+                };
+                let filter =
+                    Filter::parse(&policy.condition).map_err(|e| invalid(e.to_string()))?;
+                if filter.scope() != Scope::Processes {
+                    return Err(invalid("condition must test process fields".to_string()));
+                }
+                if !(-20..=19).contains(&policy.nice) {
+                    return Err(invalid(format!("nice {} is outside -20..=19", policy.nice)));
+                }
+                Ok(ActivePolicy {
+                    policy: policy.clone(),
+                    filter,
+                    matching_since: HashMap::new(),
+                    handled: HashSet::new(),
+                })
+            })
+            .collect::<Result<_, DashboardError>>()?;
+        Ok(Self {
+            config,
+            policies,
+            log: VecDeque::new(),
+        })
+    }
+
+    pub fn config(&self) -> &RemediationConfig {
+        &self.config
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+
+    /// Recent audit entries, oldest first.
+    pub fn log(&self) -> impl Iterator<Item = &RemediationEvent> {
+        self.log.iter()
+    }
+
+    /// Check every policy against `monitor`'s processes, act on those
+    /// that have matched for the policy's `for_seconds`, and return what
+    /// was done. Each process is handled at most once per policy. Call
+    /// it only after refreshing processes; each target is re-read just
+    /// before acting, and skipped if it exited or its PID was reused.
+    pub fn evaluate(&mut self, monitor: &mut SystemMonitor, now: Instant) -> Vec<RemediationEvent> {
+        if self.policies.is_empty() {
+            return Vec::new();
+        }
+        let processes = per_process(monitor);
+        let live: HashSet<(u32, u64)> = processes.iter().map(|(key, _)| *key).collect();
+        let mut due = Vec::new();
+        for (index, active) in self.policies.iter_mut().enumerate() {
+            active.handled.retain(|key| live.contains(key));
+            let mut matching = HashMap::new();
+            for (key, process) in &processes {
+                if !active.filter.matches(process, monitor) {
+                    continue;
+                }
+                let since = active.matching_since.get(key).copied().unwrap_or(now);
+                matching.insert(*key, since);
+                let held = now.saturating_duration_since(since);
+                if held >= Duration::from_secs(active.policy.for_seconds)
+                    && active.handled.insert(*key)
+                {
+                    due.push((index, *key, process.name.clone()));
+                }
+            }
+            active.matching_since = matching;
+        }
+
+        let own_user = monitor.process_user(std::process::id()).map(str::to_string);
+        let mut events = Vec::new();
+        for (index, (pid, start_time), name) in due {
+            let policy = &self.policies[index].policy;
+            let user = monitor.process_user(pid).map(str::to_string);
+            let nice = (policy.action == RemediationAction::Renice).then_some(policy.nice);
+            let outcome = match self.protection(pid, &name, user.as_deref(), own_user.as_deref()) {
+                Some(reason) => RemediationOutcome::Protected { reason },
+                None if self.config.dry_run => RemediationOutcome::DryRun,
+                // The table may be a refresh old: make sure the PID still
+                // belongs to the process that matched before signalling it.
+                None if monitor.refresh_process(pid) != Some(start_time) => {
+                    RemediationOutcome::Failed {
+                        error: "process exited before the action ran".to_string(),
+                    }
+                }
+                None => {
+                    let result = match policy.action {
+                        RemediationAction::Renice => monitor.renice_process(pid, policy.nice),
+                        RemediationAction::Terminate => monitor.terminate_process(pid),
+                        RemediationAction::Kill => monitor.kill_process(pid),
+                    };
+                    match result {
+                        Ok(()) => RemediationOutcome::Applied,
+                        Err(error) => RemediationOutcome::Failed { error },
+                    }
+                }
+            };
+            let verb = match policy.action {
+                RemediationAction::Renice => format!("renice to {}", policy.nice),
+                RemediationAction::Terminate => "terminate".to_string(),
+                RemediationAction::Kill => "kill".to_string(),
+            };
+            let target = format!("{} ({})", name, pid);
+            let message = match &outcome {
+                RemediationOutcome::Applied => format!("{}: {} {}", policy.name, verb, target),
+                RemediationOutcome::DryRun => {
+                    format!("{}: would {} {} (dry run)", policy.name, verb, target)
+                }
+                RemediationOutcome::Protected { reason } => {
+                    format!("{}: not acting on {}: {}", policy.name, target, reason)
+                }
+                RemediationOutcome::Failed { error } => {
+                    format!("{}: failed to {} {}: {}", policy.name, verb, target, error)
+                }
+            };
+            events.push(RemediationEvent {
+                timestamp: unix_now(),
+                policy: policy.name.clone(),
+                pid,
+                name,
+                user,
+                action: policy.action,
+                nice,
+                outcome,
+                message,
+            });
+        }
+        for event in &events {
+            self.record(event);
+        }
+        events
+    }
+
+    /// Why `pid` mustn't be touched, if it mustn't.
+    fn protection(
+        &self,
+        pid: u32,
+        name: &str,
+        user: Option<&str>,
+        own_user: Option<&str>,
+    ) -> Option<String> {
+        if pid <= 1 {
+            return Some("system process (PID 0 or 1)".to_string());
+        }
+        if pid == std::process::id() {
+            return Some("the dashboard itself".to_string());
+        }
+        let listed = BUILTIN_PROTECTED
+            .iter()
+            .copied()
+            .chain(self.config.protected.iter().map(String::as_str))
+            .any(|protected| protected.eq_ignore_ascii_case(name));
+        if listed {
+            return Some(format!("`{}` is protected", name));
+        }
+        if self.config.only_own_processes {
+            match (user, own_user) {
+                (Some(user), Some(own)) if user == own => {}
+                (Some(user), _) => return Some(format!("owned by `{}`", user)),
+                (None, _) => return Some("owner unknown".to_string()),
+            }
+        }
+        None
+    }
+
+    fn record(&mut self, event: &RemediationEvent) {
+        match event.outcome {
+            RemediationOutcome::Failed { .. } | RemediationOutcome::Protected { .. } => {
+                log::warn!("Remediation: {}", event.message)
+            }
+            _ => log::info!("Remediation: {}", event.message),
+        }
+        if self.log.len() == LOG_CAPACITY {
+            self.log.pop_front();
+        }
+        self.log.push_back(event.clone());
+        if let Some(path) = &self.config.audit_log {
+            let written = serde_json::to_string(event)
+                .map_err(|e| e.to_string())
+                .and_then(|line| {
+                    OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(path)
+                        .and_then(|mut file| writeln!(file, "{}", line))
+                        .map_err(|e| e.to_string())
+                });
+            if let Err(e) = written {
+                log::error!(
+                    "Cannot write remediation audit log {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Every live process as a one-PID `CombinedProcess`, keyed by PID and
+/// start time so a reused PID isn't mistaken for the same process.
+fn per_process(monitor: &SystemMonitor) -> Vec<((u32, u64), CombinedProcess)> {
+    monitor
+        .combined_process_list()
+        .iter()
+        .flat_map(|group| {
+            group.pids.iter().filter_map(move |&pid| {
+                let (cpu_usage, memory_usage) = monitor.usage_for_pid(pid)?;
+                let start = monitor.process_start_time(pid).unwrap_or(0);
+                let net = monitor.network_usage_for_pid(pid);
+                Some((
+                    (pid, start),
+                    CombinedProcess {
+                        name: group.name.clone(),
+                        cpu_usage,
+                        memory_usage,
+                        pids: vec![pid],
+                        net_rx_rate: net.map_or(0.0, |n| n.rx_rate),
+                        net_tx_rate: net.map_or(0.0, |n| n.tx_rate),
+                        connections: net.map_or(0, |n| n.connections),
+                    },
+                ))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,415 @@
+//! Playback of NDJSON recordings (see `recording`) through the same path
+//! as live data. Hand a `Replay` to `MonitorService::set_source` and its
+//! snapshots are published in place of live ones, so every panel and
+//! chart works on the recording.
+//!
+//! Playback follows recorded time: at speed 2, a snapshot recorded ten
+//! seconds after the previous one is published five seconds after it.
+//!
+//! Opening a recording only indexes it (the timestamp and position of
+//! each line); snapshots are decoded when they are published or asked
+//! for, so long recordings don't have to fit in memory.
+
+use crate::error::DashboardError;
+use crate::recording::RecordedSnapshot;
+use crate::service::MetricsSource;
+use crate::snapshot::SystemSnapshot;
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Playback speeds accepted by `Replay::set_speed`.
+pub const MIN_SPEED: f64 = 0.1;
+pub const MAX_SPEED: f64 = 1000.0;
+
+/// Where playback stands. Times are seconds since the Unix epoch, as
+/// recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayStatus {
+    pub files: Vec<PathBuf>,
+    pub start: f64,
+    pub end: f64,
+    /// Recorded time of the snapshot last published (or about to be).
+    pub position: f64,
+    pub frame: usize,
+    pub frames: usize,
+    pub playing: bool,
+    pub speed: f64,
+}
+
+/// A loaded recording with a play head.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::recording::RecordedSnapshot;
+/// use rust_dashboard_lib::replay::Replay;
+/// use rust_dashboard_lib::service::MetricsSource;
+/// use rust_dashboard_lib::snapshot::SystemSnapshot;
+/// use rust_dashboard_lib::system::SystemMonitor;
+/// use std::time::{Duration, Instant};
+///
+/// let snapshot = SystemSnapshot::capture(&SystemMonitor::new());
+/// let frames = (0..3)
+///     .map(|i| RecordedSnapshot { timestamp: 100.0 + i as f64, snapshot: snapshot.clone() })
+///     .collect();
+/// let mut replay = Replay::from_frames(frames).unwrap();
+/// let start = Instant::now();
+/// assert!(replay.poll(start).is_some()); // the first frame, shown on load
+/// replay.play(start);
+/// let later = start + Duration::from_millis(1500);
+/// assert!(replay.poll(later).is_some());
+/// assert_eq!(replay.status(later).frame, 1);
+/// ```
+pub struct Replay {
+    files: Vec<PathBuf>,
+    /// One entry per snapshot, sorted by timestamp.
+    index: Vec<FrameRef>,
+    /// Snapshots handed to `from_frames`.
+    decoded: Vec<RecordedSnapshot>,
+    /// The recordings `open` indexed, in `files` order. Gzipped ones
+    /// are decompressed into anonymous temporary files.
+    sources: Vec<File>,
+    frame: usize,
+    /// The current frame still has to be published.
+    pending: bool,
+    speed: f64,
+    /// While playing: the wall-clock instant and recorded time playback
+    /// was (re)anchored at.
+    anchor: Option<(Instant, f64)>,
+}
+
+impl Replay {
+    /// Index one or more NDJSON recordings, gzipped or not, e.g. the
+    /// files a recording rotated through. Lines that don't parse, like
+    /// a last line cut short, are skipped, and a gzipped file that ends
+    /// early is replayed up to the cut.
+    pub fn open(paths: &[PathBuf]) -> Result<Self, DashboardError> {
+        let mut index = Vec::new();
+        let mut sources = Vec::with_capacity(paths.len());
+        for (source, path) in paths.iter().enumerate() {
+            sources.push(index_recording(path, source, &mut index)?);
+        }
+        let mut replay = Self::new(index)?;
+        replay.files = paths.to_vec();
+        replay.sources = sources;
+        Ok(replay)
+    }
+
+    /// A replay of `frames`, paused on the first one.
+    pub fn from_frames(frames: Vec<RecordedSnapshot>) -> Result<Self, DashboardError> {
+        let index = frames
+            .iter()
+            .enumerate()
+            .map(|(i, f)| FrameRef {
+                timestamp: f.timestamp,
+                location: Location::Decoded(i),
+            })
+            .collect();
+        let mut replay = Self::new(index)?;
+        replay.decoded = frames;
+        Ok(replay)
+    }
+
+    fn new(mut index: Vec<FrameRef>) -> Result<Self, DashboardError> {
+        if index.is_empty() {
+            return Err(DashboardError::ReplayFailed(
+                "the recording has no snapshots".to_string(),
+            ));
+        }
+        index.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        Ok(Self {
+            files: Vec::new(),
+            index,
+            decoded: Vec::new(),
+            sources: Vec::new(),
+            frame: 0,
+            pending: true,
+            speed: 1.0,
+            anchor: None,
+        })
+    }
+
+    pub fn start(&self) -> f64 {
+        self.index[0].timestamp
+    }
+
+    pub fn end(&self) -> f64 {
+        self.index[self.index.len() - 1].timestamp
+    }
+
+    /// Decode snapshot number `frame` (see `ReplayStatus::frame`).
+    pub fn frame(&self, frame: usize) -> Result<RecordedSnapshot, DashboardError> {
+        let entry = self.index.get(frame).ok_or_else(|| {
+            DashboardError::ReplayFailed(format!(
+                "no frame {} in a recording of {}",
+                frame,
+                self.index.len()
+            ))
+        })?;
+        let (source, offset, len) = match entry.location {
+            Location::Decoded(i) => return Ok(self.decoded[i].clone()),
+            Location::Line {
+                source,
+                offset,
+                len,
+            } => (source, offset, len),
+        };
+        let failed = |e: &dyn std::fmt::Display| {
+            DashboardError::ReplayFailed(format!("{}: {}", self.files[source].display(), e))
+        };
+        let mut file = &self.sources[source];
+        let mut line = vec![0; len];
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.read_exact(&mut line))
+            .map_err(|e| failed(&e))?;
+        serde_json::from_slice(&line).map_err(|e| failed(&e))
+    }
+
+    /// Every recorded snapshot, oldest first, decoded one at a time.
+    /// Snapshots that no longer read back are logged and left out.
+    pub fn frames(&self) -> impl Iterator<Item = RecordedSnapshot> + '_ {
+        (0..self.index.len()).filter_map(|i| {
+            self.frame(i)
+                .map_err(|e| log::warn!("Skipping a replay frame: {}", e))
+                .ok()
+        })
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    pub fn status(&self, now: Instant) -> ReplayStatus {
+        let frame = match self.anchor {
+            Some(_) => self.frame_at(self.time_at(now)),
+            None => self.frame,
+        };
+        ReplayStatus {
+            files: self.files.clone(),
+            start: self.start(),
+            end: self.end(),
+            position: self.index[frame].timestamp,
+            frame,
+            frames: self.index.len(),
+            playing: self.is_playing(),
+            speed: self.speed,
+        }
+    }
+
+    /// Play from the current frame, or from the start if at the end.
+    pub fn play(&mut self, now: Instant) {
+        if self.anchor.is_some() {
+            return;
+        }
+        if self.frame + 1 >= self.index.len() {
+            self.frame = 0;
+            self.pending = true;
+        }
+        self.anchor = Some((now, self.index[self.frame].timestamp));
+    }
+
+    pub fn pause(&mut self, now: Instant) {
+        if self.anchor.is_some() {
+            self.advance(now);
+            self.anchor = None;
+        }
+    }
+
+    /// Jump to the last snapshot recorded at or before `timestamp`
+    /// (clamped to the recording), published on the next poll even
+    /// while paused.
+    pub fn seek(&mut self, timestamp: f64, now: Instant) {
+        self.frame = self.frame_at(timestamp.clamp(self.start(), self.end()));
+        self.pending = true;
+        if self.anchor.is_some() {
+            self.anchor = Some((now, self.index[self.frame].timestamp));
+        }
+    }
+
+    /// Recorded seconds per wall-clock second.
+    pub fn set_speed(&mut self, speed: f64, now: Instant) -> Result<(), DashboardError> {
+        if !(MIN_SPEED..=MAX_SPEED).contains(&speed) {
+            return Err(DashboardError::ReplayFailed(format!(
+                "speed must be between {} and {}",
+                MIN_SPEED, MAX_SPEED
+            )));
+        }
+        if self.anchor.is_some() {
+            let time = self.time_at(now);
+            self.anchor = Some((now, time));
+        }
+        self.speed = speed;
+        Ok(())
+    }
+
+    /// The last snapshot recorded at or before `timestamp`, or the
+    /// first one if none was.
+    pub fn snapshot_at(&self, timestamp: f64) -> Result<RecordedSnapshot, DashboardError> {
+        self.frame(self.frame_at(timestamp))
+    }
+
+    /// Recorded time at `now` while playing.
+    fn time_at(&self, now: Instant) -> f64 {
+        match self.anchor {
+            Some((at, time)) => time + now.saturating_duration_since(at).as_secs_f64() * self.speed,
+            None => self.index[self.frame].timestamp,
+        }
+    }
+
+    /// Index of the last frame at or before `time`.
+    fn frame_at(&self, time: f64) -> usize {
+        self.index
+            .partition_point(|f| f.timestamp <= time)
+            .saturating_sub(1)
+    }
+
+    /// Move the play head to `now`, stopping at the end.
+    fn advance(&mut self, now: Instant) {
+        let frame = self.frame_at(self.time_at(now));
+        if frame != self.frame {
+            self.frame = frame;
+            self.pending = true;
+        }
+        if self.frame + 1 >= self.index.len() {
+            self.anchor = None;
+        }
+    }
+}
+
+impl MetricsSource for Replay {
+    fn poll(&mut self, now: Instant) -> Option<SystemSnapshot> {
+        if self.anchor.is_some() {
+            self.advance(now);
+        }
+        if !self.pending {
+            return None;
+        }
+        self.pending = false;
+        match self.frame(self.frame) {
+            Ok(frame) => Some(frame.snapshot),
+            Err(e) => {
+                log::warn!("Skipping a replay frame: {}", e);
+                None
+            }
+        }
+    }
+}
+
+struct FrameRef {
+    timestamp: f64,
+    location: Location,
+}
+
+enum Location {
+    /// Position in `Replay::decoded`.
+    Decoded(usize),
+    /// A line of `Replay::sources[source]`.
+    Line {
+        source: usize,
+        offset: u64,
+        len: usize,
+    },
+}
+
+/// Just enough of a recorded line to index it.
+#[derive(Deserialize)]
+struct Stamp {
+    timestamp: f64,
+}
+
+/// Index the lines of the recording at `path` into `index` and return
+/// the file to read them back from.
+fn index_recording(
+    path: &Path,
+    source: usize,
+    index: &mut Vec<FrameRef>,
+) -> Result<File, DashboardError> {
+    let failed = |e: &dyn std::fmt::Display| {
+        DashboardError::ReplayFailed(format!("{}: {}", path.display(), e))
+    };
+    let name = path.to_string_lossy();
+    let plain = name.strip_suffix(".gz").unwrap_or(&name);
+    if !plain.ends_with(".ndjson") && !plain.ends_with(".jsonl") {
+        return Err(failed(&"only NDJSON recordings can be replayed"));
+    }
+    let file = File::open(path).map_err(|e| failed(&e))?;
+    let (skipped, file) = if name.ends_with(".gz") {
+        // Lines are read back by offset, which a gzip stream can't seek
+        // to, so keep a decompressed copy for the replay's lifetime.
+        let mut copy = BufWriter::new(tempfile::tempfile().map_err(|e| failed(&e))?);
+        let reader = BufReader::new(GzDecoder::new(file));
+        let skipped = scan(path, reader, source, index, |line| copy.write_all(line))?;
+        let copy = copy.into_inner().map_err(|e| failed(e.error()))?;
+        (skipped, copy)
+    } else {
+        (
+            scan(path, BufReader::new(&file), source, index, |_| Ok(()))?,
+            file,
+        )
+    };
+    if skipped > 0 {
+        log::warn!("Skipped {} unreadable lines in {}", skipped, path.display());
+    }
+    Ok(file)
+}
+
+/// Index every line `reader` yields, passing each to `copy`, and return
+/// how many didn't parse. A stream that ends early, like a gzip file
+/// that was never closed, ends the scan instead of failing it.
+fn scan(
+    path: &Path,
+    mut reader: impl BufRead,
+    source: usize,
+    index: &mut Vec<FrameRef>,
+    mut copy: impl FnMut(&[u8]) -> io::Result<()>,
+) -> Result<usize, DashboardError> {
+    let failed = |e: &dyn std::fmt::Display| {
+        DashboardError::ReplayFailed(format!("{}: {}", path.display(), e))
+    };
+    let mut line = Vec::new();
+    let mut offset = 0;
+    let mut skipped = 0;
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {}
+            // flate2 reports a stream cut inside a block as an early
+            // EOF, and one cut after a flush as invalid input.
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::UnexpectedEof | io::ErrorKind::InvalidInput
+                ) =>
+            {
+                log::warn!(
+                    "{} ends early; replaying what was written before the cut",
+                    path.display()
+                );
+                break;
+            }
+            Err(e) => return Err(failed(&e)),
+        }
+        copy(&line).map_err(|e| failed(&e))?;
+        let start = offset;
+        offset += line.len() as u64;
+        if line.trim_ascii().is_empty() {
+            continue;
+        }
+        match serde_json::from_slice::<Stamp>(&line) {
+            Ok(stamp) => index.push(FrameRef {
+                timestamp: stamp.timestamp,
+                location: Location::Line {
+                    source,
+                    offset: start,
+                    len: line.len(),
+                },
+            }),
+            Err(_) => skipped += 1,
+        }
+    }
+    Ok(skipped)
+}
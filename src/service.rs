@@ -0,0 +1,863 @@
+use crate::actions::ActionDispatcher;
+use crate::adaptive::{AdaptiveInputs, AdaptiveScheduler};
+use crate::alerts::{AlertEngine, AlertEvent, AlertRule};
+use crate::anomaly::AnomalyConfig;
+use crate::config::{AdaptiveConfig, RefreshSchedule, Subsystem, MIN_SUBSYSTEM_INTERVAL_MS};
+use crate::error::DashboardError;
+use crate::forecast::ForecastConfig;
+use crate::leaks::LeakConfig;
+use crate::network::LinkEvent;
+use crate::push::{PushConfig, PushExporter, Pusher};
+use crate::recording::{PendingCompression, Recorder, RecordingConfig, RecordingStatus};
+use crate::remediation::{RemediationConfig, RemediationEngine, RemediationEvent};
+use crate::snapshot::SystemSnapshot;
+use crate::system::{unix_now, SystemMonitor};
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Scheduling granularity of the refresh loop. Each tick the loop
+/// re-reads the interval, pause state and visibility, then refreshes
+/// whichever subsystems are due — so setting changes take effect within
+/// one tick and sub-second subsystem cadences (down to
+/// `MIN_SUBSYSTEM_INTERVAL_MS`) are honoured.
+pub const DEFAULT_TICK: Duration = Duration::from_millis(250);
+
+/// Events buffered per subscriber. A subscriber that falls further
+/// behind than this misses snapshots rather than stalling the loop;
+/// up to as many other events again wait for it (see
+/// `Subscriber::deliver`).
+pub const SUBSCRIBER_BUFFER: usize = 16;
+
+/// Something published by `MonitorService`.
+#[derive(Debug, Clone)]
+pub enum ServiceEvent {
+    /// A refresh completed. Shared, so fan-out doesn't copy the process
+    /// list per subscriber.
+    Snapshot(Arc<SystemSnapshot>),
+    /// An interface went up or down.
+    LinkChanged(LinkEvent),
+    /// The monitor lock was poisoned by a panicking holder and has been
+    /// recovered; data may be briefly stale. Published once.
+    Recovered(String),
+    /// An alert rule started or stopped firing.
+    Alert(AlertEvent),
+    /// A remediation policy acted (or, in dry run, would have acted) on
+    /// a process.
+    Remediation(RemediationEvent),
+    /// A recording stopped on its own: `stop_after_seconds` passed, or
+    /// writing failed (see `RecordingStatus::error`).
+    RecordingStopped(RecordingStatus),
+}
+
+/// Supplies snapshots in place of the live monitor, e.g. a
+/// `replay::Replay`. Polled every tick; returning a snapshot publishes
+/// it like a refresh.
+pub trait MetricsSource: Send {
+    fn poll(&mut self, now: Instant) -> Option<SystemSnapshot>;
+}
+
+/// A source shared with whoever controls it.
+impl<T: MetricsSource> MetricsSource for Arc<Mutex<T>> {
+    fn poll(&mut self, now: Instant) -> Option<SystemSnapshot> {
+        lock(self).poll(now)
+    }
+}
+
+/// Returns the subsystems currently rendered by a visible view. An
+/// empty set means nothing is visible, which lets the adaptive
+/// scheduler drop to minimal collection.
+pub type VisibilitySource = Arc<dyn Fn() -> HashSet<Subsystem> + Send + Sync>;
+
+enum Channel {
+    Sync(SyncSender<ServiceEvent>),
+    #[cfg(feature = "async")]
+    Async(futures_channel::mpsc::Sender<ServiceEvent>),
+}
+
+/// Outcome of offering an event to a subscriber's channel.
+enum Sent {
+    Delivered,
+    Full(ServiceEvent),
+    Closed,
+}
+
+impl Channel {
+    fn send(&mut self, event: ServiceEvent) -> Sent {
+        match self {
+            Channel::Sync(tx) => match tx.try_send(event) {
+                Ok(()) => Sent::Delivered,
+                Err(TrySendError::Full(event)) => Sent::Full(event),
+                Err(TrySendError::Disconnected(_)) => Sent::Closed,
+            },
+            #[cfg(feature = "async")]
+            Channel::Async(tx) => match tx.try_send(event) {
+                Ok(()) => Sent::Delivered,
+                Err(e) if e.is_disconnected() => Sent::Closed,
+                Err(e) => Sent::Full(e.into_inner()),
+            },
+        }
+    }
+}
+
+struct Subscriber {
+    channel: Channel,
+    /// Events other than snapshots that didn't fit in the channel,
+    /// oldest first. At most `SUBSCRIBER_BUFFER`.
+    pending: VecDeque<ServiceEvent>,
+    /// Snapshots skipped since the subscriber last kept up.
+    skipped: u64,
+    /// Held events dropped since the subscriber last kept up.
+    dropped: u64,
+}
+
+impl Subscriber {
+    fn new(channel: Channel) -> Self {
+        Self {
+            channel,
+            pending: VecDeque::new(),
+            skipped: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Deliver `event`; returns false once the receiving end is gone.
+    /// A snapshot that doesn't fit is skipped, since the next one
+    /// supersedes it. Anything else (alerts, remediation, link changes)
+    /// is held until there is room, so a slow subscriber still sees
+    /// them in order; only one that falls `SUBSCRIBER_BUFFER` of those
+    /// behind loses the oldest.
+    fn deliver(&mut self, event: &ServiceEvent) -> bool {
+        while let Some(held) = self.pending.pop_front() {
+            match self.channel.send(held) {
+                Sent::Delivered => {}
+                Sent::Full(held) => {
+                    self.pending.push_front(held);
+                    break;
+                }
+                Sent::Closed => return false,
+            }
+        }
+        if !self.pending.is_empty() {
+            match event {
+                ServiceEvent::Snapshot(_) => self.skip(),
+                event => self.hold(event.clone()),
+            }
+            return true;
+        }
+        match self.channel.send(event.clone()) {
+            Sent::Delivered => {
+                if self.skipped > 0 {
+                    log::warn!(
+                        "Subscriber caught up; {} snapshot(s) were skipped",
+                        self.skipped
+                    );
+                    self.skipped = 0;
+                }
+                if self.dropped > 0 {
+                    log::warn!(
+                        "Subscriber caught up; {} other event(s) were dropped",
+                        self.dropped
+                    );
+                    self.dropped = 0;
+                }
+                true
+            }
+            Sent::Full(ServiceEvent::Snapshot(_)) => {
+                self.skip();
+                true
+            }
+            Sent::Full(event) => {
+                log::warn!("Subscriber is behind; holding events until it catches up");
+                self.hold(event);
+                true
+            }
+            Sent::Closed => false,
+        }
+    }
+
+    /// Queue `event` until the channel has room, dropping the oldest held
+    /// event first if `SUBSCRIBER_BUFFER` are already waiting, so a
+    /// subscriber that never reads but keeps its receiver can't grow the
+    /// queue without limit.
+    fn hold(&mut self, event: ServiceEvent) {
+        if self.pending.len() >= SUBSCRIBER_BUFFER {
+            if self.dropped == 0 {
+                log::warn!(
+                    "Subscriber is {} events behind; dropping the oldest until it catches up",
+                    2 * SUBSCRIBER_BUFFER
+                );
+            }
+            self.pending.pop_front();
+            self.dropped += 1;
+        }
+        self.pending.push_back(event);
+    }
+
+    fn skip(&mut self) {
+        if self.skipped == 0 {
+            log::warn!(
+                "Subscriber is {} events behind; skipping snapshots until it catches up",
+                SUBSCRIBER_BUFFER
+            );
+        }
+        self.skipped += 1;
+    }
+}
+
+/// Cheap, cloneable read handle on the snapshots a `MonitorService`
+/// publishes. Readers never wait on a refresh: the loop builds each
+/// snapshot without touching the handle and only swaps the pointer in,
+/// so a read costs one `Arc` clone.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::service::MonitorService;
+///
+/// let service = MonitorService::builder().build();
+/// let snapshots = service.snapshots();
+/// assert_eq!(snapshots.generation(), 0);
+/// assert!(snapshots.latest().is_none());
+/// ```
+#[derive(Clone, Default)]
+pub struct SnapshotReader {
+    inner: Arc<Published>,
+}
+
+#[derive(Default)]
+struct Published {
+    current: RwLock<Option<Arc<SystemSnapshot>>>,
+    generation: AtomicU64,
+}
+
+impl SnapshotReader {
+    /// Most recently published snapshot; `None` before the first.
+    pub fn latest(&self) -> Option<Arc<SystemSnapshot>> {
+        self.inner
+            .current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Number of snapshots published so far. Changes whenever `latest`
+    /// does, so pollers can skip work when nothing is new.
+    pub fn generation(&self) -> u64 {
+        self.inner.generation.load(Ordering::Acquire)
+    }
+
+    fn store(&self, snapshot: Arc<SystemSnapshot>) {
+        let mut current = self
+            .inner
+            .current
+            .write()
+            .unwrap_or_else(|e| e.into_inner());
+        *current = Some(snapshot);
+        self.inner.generation.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+struct Shared {
+    monitor: Arc<Mutex<SystemMonitor>>,
+    interval_ms: AtomicU64,
+    tick: Duration,
+    paused: AtomicBool,
+    stop: AtomicBool,
+    refresh_requested: AtomicBool,
+    disk_rescan_requested: AtomicBool,
+    adaptive: Mutex<AdaptiveScheduler>,
+    visibility: Mutex<Option<VisibilitySource>>,
+    subscribers: Mutex<Vec<Subscriber>>,
+    published: SnapshotReader,
+    alerts: Mutex<AlertEngine>,
+    actions: Mutex<ActionDispatcher>,
+    remediation: Mutex<RemediationEngine>,
+    recorder: Mutex<Option<Recorder>>,
+    /// Last files of finished recordings still being gzipped; `stop`
+    /// waits for them.
+    compressing: Mutex<Vec<PendingCompression>>,
+    push: Mutex<Option<PushExporter>>,
+    source: Mutex<Option<Box<dyn MetricsSource>>>,
+}
+
+/// Locks in the service only guard plain data, so a poisoned lock is
+/// recovered rather than propagated.
+fn lock<T>(m: &Mutex<T>) -> MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Shared {
+    fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.load(Ordering::Acquire))
+    }
+
+    fn publish(&self, event: ServiceEvent) {
+        lock(&self.subscribers).retain_mut(|sub| sub.deliver(&event));
+    }
+
+    /// Finish `recorder` without waiting for its last file to be
+    /// compressed, so neither the loop nor a UI thread blocks on gzip.
+    fn finish_recording(&self, recorder: Recorder) -> RecordingStatus {
+        let (status, pending) = recorder.finish_in_background();
+        let mut compressing = lock(&self.compressing);
+        compressing.retain(|pending| !pending.is_finished());
+        compressing.push(pending);
+        status
+    }
+
+    /// Hand `snapshot` to the recorder, if one is running, and stop it
+    /// when it expires or fails.
+    fn record(&self, snapshot: &SystemSnapshot) {
+        let mut recorder = lock(&self.recorder);
+        let now = unix_now();
+        let result = match recorder.as_mut() {
+            Some(active) => match active.record(snapshot, now) {
+                Ok(()) if !active.expired(now) => return,
+                result => result,
+            },
+            None => return,
+        };
+        let finished = recorder.take().expect("checked above");
+        drop(recorder);
+        let mut status = self.finish_recording(finished);
+        if let Err(e) = result {
+            log::warn!("Recording stopped: {}", e);
+            status.error = Some(e.to_string());
+        }
+        self.publish(ServiceEvent::RecordingStopped(status));
+    }
+
+    fn run(&self) {
+        let mut poison_alerted = false;
+        while !self.stop.load(Ordering::Acquire) {
+            let forced = self.refresh_requested.swap(false, Ordering::AcqRel);
+            let rescan = self.disk_rescan_requested.swap(false, Ordering::AcqRel);
+            let replayed = lock(&self.source)
+                .as_mut()
+                .map(|source| source.poll(Instant::now()));
+            match replayed {
+                Some(Some(snapshot)) => {
+                    let snapshot = Arc::new(snapshot);
+                    self.published.store(snapshot.clone());
+                    self.publish(ServiceEvent::Snapshot(snapshot));
+                }
+                Some(None) => {}
+                None if forced || rescan || !self.paused.load(Ordering::Acquire) => {
+                    self.cycle(forced, rescan, &mut poison_alerted);
+                }
+                None => {}
+            }
+            // Woken early by stop(), resume() and the request_* methods.
+            thread::park_timeout(self.tick);
+        }
+    }
+
+    fn cycle(&self, forced: bool, rescan: bool, poison_alerted: &mut bool) {
+        // Resolve visibility and power before taking the monitor lock:
+        // both may be slow (window queries, sysfs reads).
+        let visibility = lock(&self.visibility).clone();
+        let visible = visibility.map(|source| source());
+        let any_view_visible = !matches!(&visible, Some(v) if v.is_empty());
+        let power = lock(&self.adaptive).power_source();
+
+        let refreshed = {
+            let mut mon = self.monitor.lock().unwrap_or_else(|e| {
+                log::warn!("Monitor mutex was poisoned, recovering: {}", e);
+                if !*poison_alerted {
+                    *poison_alerted = true;
+                    self.publish(ServiceEvent::Recovered(
+                        "Monitor recovered from internal error — data may be temporarily stale"
+                            .to_string(),
+                    ));
+                }
+                e.into_inner()
+            });
+            mon.set_global_interval(self.interval());
+            if let Some(visible) = visible {
+                mon.set_visible(visible);
+            }
+            let decision = lock(&self.adaptive).decide(AdaptiveInputs {
+                any_view_visible,
+                power,
+                cpu_usage: mon.global_cpu_usage(),
+            });
+            if decision != mon.adaptive {
+                log::info!(
+                    "Adaptive mode: {:?}, interval x{}",
+                    decision.mode,
+                    decision.interval_multiplier
+                );
+            }
+            mon.set_adaptive(decision);
+
+            if rescan {
+                mon.refresh_disks_now();
+            }
+            let refreshed = match forced {
+                true => mon.refresh(),
+                false => mon.refresh_due(),
+            };
+            // Due subsystems are refreshed either way; a rescan alone
+            // still has new disks to publish.
+            let any_due = forced || !refreshed.is_empty() || rescan;
+            any_due.then(|| {
+                let now = Instant::now();
+                // Policies act on processes, so they only run against a
+                // process table refreshed this cycle. In minimal mode it
+                // isn't, and hold timers would otherwise keep running
+                // on stale usage.
+                let remediation = match refreshed.contains(&Subsystem::Processes) {
+                    true => lock(&self.remediation).evaluate(&mut mon, now),
+                    false => Vec::new(),
+                };
+                (
+                    Arc::new(SystemSnapshot::capture(&mon)),
+                    mon.take_link_events(),
+                    lock(&self.alerts).evaluate(&mon, now),
+                    remediation,
+                )
+            })
+        };
+
+        if let Some((snapshot, link_events, alerts, remediation)) = refreshed {
+            self.published.store(snapshot.clone());
+            self.record(&snapshot);
+            if let Some(exporter) = lock(&self.push).as_mut() {
+                exporter.offer(&snapshot, Instant::now(), unix_now());
+            }
+            self.publish(ServiceEvent::Snapshot(snapshot));
+            for event in link_events {
+                self.publish(ServiceEvent::LinkChanged(event));
+            }
+            if !alerts.is_empty() {
+                let engine = lock(&self.alerts);
+                let mut actions = lock(&self.actions);
+                let now = Instant::now();
+                for event in &alerts {
+                    if let Some(rule) = engine.rule(&event.rule) {
+                        actions.dispatch(&rule.actions, event, now);
+                    }
+                }
+            }
+            for event in alerts {
+                self.publish(ServiceEvent::Alert(event));
+            }
+            for event in remediation {
+                self.publish(ServiceEvent::Remediation(event));
+            }
+        }
+    }
+}
+
+/// Owns the refresh loop for a `SystemMonitor` and publishes snapshots
+/// to any number of subscribers, so the desktop app and library
+/// consumers share one scheduler instead of each polling `refresh()`.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::service::{MonitorService, ServiceEvent};
+/// use std::time::Duration;
+///
+/// let service = MonitorService::builder()
+///     .interval(Duration::from_millis(500))
+///     .build();
+/// let events = service.subscribe();
+/// service.start();
+/// if let Ok(ServiceEvent::Snapshot(snapshot)) = events.recv_timeout(Duration::from_secs(5)) {
+///     println!("CPU: {:.1}%", snapshot.cpu_usage);
+/// }
+/// service.stop();
+/// ```
+pub struct MonitorService {
+    shared: Arc<Shared>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// Configures a `MonitorService` before it's built.
+pub struct MonitorServiceBuilder {
+    monitor: Option<SystemMonitor>,
+    interval: Duration,
+    tick: Duration,
+    schedule: Option<RefreshSchedule>,
+    adaptive: AdaptiveConfig,
+    leaks: Option<LeakConfig>,
+    disk_forecast: Option<ForecastConfig>,
+    anomalies: Option<AnomalyConfig>,
+    paused: bool,
+}
+
+impl Default for MonitorServiceBuilder {
+    fn default() -> Self {
+        Self {
+            monitor: None,
+            interval: Duration::from_secs(2),
+            tick: DEFAULT_TICK,
+            schedule: None,
+            adaptive: AdaptiveConfig::default(),
+            leaks: None,
+            disk_forecast: None,
+            anomalies: None,
+            paused: false,
+        }
+    }
+}
+
+impl MonitorServiceBuilder {
+    /// Use an existing monitor instead of creating one at `build`.
+    pub fn monitor(mut self, monitor: SystemMonitor) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Global refresh interval; see `MonitorService::set_interval`.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// How often the loop checks for due subsystems. Defaults to
+    /// `DEFAULT_TICK`.
+    pub fn tick(mut self, tick: Duration) -> Self {
+        self.tick = tick;
+        self
+    }
+
+    pub fn schedule(mut self, schedule: RefreshSchedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    pub fn adaptive(mut self, config: AdaptiveConfig) -> Self {
+        self.adaptive = config;
+        self
+    }
+
+    /// Thresholds for `SystemMonitor::leaks`.
+    pub fn leak_detection(mut self, config: LeakConfig) -> Self {
+        self.leaks = Some(config);
+        self
+    }
+
+    /// Settings for `SystemMonitor::disk_forecasts`.
+    pub fn disk_forecast(mut self, config: ForecastConfig) -> Self {
+        self.disk_forecast = Some(config);
+        self
+    }
+
+    /// Sensitivity of `SystemMonitor::anomalies` and of pinned watches'
+    /// baselines.
+    pub fn anomaly_detection(mut self, config: AnomalyConfig) -> Self {
+        self.anomalies = Some(config);
+        self
+    }
+
+    /// Start paused; only `request_refresh` refreshes until `resume`.
+    pub fn paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    /// Build the service. The loop doesn't run until `start`.
+    pub fn build(self) -> MonitorService {
+        let mut monitor = self.monitor.unwrap_or_default();
+        if let Some(schedule) = self.schedule {
+            monitor.set_schedule(schedule);
+        }
+        if let Some(leaks) = self.leaks {
+            monitor.leaks.set_config(leaks);
+        }
+        if let Some(forecast) = self.disk_forecast {
+            monitor.disk_forecasts.set_config(forecast);
+        }
+        if let Some(anomalies) = self.anomalies {
+            monitor.anomalies.set_config(anomalies);
+            monitor.watches.anomaly_config = anomalies;
+        }
+        let shared = Shared {
+            monitor: Arc::new(Mutex::new(monitor)),
+            interval_ms: AtomicU64::new(clamp_interval(self.interval).as_millis() as u64),
+            tick: self.tick,
+            paused: AtomicBool::new(self.paused),
+            stop: AtomicBool::new(false),
+            refresh_requested: AtomicBool::new(false),
+            disk_rescan_requested: AtomicBool::new(false),
+            adaptive: Mutex::new(AdaptiveScheduler::new(self.adaptive)),
+            visibility: Mutex::new(None),
+            subscribers: Mutex::new(Vec::new()),
+            published: SnapshotReader::default(),
+            alerts: Mutex::new(AlertEngine::default()),
+            actions: Mutex::new(ActionDispatcher::new()),
+            remediation: Mutex::new(RemediationEngine::default()),
+            recorder: Mutex::new(None),
+            compressing: Mutex::new(Vec::new()),
+            push: Mutex::new(None),
+            source: Mutex::new(None),
+        };
+        MonitorService {
+            shared: Arc::new(shared),
+            worker: Mutex::new(None),
+        }
+    }
+}
+
+fn clamp_interval(interval: Duration) -> Duration {
+    interval.max(Duration::from_millis(MIN_SUBSYSTEM_INTERVAL_MS))
+}
+
+impl MonitorService {
+    pub fn builder() -> MonitorServiceBuilder {
+        MonitorServiceBuilder::default()
+    }
+
+    /// Spawn the refresh loop. Calling it again while running is a no-op.
+    pub fn start(&self) {
+        let mut worker = lock(&self.worker);
+        if worker.is_some() {
+            return;
+        }
+        self.shared.stop.store(false, Ordering::Release);
+        let shared = self.shared.clone();
+        let handle = thread::Builder::new()
+            .name("monitor-service".to_string())
+            .spawn(move || shared.run())
+            .expect("failed to spawn monitor-service thread");
+        *worker = Some(handle);
+    }
+
+    /// Stop the loop and wait for it to exit, and for finished
+    /// recordings to be compressed. Subscribers are dropped, so their
+    /// receivers see the channel close.
+    pub fn stop(&self) {
+        let handle = lock(&self.worker).take();
+        if let Some(handle) = handle {
+            self.shared.stop.store(true, Ordering::Release);
+            handle.thread().unpark();
+            let _ = handle.join();
+        }
+        lock(&self.shared.subscribers).clear();
+        let compressing = std::mem::take(&mut *lock(&self.shared.compressing));
+        for pending in compressing {
+            pending.wait();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        lock(&self.worker).is_some()
+    }
+
+    /// The monitor the loop refreshes, for queries and actions that
+    /// aren't part of a snapshot (process details, kill). Reads of
+    /// snapshot data should go through `snapshots` instead, which never
+    /// waits for a refresh to finish.
+    pub fn monitor(&self) -> Arc<Mutex<SystemMonitor>> {
+        self.shared.monitor.clone()
+    }
+
+    /// Subscribe to snapshots and events. The latest snapshot, if any,
+    /// is delivered immediately so late subscribers don't start empty.
+    /// A subscriber that stops draining misses snapshots once
+    /// `SUBSCRIBER_BUFFER` events are queued; alerts, remediation and
+    /// other events are held for it, up to another `SUBSCRIBER_BUFFER`
+    /// before the oldest are dropped. Dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> Receiver<ServiceEvent> {
+        let (tx, rx) = mpsc::sync_channel(SUBSCRIBER_BUFFER);
+        self.add_subscriber(Subscriber::new(Channel::Sync(tx)));
+        rx
+    }
+
+    /// Like `subscribe`, but as an async `Stream` for use from an
+    /// executor. Requires the `async` feature.
+    #[cfg(feature = "async")]
+    pub fn subscribe_stream(
+        &self,
+    ) -> impl futures_core::Stream<Item = ServiceEvent> + Unpin + Send {
+        let (tx, rx) = futures_channel::mpsc::channel(SUBSCRIBER_BUFFER);
+        self.add_subscriber(Subscriber::new(Channel::Async(tx)));
+        rx
+    }
+
+    fn add_subscriber(&self, mut subscriber: Subscriber) {
+        if let Some(latest) = self.latest() {
+            subscriber.deliver(&ServiceEvent::Snapshot(latest));
+        }
+        lock(&self.shared.subscribers).push(subscriber);
+    }
+
+    /// Most recently published snapshot.
+    pub fn latest(&self) -> Option<Arc<SystemSnapshot>> {
+        self.shared.published.latest()
+    }
+
+    /// A read handle on published snapshots that can be kept and shared
+    /// independently of the service.
+    pub fn snapshots(&self) -> SnapshotReader {
+        self.shared.published.clone()
+    }
+
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::Release);
+    }
+
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::Release);
+        self.wake();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.shared.paused.load(Ordering::Acquire)
+    }
+
+    /// Set the global refresh interval, used by subsystems that follow
+    /// it. Raised to `MIN_SUBSYSTEM_INTERVAL_MS` if shorter.
+    pub fn set_interval(&self, interval: Duration) {
+        self.shared.interval_ms.store(
+            clamp_interval(interval).as_millis() as u64,
+            Ordering::Release,
+        );
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.shared.interval()
+    }
+
+    /// Ask for a full refresh on the next tick, even while paused.
+    /// Several requests before the loop gets to it collapse into one.
+    pub fn request_refresh(&self) {
+        self.shared.refresh_requested.store(true, Ordering::Release);
+        self.wake();
+    }
+
+    /// Ask for the disk list to be re-enumerated on the next tick (see
+    /// `SystemMonitor::refresh_disks_now`) and the result published,
+    /// even while paused. Requests coalesce like `request_refresh`.
+    pub fn request_disk_rescan(&self) {
+        self.shared
+            .disk_rescan_requested
+            .store(true, Ordering::Release);
+        self.wake();
+    }
+
+    /// Replace the alert rules checked after each refresh. Rules are
+    /// compiled first, so an invalid condition leaves the current rules
+    /// in place. Transitions are published as `ServiceEvent::Alert` and
+    /// run the rule's actions.
+    pub fn set_alert_rules(&self, rules: &[AlertRule]) -> Result<(), DashboardError> {
+        let engine = AlertEngine::new(rules)?;
+        lock(&self.shared.actions).retain_rules(|name| engine.rule(name).is_some());
+        *lock(&self.shared.alerts) = engine;
+        Ok(())
+    }
+
+    /// Replace the remediation policies and safeguards. Policies are
+    /// compiled first, so an invalid one leaves the current settings in
+    /// place. Hold timers and the in-memory audit trail start over.
+    pub fn set_remediation_config(&self, config: RemediationConfig) -> Result<(), DashboardError> {
+        let engine = RemediationEngine::new(config)?;
+        *lock(&self.shared.remediation) = engine;
+        Ok(())
+    }
+
+    /// Recent remediation audit entries, oldest first.
+    pub fn remediation_log(&self) -> Vec<RemediationEvent> {
+        lock(&self.shared.remediation).log().cloned().collect()
+    }
+
+    /// Start appending snapshots to rotating files, replacing (and
+    /// finishing) any recording in progress.
+    pub fn start_recording(
+        &self,
+        config: RecordingConfig,
+    ) -> Result<RecordingStatus, DashboardError> {
+        let recorder = Recorder::new(config, unix_now())?;
+        let status = recorder.status();
+        let previous = lock(&self.shared.recorder).replace(recorder);
+        if let Some(previous) = previous {
+            self.shared.finish_recording(previous);
+        }
+        Ok(status)
+    }
+
+    /// Stop recording and close the last file, which is compressed in
+    /// the background. Returns the final status, or `None` if nothing
+    /// was being recorded.
+    pub fn stop_recording(&self) -> Option<RecordingStatus> {
+        let recorder = lock(&self.shared.recorder).take();
+        recorder.map(|recorder| self.shared.finish_recording(recorder))
+    }
+
+    pub fn recording_status(&self) -> Option<RecordingStatus> {
+        lock(&self.shared.recorder).as_ref().map(Recorder::status)
+    }
+
+    /// Replace the push exporters. The config is checked first, so an
+    /// invalid one leaves the current exporters running; no targets
+    /// turns pushing off.
+    pub fn set_push_config(&self, config: PushConfig) -> Result<(), DashboardError> {
+        let exporter = match config.targets.is_empty() {
+            true => None,
+            false => {
+                let hostname = sysinfo::System::host_name().unwrap_or_default();
+                Some(PushExporter::start(Pusher::new(config, hostname)?)?)
+            }
+        };
+        *lock(&self.shared.push) = exporter;
+        Ok(())
+    }
+
+    /// Publish snapshots from `source` instead of refreshing the live
+    /// monitor. Alerts, remediation, recording and pushing pause until
+    /// `clear_source`.
+    pub fn set_source(&self, source: impl MetricsSource + 'static) {
+        *lock(&self.shared.source) = Some(Box::new(source));
+        self.wake();
+    }
+
+    /// Go back to live data, refreshing right away.
+    pub fn clear_source(&self) {
+        if lock(&self.shared.source).take().is_some() {
+            self.request_refresh();
+        }
+    }
+
+    pub fn has_source(&self) -> bool {
+        lock(&self.shared.source).is_some()
+    }
+
+    /// Install what `notify` alert actions call to show a desktop
+    /// notification. Without one those actions fail and are logged.
+    pub fn set_notifier(
+        &self,
+        notifier: impl Fn(&AlertEvent) -> Result<(), String> + Send + Sync + 'static,
+    ) {
+        lock(&self.shared.actions).set_notifier(notifier);
+    }
+
+    /// Install the callback the loop uses each tick to learn which
+    /// subsystems are visible. Without one, everything counts as
+    /// visible.
+    pub fn set_visibility_source(
+        &self,
+        source: impl Fn() -> HashSet<Subsystem> + Send + Sync + 'static,
+    ) {
+        *lock(&self.shared.visibility) = Some(Arc::new(source));
+    }
+
+    pub fn set_adaptive_config(&self, config: AdaptiveConfig) {
+        lock(&self.shared.adaptive).set_config(config);
+    }
+
+    fn wake(&self) {
+        if let Some(handle) = lock(&self.worker).as_ref() {
+            handle.thread().unpark();
+        }
+    }
+}
+
+impl Drop for MonitorService {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
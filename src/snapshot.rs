@@ -0,0 +1,283 @@
+use crate::adaptive::{AdaptiveDecision, RefreshCost};
+use crate::config::Subsystem;
+use crate::network::InterfaceDetails;
+use crate::system::{CombinedProcess, SystemMonitor};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Point-in-time copy of everything the dashboard shows, built from a
+/// `SystemMonitor` after a refresh. This is what `MonitorService`
+/// publishes to subscribers and what `delta::DeltaEncoder` encodes for
+/// the desktop app's `system-delta` events.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SystemSnapshot {
+    pub cpu_usage: f32,
+    pub per_cpu: Vec<f32>,
+    pub memory: MemoryInfo,
+    pub disks: Vec<DiskInfo>,
+    pub networks: Vec<NetworkInfo>,
+    pub processes: Vec<CombinedProcess>,
+    /// CPU and memory of the process taking the snapshot.
+    pub self_usage: Option<SelfUsage>,
+    pub uptime_seconds: u64,
+    pub load_average: (f64, f64, f64),
+    pub overhead: MonitorOverhead,
+    /// System series (`anomaly::SYSTEM_SERIES`) whose latest point is
+    /// anomalous.
+    #[serde(default)]
+    pub anomalies: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SelfUsage {
+    pub cpu: f32,
+    pub memory: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryInfo {
+    pub used: u64,
+    pub free: u64,
+    pub total: u64,
+    pub available: u64,
+    pub swap_used: u64,
+    pub swap_total: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiskInfo {
+    pub name: String,
+    pub filesystem: String,
+    pub mount_point: String,
+    pub used: u64,
+    pub available: u64,
+    pub total: u64,
+    /// Usage trend in bytes per hour, once the mount has enough history;
+    /// see `forecast::DiskForecaster`.
+    #[serde(default)]
+    pub growth_bytes_per_hour: Option<f64>,
+    /// Estimated seconds until the mount is full at that trend.
+    #[serde(default)]
+    pub full_in_seconds: Option<f64>,
+}
+
+impl DiskInfo {
+    /// Current mounts with their forecasts.
+    pub fn list(monitor: &SystemMonitor) -> Vec<DiskInfo> {
+        monitor
+            .disk_info()
+            .into_iter()
+            .map(|(name, filesystem, mount_point, used, available, total)| {
+                let forecast = monitor.disk_forecasts.forecast(&mount_point);
+                DiskInfo {
+                    growth_bytes_per_hour: forecast.map(|f| f.growth_bytes_per_hour),
+                    full_in_seconds: forecast.and_then(|f| f.seconds_until_full),
+                    name,
+                    filesystem,
+                    mount_point,
+                    used,
+                    available,
+                    total,
+                }
+            })
+            .collect()
+    }
+}
+
+/// An interface in a snapshot: what `SystemMonitor::network_interfaces`
+/// reports.
+pub type NetworkInfo = InterfaceDetails;
+
+/// What machine a snapshot came from. Static for the life of the
+/// process, so it's kept out of `SystemSnapshot` and the delta stream.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HostInfo {
+    pub hostname: String,
+    pub os: String,
+    pub os_version: String,
+    pub kernel_version: String,
+    pub cpu_brand: String,
+    pub cpu_count: usize,
+}
+
+impl HostInfo {
+    /// # Example
+    /// ```
+    /// use rust_dashboard_lib::snapshot::HostInfo;
+    /// use rust_dashboard_lib::system::SystemMonitor;
+    ///
+    /// let host = HostInfo::current(&SystemMonitor::new());
+    /// assert!(host.cpu_count > 0);
+    /// ```
+    pub fn current(monitor: &SystemMonitor) -> Self {
+        let cpus = monitor.sys.cpus();
+        Self {
+            hostname: sysinfo::System::host_name().unwrap_or_default(),
+            os: sysinfo::System::name().unwrap_or_default(),
+            os_version: sysinfo::System::os_version().unwrap_or_default(),
+            kernel_version: sysinfo::System::kernel_version().unwrap_or_default(),
+            cpu_brand: cpus
+                .first()
+                .map(|cpu| cpu.brand().trim().to_string())
+                .unwrap_or_default(),
+            cpu_count: cpus.len(),
+        }
+    }
+}
+
+/// Current adaptive mode and what the monitor's own refreshes cost.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MonitorOverhead {
+    #[serde(flatten)]
+    pub decision: AdaptiveDecision,
+    pub cost: RefreshCost,
+}
+
+impl SystemSnapshot {
+    /// Copy the monitor's current state. Doesn't refresh anything.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_dashboard_lib::snapshot::SystemSnapshot;
+    /// use rust_dashboard_lib::system::SystemMonitor;
+    ///
+    /// let monitor = SystemMonitor::new();
+    /// let snapshot = SystemSnapshot::capture(&monitor);
+    /// assert!(snapshot.memory.total > 0);
+    /// ```
+    pub fn capture(monitor: &SystemMonitor) -> Self {
+        let (used, free, total, available, swap_used, swap_total) = monitor.memory_info();
+        let per_cpu: Vec<f32> = monitor
+            .sys
+            .cpus()
+            .iter()
+            .map(|cpu| cpu.cpu_usage())
+            .collect();
+        let self_usage = monitor
+            .usage_for_pid(std::process::id())
+            .map(|(cpu, mem)| SelfUsage { cpu, memory: mem });
+
+        SystemSnapshot {
+            cpu_usage: monitor.global_cpu_usage(),
+            per_cpu,
+            memory: MemoryInfo {
+                used,
+                free,
+                total,
+                available,
+                swap_used,
+                swap_total,
+            },
+            disks: DiskInfo::list(monitor),
+            networks: monitor.network_interfaces(),
+            processes: monitor.combined_process_list().to_vec(),
+            self_usage,
+            uptime_seconds: monitor.system_uptime(),
+            load_average: monitor.load_average(),
+            overhead: MonitorOverhead {
+                decision: monitor.adaptive,
+                cost: monitor.refresh_cost().clone(),
+            },
+            anomalies: monitor.anomalies.anomalous(),
+        }
+    }
+}
+
+/// The parts of a snapshot one consumer renders, e.g. a single window.
+/// CPU and memory totals, uptime, load, overhead and anomalies are
+/// always kept; they're a few bytes and every view's header shows them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotInterest {
+    /// `Cpu` keeps `per_cpu`, `Processes` the process list, `Disks` and
+    /// `Networks` their sections. `Memory` only matters for refresh
+    /// scheduling; memory totals are always kept.
+    pub subsystems: HashSet<Subsystem>,
+    /// Keep only the N busiest process groups by CPU. `None` keeps all.
+    pub top_processes: Option<usize>,
+}
+
+impl Default for SnapshotInterest {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl SnapshotInterest {
+    /// Everything, untrimmed.
+    pub fn all() -> Self {
+        SnapshotInterest {
+            subsystems: Subsystem::ALL.into_iter().collect(),
+            top_processes: None,
+        }
+    }
+
+    pub fn wants(&self, subsystem: Subsystem) -> bool {
+        self.subsystems.contains(&subsystem)
+    }
+
+    /// Whether this consumer shows per-process network estimates, which
+    /// takes both `Processes` and `Networks`. The monitor only samples
+    /// them (a walk of every process's file descriptors) while a visible
+    /// view does.
+    pub fn wants_process_net(&self) -> bool {
+        self.wants(Subsystem::Processes) && self.wants(Subsystem::Networks)
+    }
+
+    /// Copy of `snapshot` with the sections this consumer doesn't render
+    /// left empty.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_dashboard_lib::config::Subsystem;
+    /// use rust_dashboard_lib::snapshot::{SnapshotInterest, SystemSnapshot};
+    /// use rust_dashboard_lib::system::SystemMonitor;
+    ///
+    /// let interest = SnapshotInterest {
+    ///     subsystems: [Subsystem::Networks].into_iter().collect(),
+    ///     top_processes: None,
+    /// };
+    /// let snapshot = interest.apply(&SystemSnapshot::capture(&SystemMonitor::new()));
+    /// assert!(snapshot.processes.is_empty());
+    /// ```
+    pub fn apply(&self, snapshot: &SystemSnapshot) -> SystemSnapshot {
+        let processes = if self.wants(Subsystem::Processes) {
+            match self.top_processes {
+                Some(n) => {
+                    let mut top = snapshot.processes.clone();
+                    top.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+                    top.truncate(n);
+                    top
+                }
+                None => snapshot.processes.clone(),
+            }
+        } else {
+            Vec::new()
+        };
+        SystemSnapshot {
+            cpu_usage: snapshot.cpu_usage,
+            per_cpu: if self.wants(Subsystem::Cpu) {
+                snapshot.per_cpu.clone()
+            } else {
+                Vec::new()
+            },
+            memory: snapshot.memory.clone(),
+            disks: if self.wants(Subsystem::Disks) {
+                snapshot.disks.clone()
+            } else {
+                Vec::new()
+            },
+            networks: if self.wants(Subsystem::Networks) {
+                snapshot.networks.clone()
+            } else {
+                Vec::new()
+            },
+            processes,
+            self_usage: snapshot.self_usage.clone(),
+            uptime_seconds: snapshot.uptime_seconds,
+            load_average: snapshot.load_average,
+            overhead: snapshot.overhead.clone(),
+            anomalies: snapshot.anomalies.clone(),
+        }
+    }
+}
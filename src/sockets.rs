@@ -0,0 +1,392 @@
+use crate::error::DashboardError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+/// Default procfs mount point. Tests pass a fixture directory instead.
+pub const PROC_ROOT: &str = "/proc";
+
+/// Socket family/transport, one per `/proc/net` table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketProtocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+    Unix,
+}
+
+impl SocketProtocol {
+    /// File name of this protocol's table under `<proc>/net/`.
+    pub fn table_name(self) -> &'static str {
+        match self {
+            SocketProtocol::Tcp => "tcp",
+            SocketProtocol::Tcp6 => "tcp6",
+            SocketProtocol::Udp => "udp",
+            SocketProtocol::Udp6 => "udp6",
+            SocketProtocol::Unix => "unix",
+        }
+    }
+
+    pub const ALL: [SocketProtocol; 5] = [
+        SocketProtocol::Tcp,
+        SocketProtocol::Tcp6,
+        SocketProtocol::Udp,
+        SocketProtocol::Udp6,
+        SocketProtocol::Unix,
+    ];
+}
+
+/// Socket state. TCP states follow `include/net/tcp_states.h`; UDP and
+/// Unix sockets only ever report `Listen`, `Established`/`Connected` or
+/// `Unconnected`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SocketState {
+    Established,
+    SynSent,
+    SynRecv,
+    FinWait1,
+    FinWait2,
+    TimeWait,
+    Close,
+    CloseWait,
+    LastAck,
+    Listen,
+    Closing,
+    Connected,
+    Unconnected,
+    Unknown,
+}
+
+impl SocketState {
+    fn from_tcp_code(code: u8) -> Self {
+        match code {
+            0x01 => SocketState::Established,
+            0x02 => SocketState::SynSent,
+            0x03 => SocketState::SynRecv,
+            0x04 => SocketState::FinWait1,
+            0x05 => SocketState::FinWait2,
+            0x06 => SocketState::TimeWait,
+            0x07 => SocketState::Close,
+            0x08 => SocketState::CloseWait,
+            0x09 => SocketState::LastAck,
+            0x0A => SocketState::Listen,
+            0x0B => SocketState::Closing,
+            _ => SocketState::Unknown,
+        }
+    }
+}
+
+/// One row of the connections view.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SocketEntry {
+    pub protocol: SocketProtocol,
+    /// IP address for inet sockets, bound path for Unix sockets (empty
+    /// for unnamed ones).
+    pub local_address: String,
+    pub local_port: Option<u16>,
+    /// `None` for listening/unconnected sockets.
+    pub remote_address: Option<String>,
+    pub remote_port: Option<u16>,
+    pub state: SocketState,
+    pub inode: u64,
+    /// Owning UID; not reported for Unix sockets.
+    pub uid: Option<u32>,
+    pub tx_queue: u64,
+    pub rx_queue: u64,
+    /// Owning process, resolved through `/proc/<pid>/fd`. `None` when the
+    /// owner isn't readable by the current user or has already exited.
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+}
+
+impl SocketEntry {
+    /// Listening TCP sockets and bound-but-unconnected UDP/Unix sockets.
+    pub fn is_listening(&self) -> bool {
+        match self.state {
+            SocketState::Listen => true,
+            SocketState::Unconnected | SocketState::Close => {
+                self.remote_address.is_none()
+                    && (self.local_port.is_some_and(|p| p != 0)
+                        || (self.protocol == SocketProtocol::Unix
+                            && !self.local_address.is_empty()))
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Filter for `read_socket_table` results. All set fields must match.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SocketFilter {
+    pub protocol: Option<SocketProtocol>,
+    pub state: Option<SocketState>,
+    /// Matches either the local or the remote port.
+    pub port: Option<u16>,
+    pub pid: Option<u32>,
+    /// Case-insensitive substring match on the owning process name.
+    pub process: Option<String>,
+    pub listening_only: bool,
+}
+
+impl SocketFilter {
+    pub fn matches(&self, entry: &SocketEntry) -> bool {
+        if self.protocol.is_some_and(|p| p != entry.protocol) {
+            return false;
+        }
+        if self.state.is_some_and(|s| s != entry.state) {
+            return false;
+        }
+        if let Some(port) = self.port {
+            if entry.local_port != Some(port) && entry.remote_port != Some(port) {
+                return false;
+            }
+        }
+        if self.pid.is_some() && self.pid != entry.pid {
+            return false;
+        }
+        if let Some(needle) = self.process.as_deref().filter(|n| !n.is_empty()) {
+            let needle = needle.to_lowercase();
+            match &entry.process_name {
+                Some(name) if name.to_lowercase().contains(&needle) => {}
+                _ => return false,
+            }
+        }
+        if self.listening_only && !entry.is_listening() {
+            return false;
+        }
+        true
+    }
+
+    pub fn apply(&self, entries: Vec<SocketEntry>) -> Vec<SocketEntry> {
+        entries.into_iter().filter(|e| self.matches(e)).collect()
+    }
+}
+
+/// Parse an `ADDR:PORT` pair from `/proc/net/{tcp,udp}[6]`. The address is
+/// the raw `in_addr`/`in6_addr` printed as host-order 32-bit words, so
+/// each word is converted back with native endianness.
+fn parse_inet_endpoint(field: &str) -> Option<(String, u16)> {
+    let (addr_hex, port_hex) = field.split_once(':')?;
+    let port = u16::from_str_radix(port_hex, 16).ok()?;
+    let addr = match addr_hex.len() {
+        8 => {
+            let word = u32::from_str_radix(addr_hex, 16).ok()?;
+            Ipv4Addr::from(word.to_ne_bytes()).to_string()
+        }
+        32 => {
+            let mut bytes = [0u8; 16];
+            for (i, chunk) in bytes.chunks_mut(4).enumerate() {
+                let word = u32::from_str_radix(&addr_hex[i * 8..i * 8 + 8], 16).ok()?;
+                chunk.copy_from_slice(&word.to_ne_bytes());
+            }
+            Ipv6Addr::from(bytes).to_string()
+        }
+        _ => return None,
+    };
+    Some((addr, port))
+}
+
+fn parse_queue(field: &str) -> (u64, u64) {
+    let (tx, rx) = field.split_once(':').unwrap_or(("0", "0"));
+    (
+        u64::from_str_radix(tx, 16).unwrap_or(0),
+        u64::from_str_radix(rx, 16).unwrap_or(0),
+    )
+}
+
+/// Parse the contents of `/proc/net/{tcp,tcp6,udp,udp6}`. Malformed lines
+/// are skipped.
+pub fn parse_inet_table(contents: &str, protocol: SocketProtocol) -> Vec<SocketEntry> {
+    let is_tcp = matches!(protocol, SocketProtocol::Tcp | SocketProtocol::Tcp6);
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                return None;
+            }
+            let (local_address, local_port) = parse_inet_endpoint(fields[1])?;
+            let (remote_addr, remote_port) = parse_inet_endpoint(fields[2])?;
+            let code = u8::from_str_radix(fields[3], 16).ok()?;
+            let (tx_queue, rx_queue) = parse_queue(fields[4]);
+            let uid = fields[7].parse::<u32>().ok();
+            let inode = fields[9].parse::<u64>().ok()?;
+
+            let unconnected = remote_port == 0;
+            let state = if is_tcp {
+                SocketState::from_tcp_code(code)
+            } else if code == 0x01 {
+                SocketState::Established
+            } else {
+                SocketState::Unconnected
+            };
+
+            Some(SocketEntry {
+                protocol,
+                local_address,
+                local_port: Some(local_port),
+                remote_address: (!unconnected).then_some(remote_addr),
+                remote_port: (!unconnected).then_some(remote_port),
+                state,
+                inode,
+                uid,
+                tx_queue,
+                rx_queue,
+                pid: None,
+                process_name: None,
+            })
+        })
+        .collect()
+}
+
+/// `__SO_ACCEPTCON` in the Unix table's flags column marks a listening
+/// socket.
+const UNIX_ACCEPTCON: u32 = 0x0001_0000;
+
+/// Parse the contents of `/proc/net/unix`. Malformed lines are skipped.
+pub fn parse_unix_table(contents: &str) -> Vec<SocketEntry> {
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let flags = u32::from_str_radix(fields[3], 16).ok()?;
+            let st = u8::from_str_radix(fields[5], 16).ok()?;
+            let inode = fields[6].parse::<u64>().ok()?;
+            // The path is the rest of the line; abstract sockets start with
+            // '@' and paths may (rarely) contain spaces.
+            let path = if fields.len() > 7 {
+                fields[7..].join(" ")
+            } else {
+                String::new()
+            };
+            let state = if flags & UNIX_ACCEPTCON != 0 {
+                SocketState::Listen
+            } else if st == 0x03 {
+                SocketState::Connected
+            } else {
+                SocketState::Unconnected
+            };
+            Some(SocketEntry {
+                protocol: SocketProtocol::Unix,
+                local_address: path,
+                local_port: None,
+                remote_address: None,
+                remote_port: None,
+                state,
+                inode,
+                uid: None,
+                tx_queue: 0,
+                rx_queue: 0,
+                pid: None,
+                process_name: None,
+            })
+        })
+        .collect()
+}
+
+/// Parse a `socket:[41001]` file descriptor link.
+pub(crate) fn socket_inode(target: &Path) -> Option<u64> {
+    target
+        .to_str()?
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Map socket inodes to their owning `(pid, process name)` by scanning
+/// `<proc_root>/<pid>/fd`. Processes whose fd directory can't be read
+/// (other users' processes without privileges, or exited mid-scan) are
+/// skipped. When several processes share a socket (forked servers), the
+/// lowest PID wins so the result is deterministic.
+pub fn socket_inode_owners(proc_root: &Path) -> HashMap<u64, (u32, String)> {
+    let mut owners: HashMap<u64, (u32, String)> = HashMap::new();
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return owners;
+    };
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let mut name: Option<String> = None;
+        for fd in fds.flatten() {
+            let Some(inode) = fs::read_link(fd.path())
+                .ok()
+                .and_then(|target| socket_inode(&target))
+            else {
+                continue;
+            };
+            if owners.get(&inode).is_some_and(|&(owner, _)| owner < pid) {
+                continue;
+            }
+            let name = name
+                .get_or_insert_with(|| {
+                    fs::read_to_string(entry.path().join("comm"))
+                        .map(|s| s.trim_end().to_string())
+                        .unwrap_or_default()
+                })
+                .clone();
+            owners.insert(inode, (pid, name));
+        }
+    }
+    owners
+}
+
+/// Read every socket table under `<proc_root>/net` and attach owning
+/// processes. Tables that don't exist (e.g. `tcp6` with IPv6 disabled)
+/// are skipped; it's only an error if none could be read, which is the
+/// case on platforms without procfs and is reported as
+/// `DashboardError::SocketTableUnavailable`.
+pub fn read_socket_table(proc_root: &Path) -> Result<Vec<SocketEntry>, DashboardError> {
+    let mut entries = read_socket_entries(proc_root)?;
+    let owners = socket_inode_owners(proc_root);
+    for entry in &mut entries {
+        if let Some((pid, name)) = owners.get(&entry.inode) {
+            entry.pid = Some(*pid);
+            entry.process_name = Some(name.clone());
+        }
+    }
+    Ok(entries)
+}
+
+/// `read_socket_table` without resolving owners.
+pub(crate) fn read_socket_entries(proc_root: &Path) -> Result<Vec<SocketEntry>, DashboardError> {
+    let net = proc_root.join("net");
+    let mut entries = Vec::new();
+    let mut any_read = false;
+    for protocol in SocketProtocol::ALL {
+        let Ok(contents) = fs::read_to_string(net.join(protocol.table_name())) else {
+            continue;
+        };
+        any_read = true;
+        match protocol {
+            SocketProtocol::Unix => entries.extend(parse_unix_table(&contents)),
+            _ => entries.extend(parse_inet_table(&contents, protocol)),
+        }
+    }
+    if !any_read {
+        return Err(DashboardError::SocketTableUnavailable(format!(
+            "no socket tables found under {} (procfs is only available on Linux)",
+            net.display()
+        )));
+    }
+    Ok(entries)
+}
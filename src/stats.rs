@@ -0,0 +1,99 @@
+//! Small statistics helpers shared by the trend-based detectors.
+
+use serde::{Deserialize, Serialize};
+
+/// Residuals beyond this many median absolute deviations count as
+/// outliers when scoring a fit.
+const OUTLIER_MADS: f64 = 6.0;
+
+/// A straight-line fit to a series.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Trend {
+    /// Units per second.
+    pub slope: f64,
+    pub intercept: f64,
+    /// Fraction of variance explained by the line, clamped to 0–1,
+    /// ignoring outliers.
+    pub r_squared: f64,
+    /// Seconds between the first and last point.
+    pub span: f64,
+}
+
+impl Trend {
+    /// Value the line reaches at `x`.
+    pub fn at(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    let mid = values.len() / 2;
+    let (_, &mut upper, _) = values.select_nth_unstable_by(mid, f64::total_cmp);
+    if values.len() % 2 == 1 {
+        upper
+    } else {
+        let lower = values[..mid].iter().copied().fold(f64::MIN, f64::max);
+        (lower + upper) / 2.0
+    }
+}
+
+/// Theil–Sen fit: the slope is the median of all pairwise slopes, so a
+/// few outliers (a restart, a one-off allocation) don't swing it the way
+/// they would a least-squares fit. Needs at least two distinct x values.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::stats::trend;
+///
+/// let points = [(0.0, 10.0), (1.0, 12.0), (2.0, 14.0), (3.0, 100.0), (4.0, 18.0)];
+/// let fit = trend(&points).unwrap();
+/// assert!((fit.slope - 2.0).abs() < 1e-9);
+/// ```
+pub fn trend(points: &[(f64, f64)]) -> Option<Trend> {
+    let mut slopes = Vec::with_capacity(points.len() * points.len().saturating_sub(1) / 2);
+    for (i, &(x1, y1)) in points.iter().enumerate() {
+        for &(x2, y2) in &points[i + 1..] {
+            if x2 != x1 {
+                slopes.push((y2 - y1) / (x2 - x1));
+            }
+        }
+    }
+    if slopes.is_empty() {
+        return None;
+    }
+    let slope = median(&mut slopes);
+    let mut offsets: Vec<f64> = points.iter().map(|&(x, y)| y - slope * x).collect();
+    let intercept = median(&mut offsets);
+
+    // Score the fit on inliers only: points further from the line than
+    // a few median absolute deviations would otherwise dominate R².
+    let residuals: Vec<f64> = points
+        .iter()
+        .map(|&(x, y)| y - (intercept + slope * x))
+        .collect();
+    let mut deviations: Vec<f64> = residuals.iter().map(|r| r.abs()).collect();
+    let cutoff = OUTLIER_MADS * median(&mut deviations);
+    let inliers: Vec<(f64, f64)> = points
+        .iter()
+        .zip(&residuals)
+        .filter(|(_, r)| r.abs() <= cutoff)
+        .map(|(&(_, y), &r)| (y, r))
+        .collect();
+    let mean = inliers.iter().map(|&(y, _)| y).sum::<f64>() / inliers.len().max(1) as f64;
+    let total: f64 = inliers.iter().map(|&(y, _)| (y - mean).powi(2)).sum();
+    let residual: f64 = inliers.iter().map(|&(_, r)| r * r).sum();
+    let r_squared = if total > 0.0 {
+        (1.0 - residual / total).clamp(0.0, 1.0)
+    } else {
+        // A flat series is perfectly explained by a flat line.
+        1.0
+    };
+    let first = points.iter().map(|p| p.0).fold(f64::MAX, f64::min);
+    let last = points.iter().map(|p| p.0).fold(f64::MIN, f64::max);
+    Some(Trend {
+        slope,
+        intercept,
+        r_squared,
+        span: last - first,
+    })
+}
@@ -1,5 +1,29 @@
-use std::collections::HashMap;
-use sysinfo::{CpuRefreshKind, Disks, Networks, ProcessRefreshKind, System};
+use crate::adaptive::{AdaptiveDecision, RefreshCost};
+use crate::anomaly::AnomalyTracker;
+use crate::config::{RefreshSchedule, Subsystem};
+use crate::forecast::DiskForecaster;
+use crate::leaks::LeakDetector;
+use crate::network::{self, InterfaceDetails, LinkEvent, LinkInfo, LinkState};
+use crate::process_index::{ProcessIndex, ProcessSample};
+use crate::process_net::{ProcessNetSampler, ProcessNetUsage};
+use crate::watch::WatchList;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::{
+    CpuRefreshKind, Disks, IpNetwork, Networks, ProcessRefreshKind, System, UpdateKind, Users,
+};
+
+/// Seconds since the Unix epoch, the time base of recorded histories.
+pub fn unix_now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+/// Upper bound on undrained link events kept by `SystemMonitor`.
+const MAX_PENDING_LINK_EVENTS: usize = 256;
 
 /// System monitor that wraps sysinfo to provide system statistics.
 ///
@@ -9,6 +33,24 @@ pub struct SystemMonitor {
     pub sys: System,
     pub disks: Disks,
     pub networks: Networks,
+    /// User accounts, for resolving process owners. Listed once at
+    /// construction.
+    pub users: Users,
+    /// Per-subsystem cadence; see `refresh` and `refresh_due`.
+    pub schedule: RefreshSchedule,
+    /// Interval used by `refresh_due` for subsystems that follow the
+    /// global cadence.
+    pub global_interval: Duration,
+    /// Subsystems rendered by a visible view. Defaults to all of them.
+    pub visible: HashSet<Subsystem>,
+    /// Collection set and back-off chosen by an `AdaptiveScheduler`.
+    /// Defaults to the full schedule at its configured cadence.
+    pub adaptive: AdaptiveDecision,
+    /// Measured cost of this monitor's own refreshes.
+    pub refresh_cost: RefreshCost,
+    pub last_cpu_refresh: Instant,
+    pub last_memory_refresh: Instant,
+    pub last_process_refresh: Instant,
     pub last_disk_refresh: std::time::Instant,
     pub last_network_refresh: std::time::Instant,
     /// Duration captured at the most recent network refresh — the actual
@@ -17,13 +59,35 @@ pub struct SystemMonitor {
     /// when `network_info_with_rates` is queried relative to the refresh.
     pub last_network_interval: std::time::Duration,
     pub last_network_snapshot: HashMap<String, (u64, u64)>,
-    pub cached_processes: Vec<CombinedProcess>,
+    /// Link state, speed and drop counters per interface, read from sysfs
+    /// at each network refresh so snapshot building doesn't touch disk.
+    pub link_info: HashMap<String, LinkInfo>,
+    /// Link up/down transitions detected since the last
+    /// `take_link_events` call.
+    pub pending_link_events: Vec<LinkEvent>,
+    /// Estimates per-process network usage at each network refresh.
+    pub process_net: ProcessNetSampler,
+    /// Latest per-PID network estimates from `process_net`.
+    pub process_net_usage: HashMap<u32, ProcessNetUsage>,
+    /// Processes combined by name, updated in place at each process or
+    /// network refresh.
+    pub process_index: ProcessIndex,
+    /// Pinned processes and groups, sampled after each process refresh.
+    pub watches: WatchList,
+    /// Coarse per-group memory trends for leak detection.
+    pub leaks: LeakDetector,
+    /// Per-mount usage trends and time-until-full estimates, sampled at
+    /// disk refreshes.
+    pub disk_forecasts: DiskForecaster,
+    /// Baselines for the system-wide series in
+    /// `anomaly::SYSTEM_SERIES`, fed as each subsystem refreshes.
+    pub anomalies: AnomalyTracker,
 }
 
 /// A process that may have multiple instances (PIDs) combined together.
 ///
-/// CPU and memory usage are summed across all instances of the process.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+/// CPU, memory and network usage are summed across all instances of the process.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct CombinedProcess {
     /// Process name
     pub name: String,
@@ -33,6 +97,16 @@ pub struct CombinedProcess {
     pub memory_usage: u64,
     /// List of all PIDs for this process name
     pub pids: Vec<u32>,
+    /// Estimated receive rate in bytes/sec across all instances. See
+    /// `process_net::NetAttribution` for how it's derived.
+    #[serde(default)]
+    pub net_rx_rate: f64,
+    /// Estimated transmit rate in bytes/sec across all instances
+    #[serde(default)]
+    pub net_tx_rate: f64,
+    /// Active TCP/UDP connections across all instances
+    #[serde(default)]
+    pub connections: u32,
 }
 
 impl Default for SystemMonitor {
@@ -60,6 +134,15 @@ impl SystemMonitor {
             sys,
             disks,
             networks,
+            users: Users::new_with_refreshed_list(),
+            schedule: RefreshSchedule::default(),
+            global_interval: Duration::from_secs(2),
+            visible: Subsystem::ALL.into_iter().collect(),
+            adaptive: AdaptiveDecision::default(),
+            refresh_cost: RefreshCost::default(),
+            last_cpu_refresh: Instant::now(),
+            last_memory_refresh: Instant::now(),
+            last_process_refresh: Instant::now(),
             last_disk_refresh: std::time::Instant::now(),
             last_network_refresh: std::time::Instant::now(),
             // Seed with the refresh threshold so the first computed rate
@@ -69,17 +152,35 @@ impl SystemMonitor {
             // someone queries between construction and the first refresh.
             last_network_interval: std::time::Duration::from_secs(5),
             last_network_snapshot: HashMap::new(),
-            cached_processes: Vec::new(),
+            link_info: HashMap::new(),
+            pending_link_events: Vec::new(),
+            process_net: ProcessNetSampler::default(),
+            process_net_usage: HashMap::new(),
+            process_index: ProcessIndex::new(),
+            watches: WatchList::default(),
+            leaks: LeakDetector::default(),
+            disk_forecasts: DiskForecaster::default(),
+            anomalies: AnomalyTracker::default(),
         };
-        // Populate the cache on initialization
-        monitor.cached_processes = monitor.compute_combined_process_list();
+        // Seed link info without emitting events — every interface would
+        // otherwise be reported as "came up" on launch.
+        monitor.link_info = monitor.read_all_link_info();
+        // Seed namespace counters so the first real sample has a baseline.
+        monitor.refresh_process_net();
+        // Populate the process list on initialization
+        monitor.update_process_index();
         monitor
     }
 
     /// Refresh all system data.
     ///
     /// This should be called periodically to get up-to-date statistics.
-    /// Disk information is only refreshed every 60 seconds to reduce overhead.
+    /// Subsystems that follow the global interval (CPU, memory and
+    /// processes by default) are refreshed on every call; subsystems with
+    /// their own cadence in `schedule` (disks every 60 s and networks
+    /// every 5 s by default) only once it has elapsed. Subsystems
+    /// excluded by the current `adaptive` decision are skipped. Returns
+    /// the subsystems that were refreshed.
     ///
     /// # Example
     /// ```
@@ -87,23 +188,158 @@ impl SystemMonitor {
     /// let mut monitor = SystemMonitor::new();
     /// monitor.refresh();
     /// ```
-    pub fn refresh(&mut self) {
+    pub fn refresh(&mut self) -> Vec<Subsystem> {
         log::debug!("SystemMonitor: refresh() start");
-        self.do_refresh_cycle();
+        let refreshed = self.do_refresh_cycle(true);
         log::debug!("SystemMonitor: refresh() complete");
+        refreshed
+    }
+
+    /// Refresh only the subsystems whose cadence has elapsed, treating
+    /// "follow global" subsystems as due every `global_interval`.
+    ///
+    /// Intended for schedulers that tick faster than the global interval
+    /// (e.g. to honour a sub-second CPU cadence). Returns the subsystems
+    /// that were refreshed, which is empty when nothing was due.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_dashboard_lib::system::SystemMonitor;
+    /// let mut monitor = SystemMonitor::new();
+    /// let refreshed = monitor.refresh_due();
+    /// println!("refreshed {:?}", refreshed);
+    /// ```
+    pub fn refresh_due(&mut self) -> Vec<Subsystem> {
+        self.do_refresh_cycle(false)
+    }
+
+    /// Time until the next subsystem becomes due, for sleeping between
+    /// `refresh_due` calls. Zero if something is already due.
+    pub fn next_refresh_in(&self) -> Duration {
+        Subsystem::ALL
+            .iter()
+            .filter(|&&sub| self.is_enabled(sub))
+            .map(|&sub| {
+                self.effective_interval(sub)
+                    .saturating_sub(self.last_refresh(sub).elapsed())
+            })
+            .min()
+            .unwrap_or(self.adaptive.scale(self.global_interval))
+    }
+
+    /// Replace the per-subsystem cadence.
+    pub fn set_schedule(&mut self, schedule: RefreshSchedule) {
+        self.schedule = schedule;
+    }
+
+    /// Set the interval that "follow global" subsystems use in
+    /// `refresh_due`.
+    pub fn set_global_interval(&mut self, interval: Duration) {
+        self.global_interval = interval;
+    }
+
+    /// Record which subsystems are currently rendered by a visible view.
+    /// Subsystems configured `only_when_visible` are skipped while absent.
+    pub fn set_visible(&mut self, visible: impl IntoIterator<Item = Subsystem>) {
+        self.visible = visible.into_iter().collect();
+    }
+
+    /// Apply an `AdaptiveScheduler` decision: restricts which subsystems
+    /// `refresh`/`refresh_due` touch and stretches their intervals.
+    pub fn set_adaptive(&mut self, decision: AdaptiveDecision) {
+        self.adaptive = decision;
+    }
+
+    /// What this monitor's own refreshes have been costing.
+    pub fn refresh_cost(&self) -> &RefreshCost {
+        &self.refresh_cost
+    }
+
+    /// Re-enumerate mounted disks immediately, regardless of the disk
+    /// cadence — so a newly mounted USB drive shows up without waiting
+    /// up to a minute. Unmounted disks are dropped from the list.
+    pub fn refresh_disks_now(&mut self) {
+        self.disks.refresh(true);
+        self.last_disk_refresh = Instant::now();
+        self.disk_forecasts.sample(&self.disk_info(), unix_now());
+    }
+
+    fn last_refresh(&self, subsystem: Subsystem) -> Instant {
+        match subsystem {
+            Subsystem::Cpu => self.last_cpu_refresh,
+            Subsystem::Memory => self.last_memory_refresh,
+            Subsystem::Processes => self.last_process_refresh,
+            Subsystem::Disks => self.last_disk_refresh,
+            Subsystem::Networks => self.last_network_refresh,
+        }
+    }
+
+    fn is_enabled(&self, subsystem: Subsystem) -> bool {
+        self.adaptive.allows(subsystem)
+            && (!self.schedule.get(subsystem).only_when_visible
+                || self.visible.contains(&subsystem))
+    }
+
+    fn effective_interval(&self, subsystem: Subsystem) -> Duration {
+        self.adaptive
+            .scale(self.schedule.get(subsystem).interval(self.global_interval))
     }
 
-    fn do_refresh_cycle(&mut self) {
-        self.sys.refresh_cpu_specifics(CpuRefreshKind::everything());
-        self.sys.refresh_memory();
-        // require bool arg: false => do not remove unlisted
-        if self.last_disk_refresh.elapsed() >= std::time::Duration::from_secs(60) {
+    fn is_due(&self, subsystem: Subsystem, force_global: bool) -> bool {
+        if !self.is_enabled(subsystem) {
+            return false;
+        }
+        if force_global && self.schedule.get(subsystem).interval_ms.is_none() {
+            return true;
+        }
+        self.last_refresh(subsystem).elapsed() >= self.effective_interval(subsystem)
+    }
+
+    fn do_refresh_cycle(&mut self, force_global: bool) -> Vec<Subsystem> {
+        let due: Vec<Subsystem> = Subsystem::ALL
+            .into_iter()
+            .filter(|&sub| self.is_due(sub, force_global))
+            .collect();
+        if due.is_empty() {
+            return due;
+        }
+        let now = Instant::now();
+        // Wall time per subsystem this cycle, recorded into
+        // `refresh_cost` once at the end.
+        let mut spent: HashMap<Subsystem, Duration> = HashMap::new();
+        let mut timer = Instant::now();
+        let mut lap = |sub: Subsystem| {
+            *spent.entry(sub).or_default() += timer.elapsed();
+            timer = Instant::now();
+        };
+
+        if due.contains(&Subsystem::Cpu) {
+            self.sys.refresh_cpu_specifics(CpuRefreshKind::everything());
+            self.last_cpu_refresh = now;
+            self.anomalies
+                .observe("cpu_usage", self.global_cpu_usage() as f64);
+            self.anomalies.observe("load_1", self.load_average().0);
+            lap(Subsystem::Cpu);
+        }
+        if due.contains(&Subsystem::Memory) {
+            self.sys.refresh_memory();
+            self.last_memory_refresh = now;
+            self.anomalies
+                .observe("memory_used", self.sys.used_memory() as f64);
+            self.anomalies
+                .observe("swap_used", self.sys.used_swap() as f64);
+            lap(Subsystem::Memory);
+        }
+        if due.contains(&Subsystem::Disks) {
+            // false => do not remove unlisted; explicit rescans go through
+            // refresh_disks_now
             self.disks.refresh(false);
-            self.last_disk_refresh = std::time::Instant::now();
+            self.last_disk_refresh = now;
+            self.disk_forecasts.sample(&self.disk_info(), unix_now());
+            lap(Subsystem::Disks);
         }
-        // Only refresh networks every 5 seconds to reduce overhead
-        if self.last_network_refresh.elapsed() >= std::time::Duration::from_secs(5) {
-            let now = std::time::Instant::now();
+        let network_refreshed = due.contains(&Subsystem::Networks);
+        if network_refreshed {
             // Capture the actual interval before resetting the timestamp,
             // so rate calculations divide by the real elapsed time between
             // refreshes — not by "time since last refresh" which is ~0 right
@@ -118,16 +354,68 @@ impl SystemMonitor {
             }
             self.networks.refresh(false);
             self.last_network_refresh = now;
+            self.refresh_link_info();
+            let (rx, tx) = self
+                .network_info_with_rates()
+                .iter()
+                .fold((0.0, 0.0), |(rx, tx), iface| (rx + iface.3, tx + iface.4));
+            self.anomalies.observe("net_rx", rx);
+            self.anomalies.observe("net_tx", tx);
+            lap(Subsystem::Networks);
         }
 
-        self.sys.refresh_processes_specifics(
-            sysinfo::ProcessesToUpdate::All,
-            false,
-            ProcessRefreshKind::nothing().with_cpu().with_memory(),
-        );
+        let processes_refreshed = due.contains(&Subsystem::Processes);
+        if processes_refreshed {
+            // Drop exited PIDs so they leave the combined list and
+            // watches can follow a restart.
+            self.sys.refresh_processes_specifics(
+                sysinfo::ProcessesToUpdate::All,
+                true,
+                // Command line and owner never change for a PID; read
+                // them once, for new processes only.
+                ProcessRefreshKind::nothing()
+                    .with_cpu()
+                    .with_memory()
+                    .with_cmd(UpdateKind::OnlyIfNotSet)
+                    .with_user(UpdateKind::OnlyIfNotSet),
+            );
+            self.last_process_refresh = now;
+        }
 
-        // Update cached process list
-        self.cached_processes = self.compute_combined_process_list();
+        // Network attribution needs the fresh PID list, so it runs after
+        // the process refresh but on the network cadence, and only while
+        // a visible view shows it.
+        if network_refreshed {
+            if self.wants_process_net() {
+                self.refresh_process_net();
+            } else if !self.process_net_usage.is_empty() {
+                self.process_net_usage.clear();
+                self.process_net.reset();
+            }
+        }
+
+        // Update the combined process list
+        if processes_refreshed || network_refreshed {
+            self.update_process_index();
+            if processes_refreshed {
+                let timestamp = unix_now();
+                self.record_watches(timestamp);
+                self.leaks.sample(self.process_index.processes(), timestamp);
+            }
+            // Attribution and regrouping are process-table work; charge
+            // them to processes whenever that subsystem ran this cycle.
+            let owner = if processes_refreshed {
+                Subsystem::Processes
+            } else {
+                Subsystem::Networks
+            };
+            lap(owner);
+        }
+        for (sub, took) in spent {
+            self.refresh_cost.record_subsystem(sub, took);
+        }
+        self.refresh_cost.record_cycle(now, now.elapsed());
+        due
     }
 
     /// Get global CPU usage as a percentage (0-100).
@@ -263,6 +551,136 @@ impl SystemMonitor {
         out
     }
 
+    /// Get full details for every interface known to sysinfo: addresses,
+    /// MAC, MTU, link state and speed, and packet/error/drop counters
+    /// alongside the byte totals and rates from `network_info_with_rates`.
+    ///
+    /// Unlike `network_info`, idle interfaces are included so a link
+    /// that just went down is still visible.
+    ///
+    /// # Example
+    /// ```
+    /// use rust_dashboard_lib::system::SystemMonitor;
+    /// let monitor = SystemMonitor::new();
+    /// for iface in monitor.network_interfaces() {
+    ///     println!("{} {:?} mtu={}", iface.interface, iface.link_state, iface.mtu);
+    /// }
+    /// ```
+    pub fn network_interfaces(&self) -> Vec<InterfaceDetails> {
+        let dt = self.last_network_interval.as_secs_f64().max(0.1);
+        let mut out: Vec<InterfaceDetails> = self
+            .networks
+            .iter()
+            .map(|(iface, data)| {
+                let total_rx = data.total_received();
+                let total_tx = data.total_transmitted();
+                let (rx_rate, tx_rate) = match self.last_network_snapshot.get(iface) {
+                    Some(&(prev_rx, prev_tx)) => (
+                        total_rx.saturating_sub(prev_rx) as f64 / dt,
+                        total_tx.saturating_sub(prev_tx) as f64 / dt,
+                    ),
+                    None => (0.0, 0.0),
+                };
+                let mac = data.mac_address();
+                let (ipv4, ipv6): (Vec<&IpNetwork>, Vec<&IpNetwork>) =
+                    data.ip_networks().iter().partition(|n| n.addr.is_ipv4());
+                let link = self.link_info.get(iface).cloned().unwrap_or_default();
+                InterfaceDetails {
+                    interface: iface.clone(),
+                    mac_address: if mac.is_unspecified() {
+                        String::new()
+                    } else {
+                        mac.to_string()
+                    },
+                    ipv4: ipv4.iter().map(|n| n.to_string()).collect(),
+                    ipv6: ipv6.iter().map(|n| n.to_string()).collect(),
+                    mtu: data.mtu(),
+                    link_state: link.link_state,
+                    speed_mbps: link.speed_mbps,
+                    rx_bytes: total_rx,
+                    tx_bytes: total_tx,
+                    rx_rate,
+                    tx_rate,
+                    rx_packets: data.total_packets_received(),
+                    tx_packets: data.total_packets_transmitted(),
+                    rx_errors: data.total_errors_on_received(),
+                    tx_errors: data.total_errors_on_transmitted(),
+                    rx_dropped: link.rx_dropped,
+                    tx_dropped: link.tx_dropped,
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| a.interface.cmp(&b.interface));
+        out
+    }
+
+    /// Drain the link up/down events detected by refreshes since the
+    /// previous call.
+    pub fn take_link_events(&mut self) -> Vec<LinkEvent> {
+        std::mem::take(&mut self.pending_link_events)
+    }
+
+    fn read_all_link_info(&self) -> HashMap<String, LinkInfo> {
+        let root = Path::new(network::SYSFS_NET_ROOT);
+        self.networks
+            .list()
+            .keys()
+            .map(|iface| (iface.clone(), network::read_link_info(root, iface)))
+            .collect()
+    }
+
+    fn refresh_link_info(&mut self) {
+        let current = self.read_all_link_info();
+        let states = |m: &HashMap<String, LinkInfo>| -> HashMap<String, LinkState> {
+            m.iter().map(|(k, v)| (k.clone(), v.link_state)).collect()
+        };
+        let events = network::diff_link_states(&states(&self.link_info), &states(&current));
+        for event in &events {
+            log::info!(
+                "Interface {} link {:?} -> {:?}",
+                event.interface,
+                event.previous,
+                event.current
+            );
+        }
+        self.pending_link_events.extend(events);
+        // Library consumers that never drain shouldn't grow this forever.
+        let excess = self
+            .pending_link_events
+            .len()
+            .saturating_sub(MAX_PENDING_LINK_EVENTS);
+        self.pending_link_events.drain(..excess);
+        self.link_info = current;
+    }
+
+    /// Estimated network usage for a single PID, as of the last network
+    /// refresh.
+    pub fn network_usage_for_pid(&self, pid_val: u32) -> Option<&ProcessNetUsage> {
+        self.process_net_usage.get(&pid_val)
+    }
+
+    /// Per-process network estimates are shown next to both the process
+    /// list and network data, so they're sampled only while some visible
+    /// view renders both; see `SnapshotInterest::wants_process_net`.
+    fn wants_process_net(&self) -> bool {
+        self.visible.contains(&Subsystem::Processes) && self.visible.contains(&Subsystem::Networks)
+    }
+
+    fn refresh_process_net(&mut self) {
+        // Loopback traffic never leaves the host, so it isn't attributed.
+        let host_rates = self
+            .network_info_with_rates()
+            .into_iter()
+            .filter(|(iface, ..)| iface != "lo")
+            .fold((0.0, 0.0), |(rx, tx), (_, _, _, rx_rate, tx_rate)| {
+                (rx + rx_rate, tx + tx_rate)
+            });
+        let pids: Vec<u32> = self.sys.processes().keys().map(|p| p.as_u32()).collect();
+        self.process_net_usage =
+            self.process_net
+                .sample(&pids, host_rates, self.last_network_interval);
+    }
+
     /// Get system uptime in seconds.
     pub fn system_uptime(&self) -> u64 {
         System::uptime()
@@ -292,31 +710,45 @@ impl SystemMonitor {
     /// }
     /// ```
     pub fn combined_process_list(&self) -> &[CombinedProcess] {
-        &self.cached_processes
+        self.process_index.processes()
     }
 
-    /// Internal method to compute the combined process list.
-    fn compute_combined_process_list(&self) -> Vec<CombinedProcess> {
-        let mut map: HashMap<String, CombinedProcess> = HashMap::new();
-
-        for proc_ in self.sys.processes().values() {
-            let pid_val = proc_.pid().as_u32();
-            let proc_name = proc_.name().to_string_lossy().into_owned();
-
-            let entry = map
-                .entry(proc_name.clone())
-                .or_insert_with(|| CombinedProcess {
-                    name: proc_name,
-                    cpu_usage: 0.0,
-                    memory_usage: 0,
-                    pids: Vec::new(),
-                });
+    /// Feed the current process table and network estimates into the
+    /// index; only new, changed and exited PIDs do any work.
+    fn update_process_index(&mut self) {
+        let net = &self.process_net_usage;
+        self.process_index
+            .update(self.sys.processes().values().map(|proc_| {
+                let pid = proc_.pid().as_u32();
+                let usage = net.get(&pid);
+                ProcessSample {
+                    pid,
+                    name: proc_.name().to_string_lossy(),
+                    cpu_usage: proc_.cpu_usage(),
+                    memory: proc_.memory(),
+                    net_rx_rate: usage.map_or(0.0, |n| n.rx_rate),
+                    net_tx_rate: usage.map_or(0.0, |n| n.tx_rate),
+                    connections: usage.map_or(0, |n| n.connections),
+                }
+            }));
+    }
 
-            entry.cpu_usage += proc_.cpu_usage();
-            entry.memory_usage += proc_.memory();
-            entry.pids.push(pid_val);
+    /// Sample pinned processes and groups. Disk counters are read only
+    /// for the watched PIDs, so pinning stays cheap on busy hosts.
+    fn record_watches(&mut self, timestamp: f64) {
+        if self.watches.is_empty() {
+            return;
         }
-        map.into_values().collect()
+        let mut watches = std::mem::take(&mut self.watches);
+        watches.bind(self);
+        let pids: Vec<sysinfo::Pid> = watches.pids().map(sysinfo::Pid::from_u32).collect();
+        self.sys.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&pids),
+            false,
+            ProcessRefreshKind::nothing().with_disk_usage(),
+        );
+        watches.record(self, timestamp);
+        self.watches = watches;
     }
 
     /// Get CPU and memory usage for a specific process by PID.
@@ -362,24 +794,75 @@ impl SystemMonitor {
     /// }
     /// ```
     pub fn process_details(&self, pid_val: u32) -> Option<ProcessDetails> {
+        let command = self.command_line(pid_val)?;
+        self.sys
+            .processes()
+            .get(&sysinfo::Pid::from_u32(pid_val))
+            .map(|p| ProcessDetails {
+                command,
+                start_time: p.start_time(),
+                parent: p.parent().map(|pid| pid.as_u32()),
+            })
+    }
+
+    /// Command line of a process, arguments joined by spaces.
+    pub fn command_line(&self, pid_val: u32) -> Option<String> {
         self.sys
             .processes()
             .get(&sysinfo::Pid::from_u32(pid_val))
             .map(|p| {
-                let cmd_str = p
-                    .cmd()
+                p.cmd()
                     .iter()
                     .map(|s| s.to_string_lossy().into_owned())
                     .collect::<Vec<_>>()
-                    .join(" ");
-                ProcessDetails {
-                    command: cmd_str,
-                    start_time: p.start_time(),
-                    parent: p.parent().map(|pid| pid.as_u32()),
-                }
+                    .join(" ")
             })
     }
 
+    /// Cumulative bytes read from and written to disk by a process, as
+    /// of its last disk-usage refresh (watched PIDs only).
+    pub fn process_io_totals(&self, pid_val: u32) -> Option<(u64, u64)> {
+        self.sys
+            .processes()
+            .get(&sysinfo::Pid::from_u32(pid_val))
+            .map(|p| {
+                let usage = p.disk_usage();
+                (usage.total_read_bytes, usage.total_written_bytes)
+            })
+    }
+
+    /// Process start time, in seconds since the Unix epoch.
+    pub fn process_start_time(&self, pid_val: u32) -> Option<u64> {
+        self.sys
+            .processes()
+            .get(&sysinfo::Pid::from_u32(pid_val))
+            .map(|p| p.start_time())
+    }
+
+    /// Re-read one process and return its start time (seconds since the
+    /// Unix epoch), or `None` if it has exited. If the PID now belongs to
+    /// a different process, the start time is the new one's, so callers
+    /// holding a `(pid, start_time)` key can tell.
+    pub fn refresh_process(&mut self, pid_val: u32) -> Option<u64> {
+        let pid = sysinfo::Pid::from_u32(pid_val);
+        self.sys.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        self.process_start_time(pid_val)
+    }
+
+    /// Name of the user a process runs as, if it can be resolved.
+    pub fn process_user(&self, pid_val: u32) -> Option<&str> {
+        let uid = self
+            .sys
+            .processes()
+            .get(&sysinfo::Pid::from_u32(pid_val))?
+            .user_id()?;
+        self.users.get_user_by_id(uid).map(|user| user.name())
+    }
+
     /// Kill a process by PID (sends SIGKILL).
     ///
     /// # Arguments
@@ -411,6 +894,59 @@ impl SystemMonitor {
             Err("Process not found".to_string())
         }
     }
+
+    /// Ask a process to exit (SIGTERM on Unix), giving it the chance to
+    /// clean up. Same PID 0/1 guard as `kill_process`.
+    pub fn terminate_process(&mut self, pid_val: u32) -> Result<(), String> {
+        if pid_val <= 1 {
+            return Err("Cannot terminate system processes (PID 0 or 1)".to_string());
+        }
+        let process = self
+            .sys
+            .processes()
+            .get(&sysinfo::Pid::from_u32(pid_val))
+            .ok_or_else(|| "Process not found".to_string())?;
+        match process.kill_with(sysinfo::Signal::Term) {
+            Some(true) => Ok(()),
+            Some(false) => Err("Failed to terminate process".to_string()),
+            None => Err("SIGTERM is not supported on this platform".to_string()),
+        }
+    }
+
+    /// Set a process's scheduling priority (nice value, -20 to 19).
+    /// Lowering it below the current value usually needs privileges.
+    /// Unix only.
+    pub fn renice_process(&mut self, pid_val: u32, nice: i32) -> Result<(), String> {
+        if pid_val <= 1 {
+            return Err("Cannot renice system processes (PID 0 or 1)".to_string());
+        }
+        if !self
+            .sys
+            .processes()
+            .contains_key(&sysinfo::Pid::from_u32(pid_val))
+        {
+            return Err("Process not found".to_string());
+        }
+        #[cfg(unix)]
+        {
+            // SAFETY: setpriority only reads its integer arguments.
+            let result =
+                unsafe { libc::setpriority(libc::PRIO_PROCESS, pid_val as libc::id_t, nice) };
+            if result == 0 {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Failed to renice process: {}",
+                    std::io::Error::last_os_error()
+                ))
+            }
+        }
+        #[cfg(not(unix))]
+        {
+            let _ = nice;
+            Err("Renicing is not supported on this platform".to_string())
+        }
+    }
 }
 
 /// Detailed information about a process.
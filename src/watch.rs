@@ -0,0 +1,238 @@
+use crate::anomaly::{AnomalyConfig, AnomalyDetector};
+use crate::system::SystemMonitor;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+/// Samples kept per watch, matching the global CPU/memory history.
+pub const WATCH_HISTORY_CAPACITY: usize = 300;
+
+/// What a watch follows.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum WatchTarget {
+    /// Every PID sharing a process name, as in the combined list.
+    Group { name: String },
+    /// One process. When it exits, the watch moves to the newest PID
+    /// with the same name — preferring one with the same command line —
+    /// so a service restarting under its name keeps its history.
+    Process { name: String, pid: u32 },
+}
+
+impl WatchTarget {
+    pub fn name(&self) -> &str {
+        match self {
+            WatchTarget::Group { name } | WatchTarget::Process { name, .. } => name,
+        }
+    }
+}
+
+/// Usage of a watched target at one refresh. Rates are bytes/sec.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct WatchSample {
+    /// Seconds since the Unix epoch.
+    pub timestamp: f64,
+    pub cpu_usage: f32,
+    pub memory: u64,
+    pub disk_read_rate: f64,
+    pub disk_write_rate: f64,
+    pub net_rx_rate: f64,
+    pub net_tx_rate: f64,
+    /// PIDs sampled; zero while the target isn't running.
+    pub pids: u32,
+    /// CPU or memory stood out from the watch's own baseline; see
+    /// `anomaly::AnomalyDetector`.
+    #[serde(default)]
+    pub cpu_anomaly: bool,
+    #[serde(default)]
+    pub memory_anomaly: bool,
+}
+
+/// A pinned process or group and its history for this session.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Watch {
+    pub id: u64,
+    pub target: WatchTarget,
+    /// PIDs sampled at the last refresh; empty while nothing matches.
+    pub pids: Vec<u32>,
+    /// Times a `Process` watch moved to a new PID after its process
+    /// exited.
+    pub restarts: u32,
+    pub history: VecDeque<WatchSample>,
+    /// Command line of the first process bound, used to pick the right
+    /// successor among same-named processes.
+    #[serde(skip)]
+    cmdline: Option<String>,
+    #[serde(skip)]
+    cpu_baseline: AnomalyDetector,
+    #[serde(skip)]
+    memory_baseline: AnomalyDetector,
+}
+
+/// Pinned processes and groups, sampled after each process refresh by
+/// `SystemMonitor`.
+///
+/// # Example
+/// ```
+/// use rust_dashboard_lib::system::SystemMonitor;
+/// use rust_dashboard_lib::watch::WatchTarget;
+///
+/// let mut monitor = SystemMonitor::new();
+/// let pid = std::process::id();
+/// let name = monitor
+///     .combined_process_list()
+///     .iter()
+///     .find(|p| p.pids.contains(&pid))
+///     .map(|p| p.name.clone())
+///     .unwrap();
+/// let id = monitor.watches.add(WatchTarget::Process { name, pid });
+/// monitor.refresh();
+/// let watch = monitor.watches.get(id).unwrap();
+/// assert_eq!(watch.pids, vec![pid]);
+/// assert_eq!(watch.history.len(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct WatchList {
+    watches: Vec<Watch>,
+    next_id: u64,
+    /// Last cumulative disk counters per sampled PID, for rates.
+    io_totals: HashMap<u32, (f64, u64, u64)>,
+    /// Sensitivity of each watch's CPU and memory baselines.
+    pub anomaly_config: AnomalyConfig,
+}
+
+impl WatchList {
+    /// Pin `target` and return its id. Pinning something already
+    /// watched returns the existing id.
+    pub fn add(&mut self, target: WatchTarget) -> u64 {
+        if let Some(existing) = self.watches.iter().find(|w| w.target == target) {
+            return existing.id;
+        }
+        self.next_id += 1;
+        self.watches.push(Watch {
+            id: self.next_id,
+            target,
+            pids: Vec::new(),
+            restarts: 0,
+            history: VecDeque::with_capacity(WATCH_HISTORY_CAPACITY),
+            cmdline: None,
+            cpu_baseline: AnomalyDetector::new(self.anomaly_config),
+            memory_baseline: AnomalyDetector::new(self.anomaly_config),
+        });
+        self.next_id
+    }
+
+    /// Unpin; returns false if `id` wasn't watched.
+    pub fn remove(&mut self, id: u64) -> bool {
+        let before = self.watches.len();
+        self.watches.retain(|w| w.id != id);
+        self.watches.len() != before
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Watch> {
+        self.watches.iter().find(|w| w.id == id)
+    }
+
+    pub fn watches(&self) -> &[Watch] {
+        &self.watches
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.watches.is_empty()
+    }
+
+    /// PIDs bound by the last `bind`, across all watches.
+    pub fn pids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.watches.iter().flat_map(|w| w.pids.iter().copied())
+    }
+
+    /// Resolve each watch to its current PIDs, following restarts.
+    pub fn bind(&mut self, monitor: &SystemMonitor) {
+        for watch in &mut self.watches {
+            let group = monitor
+                .combined_process_list()
+                .iter()
+                .find(|g| g.name == watch.target.name());
+            let group_pids = group.map_or(&[][..], |g| g.pids.as_slice());
+            match &watch.target {
+                WatchTarget::Group { .. } => watch.pids = group_pids.to_vec(),
+                WatchTarget::Process { pid, .. } => {
+                    let current = watch.pids.first().copied().unwrap_or(*pid);
+                    if group_pids.contains(&current) {
+                        watch.pids = vec![current];
+                    } else {
+                        let successor = successor(monitor, group_pids, watch.cmdline.as_deref());
+                        if successor.is_some() && !watch.history.is_empty() {
+                            watch.restarts += 1;
+                        }
+                        watch.pids = successor.into_iter().collect();
+                    }
+                    if watch.cmdline.is_none() {
+                        watch.cmdline = watch.pids.first().and_then(|&p| monitor.command_line(p));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Append a sample per watch for the PIDs found by `bind`.
+    /// `timestamp` is seconds since the Unix epoch.
+    pub fn record(&mut self, monitor: &SystemMonitor, timestamp: f64) {
+        let mut totals = HashMap::with_capacity(self.io_totals.len());
+        for watch in &mut self.watches {
+            let mut sample = WatchSample {
+                timestamp,
+                pids: watch.pids.len() as u32,
+                ..Default::default()
+            };
+            for &pid in &watch.pids {
+                if let Some((cpu, memory)) = monitor.usage_for_pid(pid) {
+                    sample.cpu_usage += cpu;
+                    sample.memory += memory;
+                }
+                if let Some(net) = monitor.network_usage_for_pid(pid) {
+                    sample.net_rx_rate += net.rx_rate;
+                    sample.net_tx_rate += net.tx_rate;
+                }
+                if let Some((read, written)) = monitor.process_io_totals(pid) {
+                    if let Some(&(t, prev_read, prev_written)) = self.io_totals.get(&pid) {
+                        let dt = timestamp - t;
+                        if dt > 0.0 {
+                            sample.disk_read_rate += read.saturating_sub(prev_read) as f64 / dt;
+                            sample.disk_write_rate +=
+                                written.saturating_sub(prev_written) as f64 / dt;
+                        }
+                    }
+                    totals.insert(pid, (timestamp, read, written));
+                }
+            }
+            if sample.pids > 0 && self.anomaly_config.enabled {
+                watch.cpu_baseline.observe(sample.cpu_usage as f64);
+                watch.memory_baseline.observe(sample.memory as f64);
+                sample.cpu_anomaly = watch.cpu_baseline.is_anomalous();
+                sample.memory_anomaly = watch.memory_baseline.is_anomalous();
+            }
+            if watch.history.len() == WATCH_HISTORY_CAPACITY {
+                watch.history.pop_front();
+            }
+            watch.history.push_back(sample);
+        }
+        self.io_totals = totals;
+    }
+}
+
+/// Newest PID among `candidates`, preferring a matching command line.
+fn successor(monitor: &SystemMonitor, candidates: &[u32], cmdline: Option<&str>) -> Option<u32> {
+    let newest = |pids: &mut dyn Iterator<Item = u32>| {
+        pids.max_by_key(|&pid| (monitor.process_start_time(pid).unwrap_or(0), pid))
+    };
+    cmdline
+        .and_then(|cmd| {
+            newest(
+                &mut candidates
+                    .iter()
+                    .copied()
+                    .filter(|&pid| monitor.command_line(pid).as_deref() == Some(cmd)),
+            )
+        })
+        .or_else(|| newest(&mut candidates.iter().copied()))
+}
@@ -0,0 +1,36 @@
+//! Fixtures shared by the integration tests, pulled in with `mod common;`.
+#![allow(dead_code)]
+
+use rust_dashboard_lib::snapshot::{MemoryInfo, SystemSnapshot};
+use rust_dashboard_lib::system::CombinedProcess;
+
+pub const GIB: u64 = 1 << 30;
+
+/// A quiet machine with one CPU at `cpu` percent and 1 of 2 GiB of
+/// memory in use. Override the rest with struct update syntax.
+pub fn snapshot(cpu: f32) -> SystemSnapshot {
+    SystemSnapshot {
+        cpu_usage: cpu,
+        per_cpu: vec![cpu],
+        memory: MemoryInfo {
+            used: GIB,
+            free: GIB,
+            total: 2 * GIB,
+            available: GIB,
+            ..Default::default()
+        },
+        uptime_seconds: 100,
+        load_average: (0.5, 0.5, 0.5),
+        ..Default::default()
+    }
+}
+
+pub fn process(name: &str, cpu: f32, memory: u64, pids: &[u32]) -> CombinedProcess {
+    CombinedProcess {
+        name: name.to_string(),
+        cpu_usage: cpu,
+        memory_usage: memory,
+        pids: pids.to_vec(),
+        ..Default::default()
+    }
+}
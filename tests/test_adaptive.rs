@@ -0,0 +1,142 @@
+use rust_dashboard_lib::adaptive::{
+    read_power_source, AdaptiveInputs, AdaptiveScheduler, OverheadMode, PowerSource, RefreshCost,
+};
+use rust_dashboard_lib::config::{AdaptiveConfig, Subsystem};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Create `<root>/<name>/{type,online|status}` like sysfs power_supply.
+fn supply(root: &Path, name: &str, kind: &str, file: &str, value: &str) {
+    let dir = root.join(name);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("type"), format!("{}\n", kind)).unwrap();
+    fs::write(dir.join(file), format!("{}\n", value)).unwrap();
+}
+
+fn inputs(visible: bool, power: PowerSource, cpu: f32) -> AdaptiveInputs {
+    AdaptiveInputs {
+        any_view_visible: visible,
+        power,
+        cpu_usage: cpu,
+    }
+}
+
+#[test]
+fn test_power_source_from_sysfs() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(read_power_source(dir.path()), PowerSource::Unknown);
+    assert_eq!(
+        read_power_source(&dir.path().join("missing")),
+        PowerSource::Unknown
+    );
+
+    supply(dir.path(), "BAT0", "Battery", "status", "Discharging");
+    assert_eq!(read_power_source(dir.path()), PowerSource::Battery);
+
+    supply(dir.path(), "AC", "Mains", "online", "1");
+    assert_eq!(read_power_source(dir.path()), PowerSource::Ac);
+
+    supply(dir.path(), "AC", "Mains", "online", "0");
+    supply(dir.path(), "BAT0", "Battery", "status", "Not charging");
+    assert_eq!(read_power_source(dir.path()), PowerSource::Battery);
+}
+
+#[test]
+fn test_hidden_windows_select_minimal_mode() {
+    let mut scheduler = AdaptiveScheduler::new(AdaptiveConfig::default());
+
+    let hidden = scheduler.decide(inputs(false, PowerSource::Ac, 5.0));
+    assert_eq!(hidden.mode, OverheadMode::Minimal);
+    assert!(hidden.allows(Subsystem::Cpu));
+    assert!(hidden.allows(Subsystem::Memory));
+    assert!(!hidden.allows(Subsystem::Processes));
+    assert_eq!(hidden.interval_multiplier, 1);
+
+    let shown = scheduler.decide(inputs(true, PowerSource::Ac, 5.0));
+    assert_eq!(shown.mode, OverheadMode::Full);
+    assert!(shown.allows(Subsystem::Processes));
+}
+
+#[test]
+fn test_battery_and_load_multipliers_stack() {
+    let mut scheduler = AdaptiveScheduler::new(AdaptiveConfig::default());
+
+    let battery = scheduler.decide(inputs(true, PowerSource::Battery, 5.0));
+    assert!(battery.on_battery);
+    assert_eq!(battery.interval_multiplier, 2);
+
+    let both = scheduler.decide(inputs(true, PowerSource::Battery, 95.0));
+    assert!(both.under_load);
+    assert_eq!(both.interval_multiplier, 4);
+    assert_eq!(both.scale(Duration::from_secs(2)), Duration::from_secs(8));
+}
+
+#[test]
+fn test_load_backoff_has_hysteresis() {
+    let mut scheduler = AdaptiveScheduler::new(AdaptiveConfig::default());
+
+    assert!(
+        !scheduler
+            .decide(inputs(true, PowerSource::Ac, 80.0))
+            .under_load
+    );
+    assert!(
+        scheduler
+            .decide(inputs(true, PowerSource::Ac, 90.0))
+            .under_load
+    );
+    // Still within the hysteresis band below the 85% threshold.
+    assert!(
+        scheduler
+            .decide(inputs(true, PowerSource::Ac, 80.0))
+            .under_load
+    );
+    assert!(
+        !scheduler
+            .decide(inputs(true, PowerSource::Ac, 70.0))
+            .under_load
+    );
+}
+
+#[test]
+fn test_disabled_scheduler_runs_full_schedule() {
+    let mut scheduler = AdaptiveScheduler::new(AdaptiveConfig {
+        enabled: false,
+        ..AdaptiveConfig::default()
+    });
+    let decision = scheduler.decide(inputs(false, PowerSource::Battery, 99.0));
+    assert_eq!(decision.mode, OverheadMode::Full);
+    assert_eq!(decision.interval_multiplier, 1);
+}
+
+#[test]
+fn test_power_source_is_cached_between_polls() {
+    let dir = tempfile::tempdir().unwrap();
+    supply(dir.path(), "AC", "Mains", "online", "1");
+    let mut scheduler = AdaptiveScheduler::with_power_root(AdaptiveConfig::default(), dir.path());
+    assert_eq!(scheduler.power_source(), PowerSource::Ac);
+
+    // Unplugging isn't noticed until the poll interval elapses.
+    supply(dir.path(), "AC", "Mains", "online", "0");
+    supply(dir.path(), "BAT0", "Battery", "status", "Discharging");
+    assert_eq!(scheduler.power_source(), PowerSource::Ac);
+}
+
+#[test]
+fn test_refresh_cost_averages() {
+    let mut cost = RefreshCost::default();
+    let start = Instant::now();
+    cost.record_subsystem(Subsystem::Cpu, Duration::from_millis(10));
+    cost.record_subsystem(Subsystem::Cpu, Duration::from_millis(20));
+    cost.record_cycle(start, Duration::from_millis(10));
+    cost.record_cycle(start + Duration::from_secs(1), Duration::from_millis(20));
+
+    assert_eq!(cost.cycles, 2);
+    assert!((cost.last_cycle_ms - 20.0).abs() < 1e-9);
+    // EWMA moves 20% of the way from the first sample.
+    assert!((cost.subsystem_ms[&Subsystem::Cpu] - 12.0).abs() < 1e-9);
+    assert!((cost.avg_cycle_ms - 12.0).abs() < 1e-9);
+    // 20 ms of work in a 1 s period.
+    assert!((cost.busy_percent - 2.0).abs() < 1e-9);
+}
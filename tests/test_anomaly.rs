@@ -0,0 +1,115 @@
+use rust_dashboard_lib::alerts::{AlertEngine, AlertRule, AlertState};
+use rust_dashboard_lib::anomaly::{mark_anomalies, AnomalyConfig, AnomalyDetector};
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::filter::{Filter, Scope};
+use rust_dashboard_lib::system::{CombinedProcess, SystemMonitor};
+use std::time::Instant;
+
+/// Deterministic jitter in [-1, 1).
+fn jitter(i: usize) -> f64 {
+    ((i * 7919) % 200) as f64 / 100.0 - 1.0
+}
+
+#[test]
+fn test_spikes_are_flagged_and_noise_is_not() {
+    let mut series: Vec<f64> = (0..200).map(|i| 30.0 + 3.0 * jitter(i)).collect();
+    series[120] = 85.0;
+    series[160] = 2.0;
+    assert_eq!(
+        mark_anomalies(&series, &AnomalyConfig::default()),
+        vec![120, 160]
+    );
+}
+
+#[test]
+fn test_warmup_and_relative_spread() {
+    let config = AnomalyConfig::default();
+    // A spike before the baseline is established isn't flagged.
+    let mut early = vec![10.0; 40];
+    early[5] = 500.0;
+    assert!(mark_anomalies(&early, &config).is_empty());
+
+    // A nearly constant series doesn't flag a 1% wobble.
+    let mut flat = vec![4096.0; 100];
+    flat[80] = 4137.0;
+    assert!(mark_anomalies(&flat, &config).is_empty());
+}
+
+#[test]
+fn test_level_shift_is_absorbed() {
+    let mut detector = AnomalyDetector::new(AnomalyConfig::default());
+    for i in 0..100 {
+        detector.observe(20.0 + jitter(i));
+    }
+    detector.observe(60.0);
+    assert!(detector.is_anomalous());
+    let mut flagged = 1;
+    for i in 0..300 {
+        detector.observe(60.0 + jitter(i));
+        flagged += detector.is_anomalous() as usize;
+    }
+    assert!(!detector.is_anomalous());
+    assert!(flagged < 100, "{}", flagged);
+    assert!((detector.baseline() - 60.0).abs() < 2.0);
+}
+
+fn spike_cpu(monitor: &mut SystemMonitor) {
+    for i in 0..60 {
+        monitor.anomalies.observe("cpu_usage", 10.0 + jitter(i));
+    }
+    monitor.anomalies.observe("cpu_usage", 95.0);
+}
+
+#[test]
+fn test_anomaly_in_filters() {
+    let mut monitor = SystemMonitor::new();
+    let system = Filter::parse("anomaly(cpu_usage)").unwrap();
+    assert_eq!(system.scope(), Scope::System);
+    assert!(!system.matches_system(&monitor));
+    spike_cpu(&mut monitor);
+    assert!(system.matches_system(&monitor));
+    assert!(!Filter::parse("anomaly(net_rx) || !anomaly(cpu_usage)")
+        .unwrap()
+        .matches_system(&monitor));
+
+    let busy = CombinedProcess {
+        name: "busy".to_string(),
+        cpu_usage: 80.0,
+        pids: vec![1],
+        ..Default::default()
+    };
+    let filter = Filter::parse("ANOMALY('cpu_usage') && cpu > 50").unwrap();
+    assert_eq!(filter.scope(), Scope::Processes);
+    assert!(filter.matches(&busy, &monitor));
+    assert!(!filter.matches_system(&monitor));
+
+    for (source, column) in [
+        ("anomaly(cpu)", 9),
+        ("anomaly cpu_usage", 9),
+        ("anomaly(cpu_usage", 1),
+        ("anomaly()", 9),
+    ] {
+        match Filter::parse(source).unwrap_err() {
+            DashboardError::InvalidFilter { column: c, .. } => assert_eq!(c, column, "{}", source),
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn test_alert_rule_on_anomaly_matches_system() {
+    let mut monitor = SystemMonitor::new();
+    let mut engine = AlertEngine::new(&[AlertRule {
+        name: "cpu anomaly".to_string(),
+        condition: "anomaly(cpu_usage)".to_string(),
+        for_seconds: 0,
+        actions: Vec::new(),
+    }])
+    .unwrap();
+    assert!(engine.evaluate(&monitor, Instant::now()).is_empty());
+    spike_cpu(&mut monitor);
+    let events = engine.evaluate(&monitor, Instant::now());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].state, AlertState::Firing);
+    assert_eq!(events[0].matches, vec!["system".to_string()]);
+}
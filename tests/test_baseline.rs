@@ -0,0 +1,246 @@
+mod common;
+
+use common::{process, GIB};
+use rust_dashboard_lib::baseline::{
+    Baseline, BaselineStore, Change, Presence, ProcessChange, SnapshotDiff, DEFAULT_TOP,
+};
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::export::ExportFormat;
+use rust_dashboard_lib::network::LinkState;
+use rust_dashboard_lib::snapshot::{DiskInfo, HostInfo, MemoryInfo, NetworkInfo, SystemSnapshot};
+use rust_dashboard_lib::system::CombinedProcess;
+
+/// 2026-10-18 00:00:00 UTC.
+const THEN: f64 = 1_792_281_600.0;
+const NOW: f64 = THEN + 86_400.0;
+const MIB: u64 = 1 << 20;
+
+fn disk(mount_point: &str, used: u64) -> DiskInfo {
+    DiskInfo {
+        name: "/dev/sda1".to_string(),
+        filesystem: "ext4".to_string(),
+        mount_point: mount_point.to_string(),
+        used,
+        available: 100 * GIB - used,
+        total: 100 * GIB,
+        ..Default::default()
+    }
+}
+
+fn network(interface: &str, rx_rate: f64, tx_rate: f64) -> NetworkInfo {
+    NetworkInfo {
+        interface: interface.to_string(),
+        rx_rate,
+        tx_rate,
+        mtu: 1500,
+        link_state: LinkState::Up,
+        ..Default::default()
+    }
+}
+
+fn snapshot(
+    cpu: f32,
+    memory_used: u64,
+    processes: Vec<CombinedProcess>,
+    disks: Vec<DiskInfo>,
+    networks: Vec<NetworkInfo>,
+) -> SystemSnapshot {
+    SystemSnapshot {
+        memory: MemoryInfo {
+            used: memory_used,
+            free: 8 * GIB - memory_used,
+            total: 8 * GIB,
+            available: 8 * GIB - memory_used,
+            ..Default::default()
+        },
+        disks,
+        networks,
+        processes,
+        load_average: (1.0, 1.0, 1.0),
+        ..common::snapshot(cpu)
+    }
+}
+
+fn host() -> HostInfo {
+    HostInfo {
+        hostname: "build-01".to_string(),
+        os: "Linux".to_string(),
+        os_version: "12".to_string(),
+        kernel_version: "6.1.0".to_string(),
+        cpu_brand: "Test CPU".to_string(),
+        cpu_count: 1,
+    }
+}
+
+fn baseline() -> Baseline {
+    Baseline::new(
+        "yesterday",
+        THEN,
+        host(),
+        snapshot(
+            20.0,
+            2 * GIB,
+            vec![
+                process("postgres", 10.0, 500 * MIB, &[10, 11]),
+                process("nginx", 5.0, 50 * MIB, &[20]),
+                process("cron", 0.0, 5 * MIB, &[30]),
+            ],
+            vec![disk("/", 40 * GIB), disk("/mnt/old", GIB)],
+            vec![network("eth0", 1000.0, 500.0)],
+        ),
+    )
+    .unwrap()
+}
+
+fn today() -> SystemSnapshot {
+    snapshot(
+        65.0,
+        5 * GIB,
+        vec![
+            process("postgres", 55.0, 600 * MIB, &[10, 11, 12]),
+            process("nginx", 5.0, 2 * GIB, &[20]),
+            process("=indexer", 3.0, GIB, &[40]),
+            process("sidecar", 1.0, MIB, &[41]),
+        ],
+        vec![disk("/", 55 * GIB), disk("/data", 3 * GIB)],
+        vec![network("eth0", 9000.0, 250.0)],
+    )
+}
+
+fn diff() -> SnapshotDiff {
+    SnapshotDiff::compare(&baseline(), &today(), NOW, DEFAULT_TOP)
+}
+
+#[test]
+fn test_process_changes() {
+    let diff = diff();
+    let names = |changes: &[ProcessChange]| -> Vec<String> {
+        changes.iter().map(|p| p.name.clone()).collect()
+    };
+    // New and gone processes, largest memory first.
+    assert_eq!(names(&diff.new_processes), ["=indexer", "sidecar"]);
+    assert_eq!(diff.new_processes[0].presence, Presence::New);
+    assert_eq!(diff.new_processes[0].memory, Change::new(0.0, GIB as f64));
+    assert_eq!(names(&diff.gone_processes), ["cron"]);
+    assert_eq!(diff.gone_processes[0].instances, Change::new(1.0, 0.0));
+
+    // Only processes in both, and only those that changed.
+    assert_eq!(names(&diff.cpu_changes), ["postgres"]);
+    assert_eq!(diff.cpu_changes[0].cpu.delta, 45.0);
+    assert_eq!(diff.cpu_changes[0].instances, Change::new(2.0, 3.0));
+    assert_eq!(names(&diff.memory_changes), ["nginx", "postgres"]);
+
+    let top = SnapshotDiff::compare(&baseline(), &today(), NOW, 1);
+    assert_eq!(names(&top.memory_changes), ["nginx"]);
+    assert_eq!(top.new_processes.len(), 2, "new/gone aren't limited");
+}
+
+#[test]
+fn test_system_disk_and_network_changes() {
+    let diff = diff();
+    assert_eq!(
+        (diff.baseline.as_str(), diff.baseline_timestamp),
+        ("yesterday", THEN)
+    );
+    let system = |metric: &str| {
+        diff.system
+            .iter()
+            .find(|s| s.metric == metric)
+            .unwrap()
+            .change
+    };
+    assert_eq!(system("cpu_usage"), Change::new(20.0, 65.0));
+    assert_eq!(system("memory_used").delta, (3 * GIB) as f64);
+    assert_eq!(system("process_groups"), Change::new(3.0, 4.0));
+
+    let disks: Vec<(&str, Presence, f64)> = diff
+        .disks
+        .iter()
+        .map(|d| (d.mount_point.as_str(), d.presence, d.used.delta))
+        .collect();
+    assert_eq!(
+        disks,
+        [
+            ("/", Presence::Both, (15 * GIB) as f64),
+            ("/mnt/old", Presence::Gone, -(GIB as f64)),
+            ("/data", Presence::New, (3 * GIB) as f64),
+        ]
+    );
+    assert_eq!(diff.networks[0].rx_rate, Change::new(1000.0, 9000.0));
+    assert_eq!(diff.networks[0].tx_rate.delta, -250.0);
+}
+
+#[test]
+fn test_diff_reports() {
+    let diff = diff();
+    let markdown = diff.export(ExportFormat::Markdown).unwrap();
+    assert!(markdown.starts_with("# Comparison with baseline: yesterday\n"));
+    assert!(markdown.contains("| Baseline taken | 2026-10-18 00:00:00 UTC |"));
+    assert!(markdown.contains("| cpu_usage | 20.0% | 65.0% | +45.0% |"));
+    assert!(markdown.contains("| process_groups | 3 | 4 | +1 |"));
+    assert!(markdown.contains("| nginx | 50.0 MiB | 2.0 GiB | +2.0 GiB |"));
+    assert!(markdown.contains("| /mnt/old | gone | 1.0 GiB | 0.0 B | -1.0 GiB | 100.0 GiB |"));
+
+    let html = diff.export(ExportFormat::Html).unwrap();
+    assert!(html.contains("<h2>Gone processes</h2>"));
+    assert!(html.contains("<td>cron</td>"));
+
+    let csv = diff.export(ExportFormat::Csv).unwrap();
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    assert_eq!(reader.headers().unwrap().len(), 7);
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    let indexer = rows
+        .iter()
+        .find(|r| &r[0] == "new_process" && &r[3] == "memory bytes")
+        .unwrap();
+    // Names that look like formulas are neutralised.
+    assert_eq!((&indexer[1], &indexer[2]), ("'=indexer", "new"));
+    assert_eq!(&indexer[6], GIB.to_string());
+
+    let ndjson = diff.export(ExportFormat::Ndjson).unwrap();
+    let lines: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines[0]["type"], "diff");
+    assert_eq!(lines[0]["baseline"], "yesterday");
+    assert_eq!(lines.len(), rows.len() + 1);
+
+    let json: SnapshotDiff =
+        serde_json::from_str(&diff.export(ExportFormat::Json).unwrap()).unwrap();
+    assert_eq!(json.cpu_changes, diff.cpu_changes);
+}
+
+#[test]
+fn test_store_save_load_list_delete() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = BaselineStore::new(dir.path().join("baselines"));
+    assert!(store.list().unwrap().is_empty());
+
+    let mut later = baseline();
+    later.name = "after upgrade".to_string();
+    later.timestamp = NOW;
+    store.save(&later).unwrap();
+    store.save(&baseline()).unwrap();
+    std::fs::write(dir.path().join("baselines/broken.json"), "{").unwrap();
+
+    let names: Vec<String> = store.list().unwrap().into_iter().map(|b| b.name).collect();
+    assert_eq!(names, ["yesterday", "after upgrade"]);
+    assert_eq!(store.load("yesterday").unwrap(), baseline());
+
+    store.delete("yesterday").unwrap();
+    for result in [
+        store.load("yesterday").map(|_| ()),
+        store.delete("yesterday"),
+        store.load("../etc/passwd").map(|_| ()),
+    ] {
+        assert!(matches!(result, Err(DashboardError::ConfigError(_))));
+    }
+    for bad in ["", ".hidden", "a/b", &"x".repeat(65)] {
+        assert!(
+            Baseline::new(bad, NOW, host(), today()).is_err(),
+            "{:?}",
+            bad
+        );
+    }
+}
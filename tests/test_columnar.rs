@@ -0,0 +1,261 @@
+#![cfg(feature = "columnar")]
+
+mod common;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, TimestampMicrosecondType};
+use arrow_array::{Array, RecordBatch};
+use arrow_ipc::reader::FileReader;
+use arrow_schema::{DataType, Schema, TimeUnit};
+use common::process;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::basic::{Compression, LogicalType, TimeUnit as ParquetTimeUnit};
+use parquet::file::metadata::ParquetMetaData;
+use rust_dashboard_lib::columnar::{
+    history_points, recording_points, write_arrow, write_parquet, ColumnarFormat, SeriesPoint,
+    ROWS_PER_GROUP,
+};
+use rust_dashboard_lib::export::HistorySeries;
+use rust_dashboard_lib::recording::RecordedSnapshot;
+use rust_dashboard_lib::snapshot::{DiskInfo, SystemSnapshot};
+use std::io::Cursor;
+use std::sync::Arc;
+
+/// 2026-10-18 00:00:00 UTC.
+const NOW: f64 = 1_792_281_600.0;
+
+fn tags(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn points() -> Vec<SeriesPoint> {
+    vec![
+        SeriesPoint {
+            timestamp: NOW,
+            series: "disk.used".to_string(),
+            value: 1.5,
+            tags: tags(&[("host", "db1"), ("mount", "/")]),
+        },
+        SeriesPoint {
+            timestamp: NOW + 0.25,
+            series: "load_1".to_string(),
+            value: 0.75,
+            tags: Vec::new(),
+        },
+        SeriesPoint {
+            timestamp: NOW + 1.0,
+            series: "cpu_usage".to_string(),
+            value: 42.0,
+            tags: tags(&[("unit", "%")]),
+        },
+    ]
+}
+
+/// The rows of `batches` as points.
+fn rows(batches: &[RecordBatch]) -> Vec<SeriesPoint> {
+    let mut points = Vec::new();
+    for batch in batches {
+        let timestamps = batch.column(0).as_primitive::<TimestampMicrosecondType>();
+        let series = batch.column(1).as_string::<i32>();
+        let values = batch.column(2).as_primitive::<Float64Type>();
+        let tags = batch.column(3).as_map();
+        for row in 0..batch.num_rows() {
+            let entries = tags.value(row);
+            let keys = entries.column(0).as_string::<i32>();
+            let tag_values = entries.column(1).as_string::<i32>();
+            points.push(SeriesPoint {
+                timestamp: timestamps.value(row) as f64 / 1e6,
+                series: series.value(row).to_string(),
+                value: values.value(row),
+                tags: (0..entries.len())
+                    .map(|i| (keys.value(i).to_string(), tag_values.value(i).to_string()))
+                    .collect(),
+            });
+        }
+    }
+    points
+}
+
+fn assert_schema(schema: &Schema) {
+    let columns: Vec<(&str, &DataType, bool)> = schema
+        .fields()
+        .iter()
+        .map(|f| (f.name().as_str(), f.data_type(), f.is_nullable()))
+        .collect();
+    assert_eq!(columns.len(), 4);
+    assert_eq!(
+        columns[0],
+        (
+            "timestamp",
+            &DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false
+        )
+    );
+    assert_eq!(columns[1], ("series", &DataType::Utf8, false));
+    assert_eq!(columns[2], ("value", &DataType::Float64, false));
+    let DataType::Map(entries, false) = columns[3].1 else {
+        panic!("tags: {:?}", columns[3]);
+    };
+    let DataType::Struct(pair) = entries.data_type() else {
+        panic!("entries: {:?}", entries);
+    };
+    let names: Vec<&str> = pair.iter().map(|f| f.name().as_str()).collect();
+    assert_eq!(names, ["key", "value"]);
+}
+
+/// Write `points` as Parquet and read them back with the `parquet`
+/// crate.
+fn parquet_round_trip(points: &[SeriesPoint]) -> (Arc<ParquetMetaData>, Vec<RecordBatch>) {
+    let mut file = tempfile::tempfile().unwrap();
+    write_parquet(points.iter().cloned(), &mut file).unwrap();
+    let reader = ParquetRecordBatchReaderBuilder::try_new(file).unwrap();
+    let metadata = reader.metadata().clone();
+    assert_schema(reader.schema());
+    let batches = reader
+        .build()
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    (metadata, batches)
+}
+
+#[test]
+fn test_parquet_schema_and_rows() {
+    let (metadata, batches) = parquet_round_trip(&points());
+    let file = metadata.file_metadata();
+    assert_eq!(file.num_rows(), 3);
+    assert!(file.created_by().unwrap().starts_with("rust-dashboard"));
+    assert!(matches!(
+        file.schema_descr().column(0).logical_type(),
+        Some(LogicalType::Timestamp {
+            is_adjusted_to_u_t_c: true,
+            unit: ParquetTimeUnit::MICROS(_),
+        })
+    ));
+    let group = metadata.row_group(0);
+    assert!(group
+        .columns()
+        .iter()
+        .all(|c| matches!(c.compression(), Compression::GZIP(_))));
+
+    // Two tags, an empty map, one tag.
+    assert_eq!(rows(&batches), points());
+}
+
+#[test]
+fn test_parquet_row_groups() {
+    let point = &points()[0];
+    let many = vec![point.clone(); ROWS_PER_GROUP + 1];
+    let (metadata, batches) = parquet_round_trip(&many);
+    let groups: Vec<i64> = metadata.row_groups().iter().map(|g| g.num_rows()).collect();
+    assert_eq!(groups, [ROWS_PER_GROUP as i64, 1]);
+    assert_eq!(rows(&batches).last(), Some(point));
+
+    let (metadata, batches) = parquet_round_trip(&[]);
+    assert_eq!(metadata.file_metadata().num_rows(), 0);
+    assert!(metadata.row_groups().is_empty());
+    assert!(batches.is_empty());
+}
+
+#[test]
+fn test_arrow_ipc_file() {
+    let mut file = Vec::new();
+    write_arrow(points(), &mut file).unwrap();
+    assert_eq!(&file[..6], b"ARROW1");
+    let reader = FileReader::try_new(Cursor::new(file), None).unwrap();
+    assert_schema(&reader.schema());
+    assert_eq!(reader.num_batches(), 1);
+    let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(rows(&batches), points());
+}
+
+#[test]
+fn test_history_and_recording_points() {
+    let history = [
+        HistorySeries::new("cpu_usage", "%", vec![(NOW, 10.0), (NOW + 1.0, 20.0)]),
+        HistorySeries::new("load_1", "", vec![(NOW, 0.5)]),
+    ];
+    let points = history_points(&history, Some((NOW + 0.5, NOW + 2.0)));
+    assert_eq!(
+        points,
+        [SeriesPoint {
+            timestamp: NOW + 1.0,
+            series: "cpu_usage".to_string(),
+            value: 20.0,
+            tags: tags(&[("unit", "%")]),
+        }]
+    );
+    assert!(history_points(&history, None)[2].tags.is_empty());
+
+    let snapshot = SystemSnapshot {
+        disks: vec![DiskInfo {
+            name: "/dev/sda1".to_string(),
+            filesystem: "ext4".to_string(),
+            mount_point: "/".to_string(),
+            used: 1,
+            available: 3,
+            total: 4,
+            ..Default::default()
+        }],
+        processes: vec![
+            process("postgres", 50.0, 0, &[10]),
+            process("cron", 0.0, 0, &[20]),
+        ],
+        ..common::snapshot(25.0)
+    };
+    let frames: Vec<RecordedSnapshot> = (0..3)
+        .map(|i| RecordedSnapshot {
+            timestamp: NOW + i as f64,
+            snapshot: snapshot.clone(),
+        })
+        .collect();
+    let points: Vec<SeriesPoint> =
+        recording_points(frames, Some((NOW + 1.0, NOW + 2.0)), 1).collect();
+    assert!(points.iter().all(|p| p.timestamp >= NOW + 1.0));
+    let find = |series: &str| {
+        points
+            .iter()
+            .find(|p| p.series == series)
+            .unwrap_or_else(|| panic!("no {}", series))
+    };
+    assert_eq!(find("system.cpu_usage").value, 25.0);
+    assert!(find("system.cpu_usage").tags.is_empty(), "no host");
+    assert_eq!(
+        find("disk.used").tags,
+        tags(&[("mount", "/"), ("device", "/dev/sda1"), ("fs", "ext4")])
+    );
+    let groups: Vec<&str> = points
+        .iter()
+        .filter(|p| p.series == "process.cpu_usage")
+        .map(|p| p.tags[0].1.as_str())
+        .collect();
+    assert_eq!(
+        groups,
+        ["postgres", "postgres"],
+        "the busiest group per frame"
+    );
+
+    // Frames are only read as points are needed.
+    let endless = std::iter::repeat_with(|| RecordedSnapshot {
+        timestamp: NOW,
+        snapshot: snapshot.clone(),
+    });
+    assert_eq!(recording_points(endless, None, 1).take(1000).count(), 1000);
+}
+
+#[test]
+fn test_format_from_extension() {
+    assert_eq!(
+        ColumnarFormat::from_extension("PARQUET"),
+        Some(ColumnarFormat::Parquet)
+    );
+    assert_eq!(
+        ColumnarFormat::from_extension("feather"),
+        Some(ColumnarFormat::Arrow)
+    );
+    assert_eq!(ColumnarFormat::from_extension("csv"), None);
+    assert!("xlsx".parse::<ColumnarFormat>().is_err());
+}
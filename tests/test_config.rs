@@ -8,6 +8,7 @@ fn test_config_default() {
 }
 
 #[test]
+#[allow(clippy::field_reassign_with_default)]
 fn test_config_save_and_load() {
     // Use a tempdir so we don't touch the real platform config path
     // and don't race other tests that also call config_path().
@@ -66,3 +67,69 @@ fn test_config_serialization() {
     );
     assert_eq!(deserialized.theme, config.theme);
 }
+
+#[test]
+fn test_refresh_schedule_defaults_match_legacy_cadence() {
+    use rust_dashboard_lib::config::{RefreshSchedule, Subsystem};
+    use std::time::Duration;
+
+    let schedule = RefreshSchedule::default();
+    let global = Duration::from_secs(2);
+    assert_eq!(schedule.get(Subsystem::Cpu).interval(global), global);
+    assert_eq!(schedule.get(Subsystem::Processes).interval(global), global);
+    assert_eq!(
+        schedule.get(Subsystem::Disks).interval(global),
+        Duration::from_secs(60)
+    );
+    assert_eq!(
+        schedule.get(Subsystem::Networks).interval(global),
+        Duration::from_secs(5)
+    );
+}
+
+#[test]
+fn test_subsystem_interval_is_clamped() {
+    use rust_dashboard_lib::config::{SubsystemCadence, MIN_SUBSYSTEM_INTERVAL_MS};
+    use std::time::Duration;
+
+    let cadence = SubsystemCadence::every_ms(10);
+    assert_eq!(
+        cadence.interval(Duration::from_secs(2)),
+        Duration::from_millis(MIN_SUBSYSTEM_INTERVAL_MS)
+    );
+}
+
+#[test]
+fn test_config_without_refresh_section_uses_default_schedule() {
+    use rust_dashboard_lib::config::RefreshSchedule;
+
+    // Config files written before per-subsystem cadence existed.
+    let legacy = r#"
+        refresh_interval_seconds = 5
+        theme = "Dark"
+    "#;
+    let config: AppConfig = toml::from_str(legacy).expect("legacy config parses");
+    assert_eq!(config.refresh_interval_seconds, 5);
+    assert_eq!(config.refresh, RefreshSchedule::default());
+}
+
+#[test]
+fn test_config_refresh_section_round_trip() {
+    use rust_dashboard_lib::config::SubsystemCadence;
+
+    let dir = tempfile::tempdir().expect("create tempdir");
+    let path = dir.path().join("config.toml");
+
+    let mut config = AppConfig::default();
+    config.refresh.cpu = SubsystemCadence::every_ms(500);
+    config.refresh.processes = SubsystemCadence {
+        interval_ms: Some(10_000),
+        only_when_visible: true,
+    };
+    config.save_to(&path).expect("save config");
+
+    let loaded = AppConfig::load_from(&path);
+    assert_eq!(loaded.refresh.cpu.interval_ms, Some(500));
+    assert!(loaded.refresh.processes.only_when_visible);
+    assert_eq!(loaded.refresh.disks, config.refresh.disks);
+}
@@ -0,0 +1,203 @@
+mod common;
+
+use common::process;
+use rust_dashboard_lib::config::Subsystem;
+use rust_dashboard_lib::delta::{DeltaDecoder, DeltaEncoder, SnapshotMessage};
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::snapshot::{SnapshotInterest, SystemSnapshot};
+use rust_dashboard_lib::system::CombinedProcess;
+
+fn snapshot(processes: Vec<CombinedProcess>) -> SystemSnapshot {
+    SystemSnapshot {
+        per_cpu: vec![10.0, 10.0],
+        processes,
+        ..common::snapshot(10.0)
+    }
+}
+
+fn sorted(mut processes: Vec<CombinedProcess>) -> Vec<CombinedProcess> {
+    processes.sort_by(|a, b| a.name.cmp(&b.name));
+    processes
+}
+
+fn delta(message: SnapshotMessage) -> rust_dashboard_lib::delta::SnapshotDelta {
+    match message {
+        SnapshotMessage::Delta(delta) => delta,
+        SnapshotMessage::Full { .. } => panic!("expected a delta"),
+    }
+}
+
+#[test]
+fn test_first_message_is_full_then_deltas() {
+    let mut encoder = DeltaEncoder::new();
+    let snap = snapshot(vec![process("init", 0.0, 1000, &[1])]);
+
+    let first = encoder.encode(&snap);
+    assert!(matches!(first, SnapshotMessage::Full { seq: 1, .. }));
+
+    let second = delta(encoder.encode(&snap));
+    assert_eq!(second.seq, 2);
+    assert!(second.processes.is_empty());
+    assert!(second.metrics.memory.is_none());
+    assert!(second.metrics.per_cpu.is_none());
+}
+
+#[test]
+fn test_process_changes_round_trip() {
+    let mut encoder = DeltaEncoder::new();
+    let mut decoder = DeltaDecoder::new();
+    let before = snapshot(vec![
+        process("a", 1.0, 1000, &[10]),
+        process("b", 2.0, 2000, &[20]),
+        process("c", 3.0, 3000, &[30]),
+    ]);
+    decoder.apply(encoder.encode(&before)).unwrap();
+
+    let after = snapshot(vec![
+        process("a", 1.0, 1000, &[10]),
+        process("c", 50.0, 3000, &[30, 31]),
+        process("d", 4.0, 4000, &[40]),
+    ]);
+    let message = encoder.encode(&after);
+    let d = delta(message.clone());
+    assert_eq!(d.processes.removed, vec!["b".to_string()]);
+    assert_eq!(d.processes.added.len(), 1);
+    assert_eq!(d.processes.added[0].name, "d");
+    assert_eq!(d.processes.changed.len(), 1);
+    assert_eq!(d.processes.changed[0].name, "c");
+
+    let decoded = decoder.apply(message).unwrap();
+    assert_eq!(sorted(decoded.processes.clone()), sorted(after.processes));
+}
+
+#[test]
+fn test_small_jitter_is_suppressed_but_cannot_drift() {
+    let mut encoder = DeltaEncoder::new();
+    encoder.encode(&snapshot(vec![process("a", 1.0, 1000, &[1])]));
+
+    let d = delta(encoder.encode(&snapshot(vec![process("a", 1.05, 1000, &[1])])));
+    assert!(d.processes.changed.is_empty());
+
+    // Drift is measured against what was last sent (1.0), not 1.05.
+    let d = delta(encoder.encode(&snapshot(vec![process("a", 1.1, 1000, &[1])])));
+    assert_eq!(d.processes.changed.len(), 1);
+}
+
+#[test]
+fn test_gap_requires_resync() {
+    let mut encoder = DeltaEncoder::new();
+    let mut decoder = DeltaDecoder::new();
+    let snap = snapshot(vec![process("a", 1.0, 1000, &[1])]);
+    decoder.apply(encoder.encode(&snap)).unwrap();
+
+    // Lost message.
+    let _ = encoder.encode(&snapshot(vec![process("a", 5.0, 1000, &[1])]));
+    let third = encoder.encode(&snapshot(vec![process("a", 9.0, 1000, &[1])]));
+    match decoder.apply(third) {
+        Err(DashboardError::SequenceGap { expected, got }) => {
+            assert_eq!(expected, 2);
+            assert_eq!(got, 3);
+        }
+        other => panic!("expected a gap, got {:?}", other.map(|s| s.cpu_usage)),
+    }
+
+    let full = encoder.full().unwrap();
+    assert_eq!(full.seq(), 3);
+    let resynced = decoder.apply(full).unwrap();
+    assert!((resynced.processes[0].cpu_usage - 9.0).abs() < f32::EPSILON);
+    assert_eq!(decoder.seq(), Some(3));
+}
+
+#[test]
+fn test_stale_delta_is_ignored_and_early_delta_rejected() {
+    let mut encoder = DeltaEncoder::new();
+    let snap = snapshot(vec![process("a", 1.0, 1000, &[1])]);
+    encoder.encode(&snap);
+    let second = encoder.encode(&snapshot(vec![process("a", 7.0, 1000, &[1])]));
+
+    let mut fresh = DeltaDecoder::new();
+    assert!(matches!(
+        fresh.apply(second.clone()),
+        Err(DashboardError::SequenceGap { .. })
+    ));
+
+    // A subscriber that joined via full() at seq 2 already has it.
+    let mut joined = DeltaDecoder::new();
+    joined.apply(encoder.full().unwrap()).unwrap();
+    let still = joined.apply(second).unwrap();
+    assert!((still.processes[0].cpu_usage - 7.0).abs() < f32::EPSILON);
+    assert_eq!(joined.seq(), Some(2));
+}
+
+#[test]
+fn test_message_json_shape() {
+    let mut encoder = DeltaEncoder::new();
+    let snap = snapshot(Vec::new());
+    let full = serde_json::to_value(encoder.encode(&snap)).unwrap();
+    assert_eq!(full["kind"], "full");
+    assert_eq!(full["seq"], 1);
+    assert!(full["snapshot"]["memory"].is_object());
+
+    let mut changed = snap.clone();
+    changed.memory.used += 1;
+    let d = serde_json::to_value(encoder.encode(&changed)).unwrap();
+    assert_eq!(d["kind"], "delta");
+    assert_eq!(d["seq"], 2);
+    assert!(d["metrics"]["memory"].is_object());
+    // Unchanged sections are omitted, not null.
+    assert!(d["metrics"].get("per_cpu").is_none());
+    assert!(d["metrics"].get("disks").is_none());
+}
+
+#[test]
+fn test_interest_trims_unwanted_sections() {
+    let full = snapshot(vec![process("a", 1.0, 1, &[1])]);
+    let interest = SnapshotInterest {
+        subsystems: [Subsystem::Memory].into_iter().collect(),
+        top_processes: None,
+    };
+    let trimmed = interest.apply(&full);
+    assert!(trimmed.per_cpu.is_empty());
+    assert!(trimmed.processes.is_empty());
+    assert_eq!(trimmed.cpu_usage, full.cpu_usage);
+    assert_eq!(trimmed.memory, full.memory);
+
+    assert_eq!(SnapshotInterest::all().apply(&full), full);
+}
+
+#[test]
+fn test_interest_keeps_top_processes_by_cpu() {
+    let full = snapshot(vec![
+        process("idle", 0.0, 1, &[1]),
+        process("busy", 50.0, 1, &[2]),
+        process("warm", 5.0, 1, &[3]),
+    ]);
+    let interest = SnapshotInterest {
+        top_processes: Some(2),
+        ..SnapshotInterest::all()
+    };
+    let names: Vec<_> = interest
+        .apply(&full)
+        .processes
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    assert_eq!(names, ["busy", "warm"]);
+}
+
+#[test]
+fn test_widening_interest_sends_new_sections_as_delta() {
+    let full = snapshot(vec![process("a", 1.0, 1, &[1])]);
+    let narrow = SnapshotInterest {
+        subsystems: [Subsystem::Cpu].into_iter().collect(),
+        top_processes: None,
+    };
+    let mut encoder = DeltaEncoder::new();
+    let mut decoder = DeltaDecoder::new();
+    decoder.apply(encoder.encode(&narrow.apply(&full))).unwrap();
+    assert!(decoder.snapshot().unwrap().processes.is_empty());
+
+    let widened = encoder.encode(&SnapshotInterest::all().apply(&full));
+    assert!(matches!(widened, SnapshotMessage::Delta(_)));
+    assert_eq!(decoder.apply(widened).unwrap(), &full);
+}
@@ -1,46 +1,127 @@
+mod common;
+
+use common::process;
+use rust_dashboard_lib::export::{ExportData, ExportFormat, Exporter, HistorySeries};
+use rust_dashboard_lib::network::LinkState;
+use rust_dashboard_lib::snapshot::{DiskInfo, HostInfo, NetworkInfo, SystemSnapshot};
 use rust_dashboard_lib::system::CombinedProcess;
 
-#[test]
-fn test_csv_export_format() {
-    use csv::Writer;
+const NOW: f64 = 1_700_000_000.0;
 
-    let mut wtr = Writer::from_writer(vec![]);
+fn data() -> ExportData {
+    ExportData {
+        timestamp: NOW,
+        host: HostInfo {
+            hostname: "build-01".to_string(),
+            os: "Linux".to_string(),
+            os_version: "12".to_string(),
+            kernel_version: "6.1.0".to_string(),
+            cpu_brand: "Test CPU".to_string(),
+            cpu_count: 2,
+        },
+        snapshot: SystemSnapshot {
+            per_cpu: vec![40.0, 45.0],
+            disks: vec![DiskInfo {
+                name: "/dev/sda1".to_string(),
+                filesystem: "ext4".to_string(),
+                mount_point: "/".to_string(),
+                used: 30 << 30,
+                available: 70 << 30,
+                total: 100 << 30,
+                growth_bytes_per_hour: Some((1 << 30) as f64),
+                full_in_seconds: Some(70.0 * 3600.0),
+            }],
+            networks: vec![NetworkInfo {
+                interface: "eth0".to_string(),
+                rx_bytes: 1000,
+                tx_bytes: 2000,
+                rx_rate: 2048.0,
+                tx_rate: 1024.0,
+                mac_address: "00:11:22:33:44:55".to_string(),
+                ipv4: vec!["10.0.0.2/24".to_string()],
+                ipv6: Vec::new(),
+                mtu: 1500,
+                link_state: LinkState::Up,
+                speed_mbps: Some(1000),
+                ..Default::default()
+            }],
+            processes: vec![
+                process("idle", 0.5, 10 << 20, &[30]),
+                process("=HYPERLINK(\"x\")", 80.0, 200 << 20, &[10, 11]),
+                process("web|<b>", 20.0, 100 << 20, &[20]),
+            ],
+            uptime_seconds: 90_000,
+            load_average: (1.5, 1.0, 0.5),
+            ..common::snapshot(42.5)
+        },
+        history: vec![HistorySeries::new(
+            "cpu_usage",
+            "%",
+            vec![(NOW - 20.0, 10.0), (NOW - 10.0, 20.0), (NOW, 30.0)],
+        )],
+    }
+}
 
-    // Write test data
-    assert!(wtr
-        .write_record(&["Type", "Name", "CPU Usage %", "Memory MB", "PIDs"])
-        .is_ok());
-    assert!(wtr
-        .write_record(&["Process", "test", "10.5", "100", "1234"])
-        .is_ok());
+fn export(exporter: Exporter) -> String {
+    exporter.export(&data()).unwrap()
+}
 
-    let data = wtr.into_inner().unwrap();
-    let csv_str = String::from_utf8(data).unwrap();
+#[test]
+fn test_csv_export_format() {
+    let csv = export(Exporter::new(ExportFormat::Csv));
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let header = reader.headers().unwrap().clone();
+    assert_eq!(&header[0], "Type");
+    assert_eq!(&header[4], "PIDs");
+    assert_eq!(header.len(), 12);
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    let find = |kind: &str, name: &str| {
+        rows.iter()
+            .find(|r| &r[0] == kind && &r[1] == name)
+            .unwrap_or_else(|| panic!("no {} row for {}", kind, name))
+    };
 
-    assert!(csv_str.contains("Type"));
-    assert!(csv_str.contains("test"));
-    assert!(csv_str.contains("10.5"));
+    assert_eq!(&find("System", "CPU")[2], "42.50");
+    assert_eq!(&find("System", "Memory")[3], "1024");
+    assert_eq!(&find("System", "Load 1m")[11], "1.50");
+    let disk = find("Disk", "/");
+    assert_eq!((&disk[5], &disk[6], &disk[7]), ("30.00", "100.00", "70.0"));
+    let network = find("Network", "eth0");
+    assert_eq!((&network[8], &network[9]), ("2.00", "1.00"));
+    // Names that look like formulas are neutralised.
+    let process = find("Process", "'=HYPERLINK(\"x\")");
+    assert_eq!(
+        (&process[2], &process[3], &process[4]),
+        ("80.00", "200", "10;11")
+    );
+    let history: Vec<_> = rows.iter().filter(|r| &r[0] == "History").collect();
+    assert_eq!(history.len(), 3);
+    assert_eq!(&history[0][1], "cpu_usage (%)");
+    assert_eq!(&history[2][11], "30");
 }
 
 #[test]
 fn test_json_export_structure() {
-    use serde_json::json;
-
-    let data = json!({
-        "timestamp": 1234567890,
-        "cpu_usage": 50.5,
-        "memory": {
-            "used_gb": 4.0,
-            "free_gb": 4.0,
-            "total_gb": 8.0
-        },
-        "processes": []
-    });
+    let json: serde_json::Value = serde_json::from_str(&export(Exporter::default())).unwrap();
+    assert_eq!(json["timestamp"], NOW);
+    assert_eq!(json["host"]["hostname"], "build-01");
+    assert_eq!(json["system"]["cpu_usage"], 42.5);
+    assert_eq!(json["system"]["memory"]["total"], 2u64 << 30);
+    assert_eq!(json["system"]["load_average"][0], 1.5);
+    assert_eq!(json["system"]["disks"][0]["mount_point"], "/");
+    assert_eq!(json["system"]["networks"][0]["link_state"], "up");
+    assert_eq!(json["processes"].as_array().unwrap().len(), 3);
+    assert_eq!(json["history"][0]["points"][2][1], 30.0);
 
-    let json_str = serde_json::to_string_pretty(&data).unwrap();
-    assert!(json_str.contains("cpu_usage"));
-    assert!(json_str.contains("memory"));
-    assert!(json_str.contains("processes"));
+    let json: serde_json::Value = serde_json::from_str(&export(Exporter {
+        include_system: false,
+        include_history: false,
+        ..Exporter::default()
+    }))
+    .unwrap();
+    assert!(json.get("system").is_none());
+    assert!(json.get("history").is_none());
+    assert!(json.get("processes").is_some());
 }
 
 #[test]
@@ -52,6 +133,7 @@ fn test_process_serialization() {
         cpu_usage: 25.5,
         memory_usage: 1024 * 1024 * 100, // 100 MB
         pids: vec![1234, 5678],
+        ..Default::default()
     };
 
     let json = serde_json::json!({
@@ -69,3 +151,90 @@ fn test_process_serialization() {
         &vec![serde_json::json!(1234), serde_json::json!(5678)]
     );
 }
+
+#[test]
+fn test_ndjson_records() {
+    let ndjson = export(Exporter::new(ExportFormat::Ndjson));
+    let records: Vec<serde_json::Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let types: Vec<&str> = records
+        .iter()
+        .map(|r| r["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        types,
+        [
+            "host", "system", "disk", "network", "process", "process", "process", "history",
+            "history", "history"
+        ]
+    );
+    assert_eq!(records[0]["hostname"], "build-01");
+    assert_eq!(records[2]["full_in_seconds"], 70.0 * 3600.0);
+    assert_eq!(records[4]["timestamp"], NOW);
+    assert_eq!(records[7]["series"], "cpu_usage");
+    assert_eq!(records[7]["timestamp"], NOW - 20.0);
+    assert_eq!(records[9]["value"], 30.0);
+}
+
+#[test]
+fn test_history_range_and_process_limit() {
+    let json: serde_json::Value = serde_json::from_str(&export(Exporter {
+        history_range: Some((NOW - 15.0, NOW)),
+        process_limit: Some(2),
+        ..Exporter::default()
+    }))
+    .unwrap();
+    let points = json["history"][0]["points"].as_array().unwrap();
+    assert_eq!(points.len(), 2);
+    assert_eq!(points[0][0], NOW - 10.0);
+    let names: Vec<&str> = json["processes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["=HYPERLINK(\"x\")", "web|<b>"]);
+}
+
+#[test]
+fn test_reports_escape_and_summarise() {
+    let markdown = export(Exporter::new(ExportFormat::Markdown));
+    assert!(markdown.starts_with("# System report: build-01\n"));
+    assert!(markdown.contains("| Generated | 2023-11-14 22:13:20 UTC |"));
+    assert!(markdown.contains("| Uptime | 1d 1h |"));
+    assert!(markdown.contains("| / | /dev/sda1 | ext4 | 30.0 GiB | 100.0 GiB | 30.0% | 2d 22h |"));
+    assert!(markdown.contains("| web\\|<b> | 1 | 20.0 | 100.0 MiB | 0.0 B |"));
+    assert!(markdown.contains("| cpu_usage | 3 |"));
+    assert!(markdown.contains("| 10.00 % | 20.00 % | 30.00 % |"));
+
+    let html = export(Exporter {
+        include_history: false,
+        ..Exporter::new(ExportFormat::Html)
+    });
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<td>web|&lt;b&gt;</td>"));
+    assert!(html.contains("<td>=HYPERLINK(&quot;x&quot;)</td>"));
+    assert!(!html.contains("<b>"));
+    assert!(!html.contains("<h2>History</h2>"));
+}
+
+#[test]
+fn test_format_names() {
+    for (name, format) in [
+        ("json", ExportFormat::Json),
+        ("CSV", ExportFormat::Csv),
+        ("jsonl", ExportFormat::Ndjson),
+        ("md", ExportFormat::Markdown),
+        ("htm", ExportFormat::Html),
+    ] {
+        assert_eq!(ExportFormat::from_extension(name), Some(format));
+        assert_eq!(name.parse::<ExportFormat>().unwrap(), format);
+        let extension = format.extension();
+        assert_eq!(ExportFormat::from_extension(extension), Some(format));
+    }
+    assert!("xlsx".parse::<ExportFormat>().is_err());
+    let exporter: Exporter = serde_json::from_str(r#"{"format": "markdown"}"#).unwrap();
+    assert_eq!(exporter, Exporter::new(ExportFormat::Markdown));
+}
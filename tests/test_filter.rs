@@ -0,0 +1,173 @@
+use rust_dashboard_lib::alerts::{AlertEngine, AlertRule, AlertState};
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::filter::Filter;
+use rust_dashboard_lib::query::ProcessQuery;
+use rust_dashboard_lib::system::{CombinedProcess, SystemMonitor};
+use std::time::{Duration, Instant};
+
+fn group(name: &str, cpu: f32, memory: u64, pids: Vec<u32>) -> CombinedProcess {
+    CombinedProcess {
+        name: name.to_string(),
+        cpu_usage: cpu,
+        memory_usage: memory,
+        pids,
+        net_rx_rate: 100.0,
+        net_tx_rate: 50.0,
+        connections: 3,
+    }
+}
+
+fn column_of(source: &str) -> usize {
+    match Filter::parse(source).unwrap_err() {
+        DashboardError::InvalidFilter { column, .. } => column,
+        other => panic!("unexpected error {:?}", other),
+    }
+}
+
+#[test]
+fn test_numeric_fields_units_and_precedence() {
+    let monitor = SystemMonitor::new();
+    let worker = group("worker", 25.0, 3 * 1024 * 1024, vec![1, 2]);
+    let cases = [
+        ("cpu > 20", true),
+        ("cpu > 20 && mem < 2MB", false),
+        ("cpu > 20 && mem >= 3M", true),
+        ("pids == 2 and conns == 3 and net == 150", true),
+        ("rx < 100 || tx > 50 || cpu > 90", false),
+        ("cpu < 5 || cpu > 20 && pids == 2", true),
+        ("(cpu < 5 || cpu > 20) && pids == 3", false),
+        ("!(cpu > 20)", false),
+        ("not cpu > 90", true),
+        ("pid == 2", true),
+        ("pid != 2", false),
+        ("pid > 2", false),
+        ("cpu >= 25%", true),
+        (r#"name == "worker" && name ~ '^wor' && name !~ "x""#, true),
+        ("name == worker", true),
+        ("name != worker", false),
+    ];
+    for (source, expected) in cases {
+        let filter = Filter::parse(source).unwrap();
+        assert_eq!(filter.matches(&worker, &monitor), expected, "{}", source);
+    }
+}
+
+#[test]
+fn test_user_and_cmdline_use_any_pid() {
+    let monitor = SystemMonitor::new();
+    let pid = std::process::id();
+    let me = monitor
+        .combined_process_list()
+        .iter()
+        .find(|p| p.pids.contains(&pid))
+        .cloned()
+        .expect("test process is listed");
+
+    let by_cmd = Filter::parse(r#"cmd ~ "test_filter" && !(cmd !~ "test_filter")"#).unwrap();
+    assert!(by_cmd.matches(&me, &monitor));
+    if let Some(user) = monitor.process_user(pid) {
+        let filter = Filter::parse(&format!("user == '{}'", user)).unwrap();
+        assert!(filter.matches(&me, &monitor));
+        let filter = Filter::parse(&format!("user != '{}'", user)).unwrap();
+        assert!(!filter.matches(&me, &monitor));
+    }
+}
+
+#[test]
+fn test_parse_errors_point_at_the_problem() {
+    assert_eq!(column_of("cpu >"), 6);
+    assert_eq!(column_of("usr == root"), 1);
+    assert_eq!(column_of("cpu > 20 & mem > 1"), 10);
+    assert_eq!(column_of(r#"name == "worker"#), 9);
+    assert_eq!(column_of("cpu ~ 20"), 5);
+    assert_eq!(column_of("name > 3"), 6);
+    assert_eq!(column_of("cpu > high"), 7);
+    assert_eq!(column_of("mem > 3 parsecs"), 9);
+    assert_eq!(column_of("mem > 3XB"), 8);
+    assert_eq!(column_of("(cpu > 1"), 1);
+    assert_eq!(column_of("cpu > 1)"), 8);
+    assert_eq!(column_of(r#"name ~ "(""#), 8);
+    assert_eq!(column_of(""), 1);
+
+    let err = Filter::parse("usr == root").unwrap_err().to_string();
+    assert!(err.contains("unknown field `usr`"), "{}", err);
+    assert!(err.contains("user"), "{}", err);
+}
+
+#[test]
+fn test_deep_nesting_is_an_error_not_a_crash() {
+    let deep_not = format!("{}cpu > 1", "!".repeat(60_000));
+    assert_eq!(column_of(&deep_not), 65);
+    let deep_paren = format!("{}cpu > 1", "(".repeat(60_000));
+    assert_eq!(column_of(&deep_paren), 65);
+    let mixed = format!("{}cpu > 1", "!(".repeat(30_000));
+    assert_eq!(column_of(&mixed), 65);
+    let err = Filter::parse(&deep_not).unwrap_err().to_string();
+    assert!(err.contains("64"), "{}", err);
+
+    // Up to the limit is fine.
+    assert!(Filter::parse(&format!("{}cpu > 1", "!".repeat(64))).is_ok());
+    let parens = format!("{}cpu > 1{}", "(".repeat(64), ")".repeat(64));
+    assert!(Filter::parse(&parens).is_ok());
+}
+
+#[test]
+fn test_query_applies_filter() {
+    let monitor = SystemMonitor::new();
+    let own = ProcessQuery {
+        filter: Some(format!("pid == {}", std::process::id())),
+        ..Default::default()
+    }
+    .run(&monitor)
+    .unwrap();
+    assert_eq!(own.total, 1);
+
+    let err = ProcessQuery {
+        filter: Some("cpu >>".to_string()),
+        ..Default::default()
+    }
+    .run(&monitor)
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        DashboardError::InvalidFilter { column: 6, .. }
+    ));
+}
+
+#[test]
+fn test_alert_rule_waits_for_duration_and_fires_once() {
+    let monitor = SystemMonitor::new();
+    let rule = AlertRule {
+        name: "self".to_string(),
+        condition: format!("pid == {}", std::process::id()),
+        for_seconds: 10,
+        actions: Vec::new(),
+    };
+    let mut engine = AlertEngine::new(&[rule]).unwrap();
+    let start = Instant::now();
+    assert!(engine.evaluate(&monitor, start).is_empty());
+    assert!(engine
+        .evaluate(&monitor, start + Duration::from_secs(5))
+        .is_empty());
+
+    let events = engine.evaluate(&monitor, start + Duration::from_secs(10));
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].state, AlertState::Firing);
+    assert_eq!(events[0].matches.len(), 1);
+    assert!(engine
+        .evaluate(&monitor, start + Duration::from_secs(20))
+        .is_empty());
+}
+
+#[test]
+fn test_invalid_alert_condition_names_the_rule() {
+    let err = AlertEngine::new(&[AlertRule {
+        name: "broken".to_string(),
+        condition: "cpu >".to_string(),
+        for_seconds: 0,
+        actions: Vec::new(),
+    }])
+    .err()
+    .unwrap();
+    assert!(matches!(&err, DashboardError::ConfigError(m) if m.contains("broken")));
+}
@@ -0,0 +1,186 @@
+use rust_dashboard_lib::alerts::{AlertEngine, AlertRule, AlertState};
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::filter::{Filter, Scope};
+use rust_dashboard_lib::forecast::{DiskForecaster, ForecastConfig};
+use rust_dashboard_lib::query::ProcessQuery;
+use rust_dashboard_lib::snapshot::DiskInfo;
+use rust_dashboard_lib::system::SystemMonitor;
+use std::time::Instant;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+type Disk = (String, String, String, u64, u64, u64);
+
+fn disk(mount: &str, used: u64, total: u64) -> Disk {
+    (
+        "sda1".to_string(),
+        "ext4".to_string(),
+        mount.to_string(),
+        used,
+        total - used,
+        total,
+    )
+}
+
+/// Feed one sample every five minutes for `hours`, with each mount's
+/// used space given by `used(mount_index, sample)`.
+fn run(
+    forecaster: &mut DiskForecaster,
+    mounts: &[&str],
+    hours: u64,
+    used: impl Fn(usize, u64) -> u64,
+) {
+    for i in 0..hours * 12 {
+        let disks: Vec<Disk> = mounts
+            .iter()
+            .enumerate()
+            .map(|(m, mount)| disk(mount, used(m, i), 100 * GIB))
+            .collect();
+        forecaster.sample(&disks, i as f64 * 300.0);
+    }
+}
+
+#[test]
+fn test_forecasts_time_until_full_for_growing_mounts() {
+    let mut forecaster = DiskForecaster::new(ForecastConfig::default());
+    run(
+        &mut forecaster,
+        &["/", "/data", "/tmp"],
+        6,
+        |m, i| match m {
+            // 1 GiB per hour from 40 GiB.
+            0 => 40 * GIB + i * GIB / 12,
+            1 => 70 * GIB,
+            // Shrinking.
+            _ => 90 * GIB - i * GIB / 12,
+        },
+    );
+
+    let root = forecaster.forecast("/").unwrap();
+    assert!((root.growth_bytes_per_hour / GIB as f64 - 1.0).abs() < 0.01);
+    // 46 GiB used after 6 hours, 54 GiB left at 1 GiB/h.
+    let hours = root.seconds_until_full.unwrap() / 3600.0;
+    assert!((hours - 54.0).abs() < 0.2, "{}", hours);
+    assert!(forecaster
+        .forecast("/data")
+        .unwrap()
+        .seconds_until_full
+        .is_none());
+    let tmp = forecaster.forecast("/tmp").unwrap();
+    assert!(tmp.growth_bytes_per_hour < 0.0);
+    assert!(tmp.seconds_until_full.is_none());
+
+    let order: Vec<&str> = forecaster
+        .forecasts()
+        .iter()
+        .map(|f| f.mount_point.as_str())
+        .collect();
+    assert_eq!(order, ["/", "/data", "/tmp"]);
+}
+
+#[test]
+fn test_needs_history_and_forgets_unmounted_disks() {
+    let mut forecaster = DiskForecaster::new(ForecastConfig::default());
+    // 25 minutes of history, under the 30 minute minimum.
+    for i in 0..6u64 {
+        forecaster.sample(
+            &[disk("/", 10 * GIB + i * GIB, 100 * GIB)],
+            i as f64 * 300.0,
+        );
+    }
+    assert!(forecaster.forecast("/").is_none());
+    assert!(forecaster.sample(&[disk("/", 16 * GIB, 100 * GIB)], 1800.0));
+    assert!(forecaster.forecast("/").is_some());
+    // Samples closer together than the interval are skipped.
+    assert!(!forecaster.sample(&[], 1900.0));
+
+    assert!(forecaster.sample(&[disk("/mnt/usb", GIB, 8 * GIB)], 2100.0));
+    assert!(forecaster.forecast("/").is_none());
+    assert!(forecaster.forecasts().is_empty());
+}
+
+#[test]
+fn test_bursty_usage_gets_no_horizon() {
+    let mut forecaster = DiskForecaster::new(ForecastConfig::default());
+    // Log directory filling fast and rotated every 30 minutes.
+    run(&mut forecaster, &["/var"], 6, |_, i| {
+        20 * GIB + (i % 6) * 5 * GIB
+    });
+    let var = forecaster.forecast("/var").unwrap();
+    assert!(var.r_squared < 0.5, "{}", var.r_squared);
+    assert!(var.seconds_until_full.is_none());
+}
+
+fn disk_info(mount: &str, used_pct: u64, full_in_seconds: Option<f64>) -> DiskInfo {
+    DiskInfo {
+        name: "nvme0n1p2".to_string(),
+        filesystem: "btrfs".to_string(),
+        mount_point: mount.to_string(),
+        used: used_pct,
+        available: 100 - used_pct,
+        total: 100,
+        growth_bytes_per_hour: full_in_seconds.map(|_| 1.0),
+        full_in_seconds,
+    }
+}
+
+#[test]
+fn test_disk_filters_and_time_units() {
+    let monitor = SystemMonitor::new();
+    let filling = disk_info("/home", 80, Some(2.0 * 86400.0));
+    let steady = disk_info("/", 95, None);
+
+    let soon = Filter::parse("full_in < 3d").unwrap();
+    assert_eq!(soon.scope(), Scope::Disks);
+    assert!(soon.matches_disk(&filling, &monitor));
+    assert!(!soon.matches_disk(&steady, &monitor));
+    assert!(Filter::parse("full_in > 47h && full_in <= 2880min")
+        .unwrap()
+        .matches_disk(&filling, &monitor));
+    assert!(Filter::parse("used_pct >= 90 || mount ~ '^/home'")
+        .unwrap()
+        .matches_disk(&steady, &monitor));
+    assert!(
+        Filter::parse("fs == btrfs && device != sda1 && disk_growth > 0")
+            .unwrap()
+            .matches_disk(&filling, &monitor)
+    );
+
+    // A filter tests processes or disks, not both.
+    let err = Filter::parse("cpu > 1 && full_in < 1w").unwrap_err();
+    assert!(matches!(
+        err,
+        DashboardError::InvalidFilter { column: 12, .. }
+    ));
+    assert!(!Filter::parse("cpu >= 0")
+        .unwrap()
+        .matches_disk(&filling, &monitor));
+    let err = ProcessQuery {
+        filter: Some("used_pct > 1".to_string()),
+        ..Default::default()
+    }
+    .run(&monitor)
+    .unwrap_err();
+    assert!(matches!(err, DashboardError::InvalidFilter { .. }));
+}
+
+#[test]
+fn test_alert_rule_on_disks_reports_mount_points() {
+    let monitor = SystemMonitor::new();
+    let disks = DiskInfo::list(&monitor);
+    if disks.is_empty() {
+        return;
+    }
+    let mut engine = AlertEngine::new(&[AlertRule {
+        name: "any disk".to_string(),
+        condition: "size >= 0 && full_in > 0".to_string(),
+        for_seconds: 0,
+        actions: Vec::new(),
+    }])
+    .unwrap();
+    let events = engine.evaluate(&monitor, Instant::now());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].state, AlertState::Firing);
+    let mounts: Vec<String> = disks.into_iter().map(|d| d.mount_point).collect();
+    assert_eq!(events[0].matches, mounts);
+}
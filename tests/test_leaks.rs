@@ -0,0 +1,139 @@
+use rust_dashboard_lib::filter::Filter;
+use rust_dashboard_lib::leaks::{LeakConfig, LeakDetector};
+use rust_dashboard_lib::system::{CombinedProcess, SystemMonitor};
+
+const MIB: u64 = 1024 * 1024;
+
+fn group(name: &str, memory: u64) -> CombinedProcess {
+    CombinedProcess {
+        name: name.to_string(),
+        memory_usage: memory,
+        pids: vec![1],
+        ..Default::default()
+    }
+}
+
+/// Feed one sample per minute for `minutes`, with each group's memory
+/// given by `memory(name_index, minute)`.
+fn run(
+    detector: &mut LeakDetector,
+    names: &[&str],
+    minutes: u64,
+    memory: impl Fn(usize, u64) -> u64,
+) {
+    for minute in 0..minutes {
+        let groups: Vec<CombinedProcess> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| group(name, memory(i, minute)))
+            .collect();
+        detector.sample(&groups, minute as f64 * 60.0);
+    }
+}
+
+#[test]
+fn test_only_steady_growth_is_flagged() {
+    let mut detector = LeakDetector::new(LeakConfig::default());
+    let names = ["leaky", "flat", "sawtooth", "slow", "noisy-leak"];
+    run(&mut detector, &names, 90, |i, m| match i {
+        // 1 MiB/min = 60 MiB/h.
+        0 => 200 * MIB + m * MIB,
+        1 => 500 * MIB,
+        // Grows 4 MiB/min, then a collector drops it back every 10 min.
+        2 => 100 * MIB + (m % 10) * 4 * MIB,
+        // 6 MiB/h, under the default 20 MiB/h.
+        3 => 50 * MIB + m * MIB / 10,
+        // 60 MiB/h with jitter.
+        _ => 300 * MIB + m * MIB + (m * 7919 % 5) * MIB / 4,
+    });
+
+    let flagged: Vec<&str> = detector
+        .suspects()
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    assert_eq!(flagged.len(), 2, "{:?}", flagged);
+    assert!(flagged.contains(&"leaky") && flagged.contains(&"noisy-leak"));
+    let leak = detector.suspect("leaky").unwrap();
+    assert!((leak.growth_bytes_per_hour / MIB as f64 - 60.0).abs() < 0.5);
+    assert!(leak.window_seconds <= 60.0 * 60.0);
+    assert!(detector.growth_rate("flat").unwrap().abs() < 1.0);
+}
+
+#[test]
+fn test_needs_history_and_respects_interval() {
+    let mut detector = LeakDetector::new(LeakConfig::default());
+    run(&mut detector, &["leaky"], 15, |_, m| {
+        100 * MIB + m * 10 * MIB
+    });
+    assert!(detector.suspects().is_empty());
+    assert!(detector.growth_rate("leaky").is_none());
+
+    // Samples closer together than the interval are skipped.
+    let mut detector = LeakDetector::new(LeakConfig::default());
+    assert!(detector.sample(&[group("a", 1)], 0.0));
+    assert!(!detector.sample(&[group("a", 1)], 10.0));
+    assert!(detector.sample(&[group("a", 1)], 30.0));
+}
+
+#[test]
+fn test_single_outlier_does_not_hide_a_leak() {
+    let mut detector = LeakDetector::new(LeakConfig::default());
+    run(&mut detector, &["daemon"], 60, |_, m| {
+        if m == 45 {
+            10 * MIB
+        } else {
+            200 * MIB + m * MIB
+        }
+    });
+    assert!(detector.suspect("daemon").is_some());
+}
+
+#[test]
+fn test_exited_groups_are_forgotten() {
+    let mut detector = LeakDetector::new(LeakConfig::default());
+    run(&mut detector, &["leaky"], 40, |_, m| 100 * MIB + m * MIB);
+    assert!(detector.suspect("leaky").is_some());
+    detector.sample(&[group("other", MIB)], 40.0 * 60.0);
+    assert!(detector.suspects().is_empty());
+    assert!(detector.growth_rate("leaky").is_none());
+}
+
+#[test]
+fn test_only_large_groups_are_tracked() {
+    let mut detector = LeakDetector::new(LeakConfig::default());
+    let names: Vec<String> = (0..300).map(|i| format!("group {}", i)).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    // Every group but the first is over the 32 MiB floor; the largest
+    // hundred of them are tracked.
+    run(&mut detector, &names, 25, |i, _| i as u64 * MIB);
+    assert!(detector.growth_rate("group 0").is_none());
+    assert!(detector.growth_rate("group 150").is_none());
+    assert!(detector.growth_rate("group 200").is_some());
+    assert!(detector.growth_rate("group 299").is_some());
+
+    // A tracked group stays tracked while it shrinks below the floor.
+    let mut detector = LeakDetector::new(LeakConfig::default());
+    run(&mut detector, &["shrinking"], 25, |_, m| (50 - 2 * m) * MIB);
+    assert!(detector.growth_rate("shrinking").unwrap() < 0.0);
+}
+
+#[test]
+fn test_leak_fields_in_filters() {
+    let mut monitor = SystemMonitor::new();
+    run(&mut monitor.leaks, &["leaky", "flat"], 40, |i, m| {
+        if i == 0 {
+            100 * MIB + m * MIB
+        } else {
+            100 * MIB
+        }
+    });
+    let leaky = group("leaky", 140 * MIB);
+    let flat = group("flat", 100 * MIB);
+
+    let filter = Filter::parse("leak && mem_growth > 50M").unwrap();
+    assert!(filter.matches(&leaky, &monitor));
+    assert!(!filter.matches(&flat, &monitor));
+    assert!(Filter::parse("!leak").unwrap().matches(&flat, &monitor));
+    assert!(Filter::parse("leak == 1").is_err());
+}
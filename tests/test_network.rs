@@ -0,0 +1,142 @@
+use rust_dashboard_lib::network::{diff_link_states, read_link_info, LinkEvent, LinkState};
+use rust_dashboard_lib::system::SystemMonitor;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+fn write_iface(root: &Path, iface: &str, files: &[(&str, &str)]) {
+    for (rel, contents) in files {
+        let path = root.join(iface).join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+#[test]
+fn test_link_state_from_operstate() {
+    assert_eq!(LinkState::from_operstate("up\n"), LinkState::Up);
+    assert_eq!(LinkState::from_operstate("down\n"), LinkState::Down);
+    assert_eq!(LinkState::from_operstate("dormant"), LinkState::Down);
+    assert_eq!(LinkState::from_operstate("unknown"), LinkState::Unknown);
+    assert_eq!(LinkState::from_operstate(""), LinkState::Unknown);
+}
+
+#[test]
+fn test_read_link_info_from_fixture() {
+    let dir = tempfile::tempdir().expect("create tempdir");
+    write_iface(
+        dir.path(),
+        "eth0",
+        &[
+            ("operstate", "up\n"),
+            ("speed", "1000\n"),
+            ("statistics/rx_dropped", "12\n"),
+            ("statistics/tx_dropped", "3\n"),
+        ],
+    );
+
+    let info = read_link_info(dir.path(), "eth0");
+    assert_eq!(info.link_state, LinkState::Up);
+    assert_eq!(info.speed_mbps, Some(1000));
+    assert_eq!(info.rx_dropped, 12);
+    assert_eq!(info.tx_dropped, 3);
+}
+
+#[test]
+fn test_read_link_info_negative_speed_is_none() {
+    let dir = tempfile::tempdir().expect("create tempdir");
+    write_iface(
+        dir.path(),
+        "wlan0",
+        &[("operstate", "down\n"), ("speed", "-1\n")],
+    );
+
+    let info = read_link_info(dir.path(), "wlan0");
+    assert_eq!(info.link_state, LinkState::Down);
+    assert_eq!(info.speed_mbps, None);
+    assert_eq!(info.rx_dropped, 0);
+}
+
+#[test]
+fn test_read_link_info_missing_interface_defaults() {
+    let dir = tempfile::tempdir().expect("create tempdir");
+    let info = read_link_info(dir.path(), "does-not-exist");
+    assert_eq!(info.link_state, LinkState::Unknown);
+    assert_eq!(info.speed_mbps, None);
+}
+
+#[test]
+fn test_diff_link_states_reports_transitions() {
+    let previous = HashMap::from([
+        ("eth0".to_string(), LinkState::Up),
+        ("wlan0".to_string(), LinkState::Down),
+        ("lo".to_string(), LinkState::Unknown),
+        ("tun0".to_string(), LinkState::Up),
+    ]);
+    let current = HashMap::from([
+        ("eth0".to_string(), LinkState::Down),
+        ("wlan0".to_string(), LinkState::Up),
+        ("lo".to_string(), LinkState::Unknown),
+        ("docker0".to_string(), LinkState::Up),
+    ]);
+
+    let events = diff_link_states(&previous, &current);
+    assert_eq!(
+        events,
+        vec![
+            LinkEvent {
+                interface: "docker0".to_string(),
+                previous: LinkState::Unknown,
+                current: LinkState::Up,
+            },
+            LinkEvent {
+                interface: "eth0".to_string(),
+                previous: LinkState::Up,
+                current: LinkState::Down,
+            },
+            LinkEvent {
+                interface: "tun0".to_string(),
+                previous: LinkState::Up,
+                current: LinkState::Down,
+            },
+            LinkEvent {
+                interface: "wlan0".to_string(),
+                previous: LinkState::Down,
+                current: LinkState::Up,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_diff_link_states_no_change_is_empty() {
+    let states = HashMap::from([("eth0".to_string(), LinkState::Up)]);
+    assert!(diff_link_states(&states, &states).is_empty());
+}
+
+#[test]
+fn test_network_interfaces_format() {
+    let mut mon = SystemMonitor::new();
+    mon.refresh();
+    let ifaces = mon.network_interfaces();
+    for iface in &ifaces {
+        assert!(!iface.interface.is_empty());
+        assert!(iface.rx_rate.is_finite() && iface.rx_rate >= 0.0);
+        assert!(iface.tx_rate.is_finite() && iface.tx_rate >= 0.0);
+        for addr in &iface.ipv4 {
+            assert!(addr.contains('/'), "expected CIDR notation, got {}", addr);
+        }
+    }
+    // Sorted by name for stable rendering
+    let names: Vec<_> = ifaces.iter().map(|i| i.interface.clone()).collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(names, sorted);
+}
+
+#[test]
+fn test_take_link_events_drains() {
+    let mut mon = SystemMonitor::new();
+    let _ = mon.take_link_events();
+    assert!(mon.take_link_events().is_empty());
+}
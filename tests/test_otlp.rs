@@ -0,0 +1,244 @@
+#![cfg(feature = "otlp")]
+
+mod common;
+
+use common::{process, GIB};
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::network::LinkState;
+use rust_dashboard_lib::otlp::{metrics_request, metrics_url};
+use rust_dashboard_lib::push::{PushConfig, PushTarget, Pusher};
+use rust_dashboard_lib::snapshot::{DiskInfo, MemoryInfo, NetworkInfo, SystemSnapshot};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+/// 2026-10-18 00:00:00 UTC.
+const NOW: f64 = 1_792_281_600.0;
+
+fn snapshot() -> SystemSnapshot {
+    SystemSnapshot {
+        per_cpu: vec![50.0, 0.0],
+        memory: MemoryInfo {
+            used: 2 * GIB,
+            free: 6 * GIB,
+            total: 8 * GIB,
+            available: 6 * GIB,
+            ..Default::default()
+        },
+        disks: vec![DiskInfo {
+            name: "/dev/sda1".to_string(),
+            filesystem: "ext4".to_string(),
+            mount_point: "/".to_string(),
+            used: GIB,
+            available: 3 * GIB,
+            total: 4 * GIB,
+            ..Default::default()
+        }],
+        networks: vec![NetworkInfo {
+            interface: "eth0".to_string(),
+            rx_bytes: 1000,
+            tx_bytes: 500,
+            mtu: 1500,
+            link_state: LinkState::Up,
+            rx_packets: 10,
+            tx_packets: 5,
+            ..Default::default()
+        }],
+        processes: vec![
+            process("postgres", 100.0, GIB, &[10, 11]),
+            process("cron", 0.0, 1024, &[20]),
+        ],
+        load_average: (1.5, 1.0, 0.5),
+        ..common::snapshot(25.0)
+    }
+}
+
+fn config() -> PushConfig {
+    PushConfig {
+        tags: [("deployment.environment".to_string(), "prod".to_string())].into(),
+        process_limit: 1,
+        ..Default::default()
+    }
+}
+
+fn attribute<'a>(attributes: &'a Value, key: &str) -> Option<&'a str> {
+    attributes
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|a| a["key"] == key)
+        .and_then(|a| a["value"]["stringValue"].as_str())
+}
+
+fn metric<'a>(request: &'a Value, name: &str) -> &'a Value {
+    request["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["name"] == name)
+        .unwrap_or_else(|| panic!("no {}", name))
+}
+
+#[test]
+fn test_semantic_conventions() {
+    let request = metrics_request(&snapshot(), "db1", &config(), NOW);
+    let resource = &request["resourceMetrics"][0]["resource"]["attributes"];
+    assert_eq!(attribute(resource, "host.name"), Some("db1"));
+    assert_eq!(attribute(resource, "service.name"), Some("rust-dashboard"));
+    assert_eq!(attribute(resource, "deployment.environment"), Some("prod"));
+
+    let cpu = metric(&request, "system.cpu.utilization");
+    assert_eq!(cpu["unit"], "1");
+    let point = &cpu["gauge"]["dataPoints"][0];
+    assert_eq!(point["asDouble"], 0.25);
+    assert_eq!(point["timeUnixNano"], "1792281600000000000");
+
+    let memory = metric(&request, "system.memory.usage");
+    assert_eq!(memory["unit"], "By");
+    assert_eq!(memory["sum"]["isMonotonic"], false);
+    let used = &memory["sum"]["dataPoints"][0];
+    assert_eq!(
+        attribute(&used["attributes"], "system.memory.state"),
+        Some("used")
+    );
+    assert_eq!(used["asInt"], (2 * GIB).to_string());
+
+    let io = &metric(&request, "system.network.io")["sum"];
+    assert_eq!(io["isMonotonic"], true);
+    assert_eq!(io["aggregationTemporality"], 2, "cumulative");
+    let points = io["dataPoints"].as_array().unwrap();
+    let directions: Vec<(&str, &str, &str)> = points
+        .iter()
+        .map(|p| {
+            (
+                attribute(&p["attributes"], "network.interface.name").unwrap(),
+                attribute(&p["attributes"], "network.io.direction").unwrap(),
+                p["asInt"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        directions,
+        [("eth0", "receive", "1000"), ("eth0", "transmit", "500")]
+    );
+    // Counters start at boot.
+    assert_eq!(points[0]["startTimeUnixNano"], "1792281500000000000");
+
+    let fs = &metric(&request, "system.filesystem.usage")["sum"]["dataPoints"][1];
+    assert_eq!(
+        attribute(&fs["attributes"], "system.filesystem.mountpoint"),
+        Some("/")
+    );
+    assert_eq!(
+        attribute(&fs["attributes"], "system.filesystem.state"),
+        Some("free")
+    );
+
+    // Only the busiest group; one full core of two is half the machine.
+    let process = metric(&request, "process.cpu.utilization")["gauge"]["dataPoints"]
+        .as_array()
+        .unwrap();
+    assert_eq!(process.len(), 1);
+    assert_eq!(
+        attribute(&process[0]["attributes"], "process.executable.name"),
+        Some("postgres")
+    );
+    assert_eq!(process[0]["asDouble"], 0.5);
+    assert!(
+        !request.to_string().contains("system.paging.usage"),
+        "no swap"
+    );
+}
+
+#[test]
+fn test_export_to_mock_collector() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let collector = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut head = String::new();
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            head.push_str(&line);
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        write!(
+            reader.get_mut(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 2\r\n\r\n{{}}"
+        )
+        .unwrap();
+        (head, serde_json::from_slice::<Value>(&body).unwrap())
+    });
+
+    let target = PushTarget::Otlp {
+        endpoint,
+        headers: [("Authorization".to_string(), "Bearer abc".to_string())].into(),
+    };
+    let pusher = Pusher::new(
+        PushConfig {
+            targets: vec![target],
+            ..config()
+        },
+        "db1",
+    )
+    .unwrap();
+    pusher.push(&snapshot(), NOW).unwrap();
+
+    let (head, request) = collector.join().unwrap();
+    assert!(head.starts_with("POST /v1/metrics HTTP/1.1\r\n"));
+    assert!(head.contains("Content-Type: application/json\r\n"));
+    assert!(head.contains("Authorization: Bearer abc\r\n"));
+    assert_eq!(request, metrics_request(&snapshot(), "db1", &config(), NOW));
+}
+
+#[test]
+fn test_https_collector_with_auth() {
+    let target = PushTarget::Otlp {
+        endpoint: "https://otel.example.com:4318".to_string(),
+        headers: BTreeMap::from([("Authorization".to_string(), "Bearer abc".to_string())]),
+    };
+    assert!(target.validate().is_ok());
+    assert_eq!(
+        metrics_url("https://otel.example.com:4318/"),
+        "https://otel.example.com:4318/v1/metrics"
+    );
+}
+
+#[test]
+fn test_invalid_otlp_targets() {
+    for (endpoint, header) in [
+        ("ftp://collector:4318", "Authorization"),
+        ("http://collector:4318", "X-Bad\r\nHost"),
+        ("http://collector:4318", ""),
+    ] {
+        let target = PushTarget::Otlp {
+            endpoint: endpoint.to_string(),
+            headers: BTreeMap::from([(header.to_string(), "x".to_string())]),
+        };
+        let config = PushConfig {
+            targets: vec![target],
+            ..Default::default()
+        };
+        assert!(
+            matches!(
+                Pusher::new(config, "db1"),
+                Err(DashboardError::ConfigError(_))
+            ),
+            "{} {:?}",
+            endpoint,
+            header
+        );
+    }
+}
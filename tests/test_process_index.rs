@@ -0,0 +1,123 @@
+use rust_dashboard_lib::process_index::{
+    group_processes, IndexUpdate, ProcessIndex, ProcessSample,
+};
+use rust_dashboard_lib::system::CombinedProcess;
+
+fn sample(pid: u32, name: &str, cpu: f32, memory: u64) -> ProcessSample<'_> {
+    ProcessSample {
+        pid,
+        name: name.into(),
+        cpu_usage: cpu,
+        memory,
+        net_rx_rate: 0.0,
+        net_tx_rate: 0.0,
+        connections: 0,
+    }
+}
+
+fn sorted(mut list: Vec<CombinedProcess>) -> Vec<CombinedProcess> {
+    list.sort_by(|a, b| a.name.cmp(&b.name));
+    list
+}
+
+fn assert_matches_rebuild(index: &ProcessIndex, samples: &[ProcessSample<'_>]) {
+    let expected = sorted(group_processes(samples.iter().cloned()));
+    let actual = sorted(index.processes().to_vec());
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(&expected) {
+        assert_eq!(a.name, e.name);
+        assert_eq!(a.pids, e.pids);
+        assert_eq!(a.memory_usage, e.memory_usage);
+        assert_eq!(a.connections, e.connections);
+        assert!(
+            (a.cpu_usage - e.cpu_usage).abs() < 1e-3,
+            "{}: {} vs {}",
+            a.name,
+            a.cpu_usage,
+            e.cpu_usage
+        );
+        assert!((a.net_rx_rate - e.net_rx_rate).abs() < 1e-6);
+        assert!((a.net_tx_rate - e.net_tx_rate).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_index_matches_rebuild_through_churn() {
+    let mut index = ProcessIndex::new();
+    let ticks: Vec<Vec<ProcessSample>> = vec![
+        vec![
+            sample(1, "init", 0.0, 100),
+            sample(10, "worker", 5.0, 1000),
+            sample(11, "worker", 2.5, 2000),
+            sample(20, "shell", 1.0, 300),
+        ],
+        // A worker changes, one exits, a new process starts.
+        vec![
+            sample(1, "init", 0.0, 100),
+            sample(10, "worker", 7.5, 1500),
+            sample(20, "shell", 1.0, 300),
+            sample(30, "editor", 3.0, 4000),
+        ],
+        // The shell execs into a worker; the editor's group empties.
+        vec![
+            sample(1, "init", 0.0, 100),
+            sample(10, "worker", 7.5, 1500),
+            sample(20, "worker", 0.5, 50),
+        ],
+    ];
+    for tick in &ticks {
+        index.update(tick.iter().cloned());
+        assert_matches_rebuild(&index, tick);
+    }
+    assert_eq!(index.pid_count(), 3);
+}
+
+#[test]
+fn test_unchanged_processes_do_no_work() {
+    let mut index = ProcessIndex::new();
+    let tick = [sample(1, "a", 1.0, 10), sample(2, "b", 2.0, 20)];
+    assert_eq!(
+        index.update(tick.iter().cloned()),
+        IndexUpdate {
+            added: 2,
+            changed: 0,
+            exited: 0
+        }
+    );
+    assert_eq!(index.update(tick.iter().cloned()), IndexUpdate::default());
+
+    let update = index.update([sample(1, "a", 1.5, 10)]);
+    assert_eq!(
+        update,
+        IndexUpdate {
+            added: 0,
+            changed: 1,
+            exited: 1
+        }
+    );
+    assert_eq!(index.processes().len(), 1);
+}
+
+#[test]
+fn test_pids_stay_sorted() {
+    let mut index = ProcessIndex::new();
+    index.update([
+        sample(30, "w", 0.0, 1),
+        sample(10, "w", 0.0, 1),
+        sample(20, "w", 0.0, 1),
+    ]);
+    assert_eq!(index.processes()[0].pids, vec![10, 20, 30]);
+    index.update([sample(30, "w", 0.0, 1), sample(5, "w", 0.0, 1)]);
+    assert_eq!(index.processes()[0].pids, vec![5, 30]);
+}
+
+#[test]
+fn test_repeated_changes_do_not_drift() {
+    let mut index = ProcessIndex::new();
+    for i in 0..10_000u32 {
+        let cpu = (i % 97) as f32 * 0.37;
+        index.update([sample(1, "busy", cpu, 1), sample(2, "busy", 1.0, 1)]);
+    }
+    index.update([sample(1, "busy", 0.0, 1), sample(2, "busy", 0.0, 1)]);
+    assert!(index.processes()[0].cpu_usage.abs() < 1e-3);
+}
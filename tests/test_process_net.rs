@@ -0,0 +1,183 @@
+use rust_dashboard_lib::process_net::{
+    active_connections, host_netns, netns_inode, parse_net_dev, NetAttribution, ProcessNetSampler,
+};
+use rust_dashboard_lib::sockets::read_socket_table;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc")
+}
+
+#[test]
+fn test_parse_net_dev_skips_loopback() {
+    let contents =
+        std::fs::read_to_string(fixture_root().join("7000/net/dev")).expect("read fixture");
+    assert_eq!(parse_net_dev(&contents), (1_000_000, 400_000));
+}
+
+#[test]
+fn test_parse_net_dev_malformed() {
+    assert_eq!(parse_net_dev(""), (0, 0));
+    assert_eq!(parse_net_dev("h1\nh2\n eth0: not numbers\n"), (0, 0));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_netns_inode_from_fixture() {
+    let root = fixture_root();
+    assert_eq!(netns_inode(&root, 7000), Some(4026532500));
+    assert_eq!(host_netns(&root), Some(4026531840));
+    assert_eq!(netns_inode(&root, 999_999), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_active_connections_excludes_listeners() {
+    let entries = read_socket_table(&fixture_root()).expect("read fixture tables");
+    let counts = active_connections(&entries);
+    // 4242 owns one established TCP socket; 5151 only has listeners.
+    assert_eq!(counts.get(&4242), Some(&1));
+    assert_eq!(counts.get(&5151), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sampler_attributes_namespace_and_connections() {
+    let mut sampler = ProcessNetSampler::new(fixture_root());
+    let pids = [1, 4242, 5151, 7000, 7001];
+
+    // First sample has no namespace baseline yet, but host traffic is
+    // already attributable by connection share.
+    let first = sampler.sample(&pids, (1000.0, 500.0), Duration::from_secs(5));
+    let web = &first[&4242];
+    assert_eq!(web.attribution, NetAttribution::Connections);
+    assert_eq!(web.connections, 1);
+    assert!((web.rx_rate - 1000.0).abs() < 1e-9);
+    assert!((web.tx_rate - 500.0).abs() < 1e-9);
+
+    let pg = &first[&5151];
+    assert_eq!(pg.attribution, NetAttribution::None);
+    assert_eq!(pg.rx_rate, 0.0);
+
+    let nginx = &first[&7000];
+    assert_eq!(nginx.attribution, NetAttribution::Namespace);
+    assert_eq!(nginx.netns, Some(4026532500));
+    assert_eq!(nginx.rx_rate, 0.0);
+
+    // Second sample with unchanged counters: namespace rate is zero, and
+    // both members share it.
+    let second = sampler.sample(&pids, (0.0, 0.0), Duration::from_secs(5));
+    assert_eq!(second[&7000].rx_rate, 0.0);
+    assert_eq!(second[&7001].attribution, NetAttribution::Namespace);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sampler_namespace_rate_split_across_members() {
+    let dir = tempfile::tempdir().expect("create tempdir");
+    let root = dir.path();
+    for (pid, ns) in [(1, 100), (10, 200), (11, 200)] {
+        let ns_dir = root.join(pid.to_string()).join("ns");
+        std::fs::create_dir_all(&ns_dir).unwrap();
+        std::os::unix::fs::symlink(format!("net:[{}]", ns), ns_dir.join("net")).unwrap();
+    }
+    let dev = root.join("10/net");
+    std::fs::create_dir_all(&dev).unwrap();
+    let write_dev = |rx: u64, tx: u64| {
+        std::fs::write(
+            dev.join("dev"),
+            format!(
+                "h1\nh2\n  eth0: {} 0 0 0 0 0 0 0 {} 0 0 0 0 0 0 0\n",
+                rx, tx
+            ),
+        )
+        .unwrap();
+    };
+
+    let mut sampler = ProcessNetSampler::new(root);
+    write_dev(1000, 2000);
+    sampler.sample(&[1, 10, 11], (0.0, 0.0), Duration::from_secs(2));
+    write_dev(5000, 4000);
+    let usage = sampler.sample(&[1, 10, 11], (0.0, 0.0), Duration::from_secs(2));
+
+    // (5000 - 1000) / 2 s = 2000 B/s, split across two members.
+    assert!((usage[&10].rx_rate - 1000.0).abs() < 1e-9);
+    assert!((usage[&11].rx_rate - 1000.0).abs() < 1e-9);
+    assert!((usage[&10].tx_rate - 500.0).abs() < 1e-9);
+    assert_eq!(usage[&1].attribution, NetAttribution::None);
+}
+
+#[test]
+fn test_combined_process_network_fields() {
+    use rust_dashboard_lib::system::SystemMonitor;
+    let mut mon = SystemMonitor::new();
+    mon.refresh();
+    for proc in mon.combined_process_list() {
+        assert!(proc.net_rx_rate.is_finite() && proc.net_rx_rate >= 0.0);
+        assert!(proc.net_tx_rate.is_finite() && proc.net_tx_rate >= 0.0);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sampler_follows_sockets_between_samples() {
+    use std::os::unix::fs::symlink;
+
+    let dir = tempfile::tempdir().expect("create tempdir");
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("net")).unwrap();
+    let fd_dir = root.join("20/fd");
+    std::fs::create_dir_all(&fd_dir).unwrap();
+    // Established TCP connections with the given inodes.
+    let write_tcp = |inodes: &[u64]| {
+        let mut table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n".to_string();
+        for (i, inode) in inodes.iter().enumerate() {
+            table += &format!(
+                "   {}: 0F02000A:9C4E 2A00A8C0:01BB 01 00000000:00000000 00:00000000 00000000  1000        0 {} 1 0000000000000000 20 4 30 10 -1\n",
+                i, inode
+            );
+        }
+        std::fs::write(root.join("net/tcp"), table).unwrap();
+    };
+
+    let mut sampler = ProcessNetSampler::new(root);
+    write_tcp(&[500]);
+    symlink("/dev/null", fd_dir.join("0")).unwrap();
+    symlink("socket:[500]", fd_dir.join("3")).unwrap();
+    let usage = sampler.sample(&[20], (0.0, 0.0), Duration::from_secs(5));
+    assert_eq!(usage[&20].connections, 1);
+
+    // A new descriptor, and fd 3 closed and reused for another socket.
+    write_tcp(&[501, 502]);
+    std::fs::remove_file(fd_dir.join("3")).unwrap();
+    symlink("socket:[501]", fd_dir.join("3")).unwrap();
+    symlink("socket:[502]", fd_dir.join("4")).unwrap();
+    let usage = sampler.sample(&[20], (0.0, 0.0), Duration::from_secs(5));
+    assert_eq!(usage[&20].connections, 2);
+
+    // Closed descriptors stop counting.
+    std::fs::remove_file(fd_dir.join("4")).unwrap();
+    let usage = sampler.sample(&[20], (0.0, 0.0), Duration::from_secs(5));
+    assert_eq!(usage[&20].connections, 1);
+}
+
+#[test]
+fn test_process_net_sampled_only_while_shown() {
+    use rust_dashboard_lib::config::{RefreshSchedule, Subsystem};
+    use rust_dashboard_lib::system::SystemMonitor;
+
+    let mut mon = SystemMonitor::new();
+    // Follow the global interval, so every `refresh` includes networks.
+    mon.set_schedule(RefreshSchedule {
+        networks: Default::default(),
+        ..Default::default()
+    });
+    mon.set_visible([Subsystem::Processes]);
+    mon.refresh();
+    assert!(mon.process_net_usage.is_empty());
+
+    mon.set_visible([Subsystem::Processes, Subsystem::Networks]);
+    mon.refresh();
+    assert!(!mon.process_net_usage.is_empty());
+}
@@ -0,0 +1,330 @@
+mod common;
+
+use common::{process, GIB};
+use rust_dashboard_lib::config::AppConfig;
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::network::LinkState;
+use rust_dashboard_lib::push::{
+    influx_lines, packets, samples, statsd_lines, PushConfig, PushExporter, PushTarget, Pusher,
+};
+use rust_dashboard_lib::snapshot::{DiskInfo, MemoryInfo, NetworkInfo, SystemSnapshot};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, UdpSocket};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 2026-10-18 00:00:00 UTC.
+const NOW: f64 = 1_792_281_600.0;
+
+fn snapshot() -> SystemSnapshot {
+    SystemSnapshot {
+        memory: MemoryInfo {
+            used: 2 * GIB,
+            free: 6 * GIB,
+            total: 8 * GIB,
+            available: 6 * GIB,
+            ..Default::default()
+        },
+        disks: vec![DiskInfo {
+            name: "/dev/sdb1".to_string(),
+            filesystem: "ext4".to_string(),
+            mount_point: "/mnt/my data".to_string(),
+            used: GIB,
+            available: 3 * GIB,
+            total: 4 * GIB,
+            ..Default::default()
+        }],
+        networks: vec![NetworkInfo {
+            interface: "eth0".to_string(),
+            rx_bytes: 1000,
+            tx_bytes: 500,
+            rx_rate: 125.5,
+            mtu: 1500,
+            link_state: LinkState::Up,
+            ..Default::default()
+        }],
+        processes: vec![
+            process("idle", 0.0, 1024, &[1, 2]),
+            process("web,server", 30.0, GIB, &[1, 2]),
+            process("db", 10.0, 2 * GIB, &[1, 2]),
+        ],
+        load_average: (1.5, 1.0, 0.5),
+        ..common::snapshot(42.5)
+    }
+}
+
+fn config(targets: Vec<PushTarget>) -> PushConfig {
+    PushConfig {
+        tags: [("env".to_string(), "prod".to_string())].into(),
+        process_limit: 2,
+        targets,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_influx_line_protocol() {
+    let points = samples(&snapshot(), "db 1", 2);
+    let groups: Vec<&str> = points
+        .iter()
+        .filter(|s| s.measurement == "process")
+        .map(|s| s.tags[1].1.as_str())
+        .collect();
+    assert_eq!(groups, ["web,server", "db"], "busiest first, limited");
+
+    let lines = influx_lines(&points, &config(Vec::new()), NOW);
+    assert_eq!(lines.len(), 5);
+    assert!(lines[0]
+        .starts_with("rdash_system,host=db\\ 1,env=prod cpu_usage=42.5,memory_used=2147483648,"));
+    assert!(lines[0].ends_with(" 1792281600000000000"));
+    assert!(lines[1].starts_with(
+        "rdash_disk,host=db\\ 1,mount=/mnt/my\\ data,device=/dev/sdb1,fs=ext4,env=prod \
+         used=1073741824,available=3221225472,total=4294967296 "
+    ));
+    assert!(lines[2].starts_with("rdash_net,host=db\\ 1,interface=eth0,env=prod rx_rate=125.5,"));
+    assert!(lines[3].starts_with("rdash_process,host=db\\ 1,group=web\\,server,env=prod "));
+
+    // No prefix, no empty tag values.
+    let bare = PushConfig {
+        prefix: String::new(),
+        ..Default::default()
+    };
+    let lines = influx_lines(&samples(&snapshot(), "", 0), &bare, NOW);
+    assert!(lines[0].starts_with("system cpu_usage=42.5,"));
+    assert!(lines[1].starts_with("disk,mount="));
+}
+
+#[test]
+fn test_statsd_and_dogstatsd_lines() {
+    let points = samples(&snapshot(), "db1", 1);
+    let tagged = config(Vec::new());
+
+    let plain = statsd_lines(&points, &tagged, false);
+    assert!(plain.contains(&"rdash.db1.system.cpu_usage:42.5|g".to_string()));
+    assert!(plain.contains(&"rdash.db1.disk.mnt_my_data.used:1073741824|g".to_string()));
+    assert!(plain.contains(&"rdash.db1.net.eth0.rx_rate:125.5|g".to_string()));
+    assert!(plain.contains(&"rdash.db1.process.web_server.instances:2|g".to_string()));
+
+    let dog = statsd_lines(&points, &tagged, true);
+    assert!(dog.contains(&"rdash.system.load_1:1.5|g|#host:db1,env:prod".to_string()));
+    assert!(dog.contains(
+        &"rdash.disk.total:4294967296|g|#host:db1,mount:/mnt/my data,device:/dev/sdb1,fs:ext4,env:prod"
+            .to_string()
+    ));
+    assert!(dog
+        .contains(&"rdash.process.cpu_usage:30|g|#host:db1,group:web_server,env:prod".to_string()));
+
+    let lines: Vec<String> = ["aaaa", "bb", "cccccccccc", "d"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    assert_eq!(packets(&lines, 8), ["aaaa\nbb", "cccccccccc", "d"]);
+}
+
+#[test]
+fn test_push_over_udp() {
+    let influx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let statsd = UdpSocket::bind("127.0.0.1:0").unwrap();
+    for socket in [&influx, &statsd] {
+        socket
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+    }
+    let mut config = config(vec![
+        PushTarget::InfluxUdp {
+            address: influx.local_addr().unwrap().to_string(),
+        },
+        PushTarget::Statsd {
+            address: statsd.local_addr().unwrap().to_string(),
+            dogstatsd: false,
+        },
+    ]);
+    config.max_packet_bytes = 512;
+    let pusher = Pusher::new(config, "db1").unwrap();
+    pusher.push(&snapshot(), NOW).unwrap();
+
+    let receive = |socket: &UdpSocket, expected: usize| -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut buf = [0; 2048];
+        while lines.len() < expected {
+            let len = socket.recv(&mut buf).unwrap();
+            assert!(len <= 512, "datagram of {} bytes", len);
+            let packet = std::str::from_utf8(&buf[..len]).unwrap();
+            lines.extend(packet.lines().map(str::to_string));
+        }
+        lines
+    };
+    let expected = samples(&snapshot(), "db1", 2);
+    let lines = receive(&influx, expected.len());
+    assert!(lines[0].starts_with("rdash_system,host=db1,env=prod "));
+    let fields: usize = expected.iter().map(|s| s.fields.len()).sum();
+    let lines = receive(&statsd, fields);
+    assert_eq!(lines[0], "rdash.db1.system.cpu_usage:42.5|g");
+}
+
+/// Answers one request with `status` and returns its head and body.
+fn stand_in(status: u16) -> (String, thread::JoinHandle<(String, String)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!(
+        "http://{}/api/v2/write?org=ops&bucket=hosts",
+        listener.local_addr().unwrap()
+    );
+    let handle = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut head = String::new();
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            head.push_str(&line);
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        write!(
+            reader.get_mut(),
+            "HTTP/1.1 {} X\r\nContent-Length: 0\r\n\r\n",
+            status
+        )
+        .unwrap();
+        (head, String::from_utf8(body).unwrap())
+    });
+    (url, handle)
+}
+
+#[test]
+fn test_push_over_http() {
+    let (url, server) = stand_in(204);
+    let target = PushTarget::InfluxHttp {
+        url,
+        token: Some("secret".to_string()),
+    };
+    Pusher::new(config(vec![target]), "db1")
+        .unwrap()
+        .push(&snapshot(), NOW)
+        .unwrap();
+    let (head, body) = server.join().unwrap();
+    assert!(head.starts_with("POST /api/v2/write?org=ops&bucket=hosts HTTP/1.1\r\n"));
+    assert!(head.contains("Authorization: Token secret\r\n"));
+    assert_eq!(body.lines().count(), 5);
+    assert!(body.ends_with(" 1792281600000000000\n"));
+
+    // A rejected write is reported, naming the target.
+    let (url, server) = stand_in(400);
+    let target = PushTarget::InfluxHttp { url, token: None };
+    let result = Pusher::new(config(vec![target]), "db1")
+        .unwrap()
+        .push(&snapshot(), NOW);
+    server.join().unwrap();
+    match result {
+        Err(DashboardError::PushFailed(message)) => {
+            assert!(message.starts_with("InfluxDB http://"), "{}", message);
+            assert!(message.ends_with("returned 400"), "{}", message);
+        }
+        other => panic!("expected PushFailed, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_exporter_pushes_once_per_interval() {
+    let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    socket
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut config = config(vec![PushTarget::Statsd {
+        address: socket.local_addr().unwrap().to_string(),
+        dogstatsd: true,
+    }]);
+    config.interval_seconds = 60;
+    let mut exporter = PushExporter::start(Pusher::new(config, "db1").unwrap()).unwrap();
+    let snapshot = Arc::new(snapshot());
+    let start = Instant::now();
+    assert!(exporter.offer(&snapshot, start, NOW));
+    assert!(!exporter.offer(&snapshot, start + Duration::from_secs(30), NOW + 30.0));
+
+    let mut buf = [0; 2048];
+    let len = socket.recv(&mut buf).unwrap();
+    assert!(std::str::from_utf8(&buf[..len])
+        .unwrap()
+        .starts_with("rdash.system.cpu_usage:42.5|g|#host:db1,env:prod"));
+    // The worker is idle again well before the next interval.
+    thread::sleep(Duration::from_millis(200));
+    assert!(exporter.offer(&snapshot, start + Duration::from_secs(60), NOW + 60.0));
+}
+
+#[test]
+fn test_push_config() {
+    let app: AppConfig = toml::from_str(
+        r#"
+        refresh_interval_seconds = 2
+        theme = "Dark"
+
+        [push]
+        interval_seconds = 5
+        tags = { env = "prod" }
+
+        [[push.targets]]
+        type = "influx_http"
+        url = "http://localhost:8086/write?db=rdash"
+
+        [[push.targets]]
+        type = "statsd"
+        address = "127.0.0.1:8125"
+        dogstatsd = true
+        "#,
+    )
+    .unwrap();
+    assert_eq!(app.push.interval_seconds, 5);
+    assert_eq!(app.push.process_limit, 10);
+    assert_eq!(
+        app.push.targets[1],
+        PushTarget::Statsd {
+            address: "127.0.0.1:8125".to_string(),
+            dogstatsd: true,
+        }
+    );
+    assert!(app.push.validate().is_ok());
+    let cloud = PushTarget::InfluxHttp {
+        url: "https://eu-central-1-1.aws.cloud2.influxdata.com/api/v2/write?org=ops&bucket=hosts"
+            .to_string(),
+        token: Some("secret".to_string()),
+    };
+    assert!(Pusher::new(config(vec![cloud]), "db1").is_ok());
+
+    for target in [
+        PushTarget::InfluxHttp {
+            url: "ftp://influx.example.com/write".to_string(),
+            token: None,
+        },
+        PushTarget::InfluxUdp {
+            address: "localhost".to_string(),
+        },
+    ] {
+        assert!(matches!(
+            Pusher::new(config(vec![target]), "db1"),
+            Err(DashboardError::ConfigError(_))
+        ));
+    }
+}
+
+#[cfg(not(feature = "otlp"))]
+#[test]
+fn test_otlp_needs_feature() {
+    let target = PushTarget::Otlp {
+        endpoint: "http://localhost:4318".to_string(),
+        headers: Default::default(),
+    };
+    match Pusher::new(config(vec![target]), "db1") {
+        Err(DashboardError::ConfigError(message)) => {
+            assert!(message.contains("`otlp` feature"), "{}", message)
+        }
+        _ => panic!("OTLP target accepted without the feature"),
+    }
+}
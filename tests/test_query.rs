@@ -0,0 +1,112 @@
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::query::{ProcessQuery, SortColumn, SortDirection};
+use rust_dashboard_lib::system::SystemMonitor;
+
+fn own_group(monitor: &SystemMonitor) -> String {
+    let pid = std::process::id();
+    monitor
+        .combined_process_list()
+        .iter()
+        .find(|p| p.pids.contains(&pid))
+        .map(|p| p.name.clone())
+        .expect("test process is listed")
+}
+
+#[test]
+fn test_default_query_sorts_by_cpu_descending() {
+    let monitor = SystemMonitor::new();
+    let page = ProcessQuery::default().run(&monitor).unwrap();
+    assert_eq!(page.total, page.groups);
+    assert_eq!(page.processes.len(), page.total);
+    assert!(page
+        .processes
+        .windows(2)
+        .all(|w| w[0].cpu_usage >= w[1].cpu_usage));
+}
+
+#[test]
+fn test_pages_partition_the_sorted_list() {
+    let monitor = SystemMonitor::new();
+    let query = ProcessQuery {
+        sort: SortColumn::Name,
+        direction: SortDirection::Asc,
+        ..Default::default()
+    };
+    let all = query.run(&monitor).unwrap().processes;
+    let mut paged = Vec::new();
+    for offset in (0..all.len()).step_by(7) {
+        let page = ProcessQuery {
+            offset,
+            limit: Some(7),
+            ..query.clone()
+        }
+        .run(&monitor)
+        .unwrap();
+        assert_eq!(page.total, all.len());
+        paged.extend(page.processes);
+    }
+    assert_eq!(paged, all);
+}
+
+#[test]
+fn test_name_user_and_cmdline_filters() {
+    let monitor = SystemMonitor::new();
+    let name = own_group(&monitor);
+    let pattern = format!("^{}$", regex::escape(&name));
+
+    let by_name = ProcessQuery {
+        name: Some(pattern.clone()),
+        ..Default::default()
+    }
+    .run(&monitor)
+    .unwrap();
+    assert_eq!(by_name.total, 1);
+    assert_eq!(by_name.processes[0].name, name);
+
+    if let Some(user) = monitor.process_user(std::process::id()) {
+        let by_user = ProcessQuery {
+            name: Some(pattern.clone()),
+            user: Some(user.to_string()),
+            ..Default::default()
+        }
+        .run(&monitor)
+        .unwrap();
+        assert_eq!(by_user.total, 1);
+    }
+    let wrong_user = ProcessQuery {
+        name: Some(pattern.clone()),
+        user: Some("no-such-user-here".to_string()),
+        ..Default::default()
+    }
+    .run(&monitor)
+    .unwrap();
+    assert_eq!(wrong_user.total, 0);
+
+    let by_cmdline = ProcessQuery {
+        cmdline: Some("test_query".to_string()),
+        ..Default::default()
+    }
+    .run(&monitor)
+    .unwrap();
+    assert!(by_cmdline.processes.iter().any(|p| p.name == name));
+}
+
+#[test]
+fn test_thresholds_and_invalid_pattern() {
+    let monitor = SystemMonitor::new();
+    let heavy = ProcessQuery {
+        min_memory: u64::MAX,
+        ..Default::default()
+    }
+    .run(&monitor)
+    .unwrap();
+    assert_eq!(heavy.total, 0);
+
+    let err = ProcessQuery {
+        name: Some("(".to_string()),
+        ..Default::default()
+    }
+    .run(&monitor)
+    .unwrap_err();
+    assert!(matches!(err, DashboardError::InvalidQuery(_)));
+}
@@ -0,0 +1,304 @@
+mod common;
+
+use flate2::read::GzDecoder;
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::recording::{
+    list_recordings, RecordedSnapshot, Recorder, RecordingConfig, RecordingFormat,
+};
+use rust_dashboard_lib::service::{MonitorService, ServiceEvent};
+use rust_dashboard_lib::snapshot::SystemSnapshot;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 2026-10-18 00:00:00 UTC.
+const START: f64 = 1_792_281_600.0;
+
+fn snapshot(cpu: f32) -> SystemSnapshot {
+    SystemSnapshot {
+        load_average: (0.5, 0.25, 0.125),
+        ..common::snapshot(cpu)
+    }
+}
+
+fn config(dir: &Path) -> RecordingConfig {
+    RecordingConfig {
+        directory: Some(dir.to_path_buf()),
+        ..Default::default()
+    }
+}
+
+fn read(path: &PathBuf) -> String {
+    let mut text = String::new();
+    if path.extension().is_some_and(|e| e == "gz") {
+        GzDecoder::new(std::fs::File::open(path).unwrap())
+            .read_to_string(&mut text)
+            .unwrap();
+    } else {
+        text = std::fs::read_to_string(path).unwrap();
+    }
+    text
+}
+
+fn names(paths: &[PathBuf]) -> Vec<String> {
+    paths
+        .iter()
+        .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+        .collect()
+}
+
+#[test]
+fn test_ndjson_records_every_nth() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut recorder = Recorder::new(
+        RecordingConfig {
+            every_nth: 2,
+            compress: false,
+            ..config(dir.path())
+        },
+        START,
+    )
+    .unwrap();
+    for i in 0..5 {
+        recorder
+            .record(&snapshot(i as f32), START + i as f64)
+            .unwrap();
+    }
+    let status = recorder.finish();
+    assert!(!status.active);
+    assert_eq!(status.snapshots_written, 3);
+    assert_eq!(status.files_written, 1);
+
+    let files = list_recordings(dir.path());
+    assert_eq!(names(&files), ["rdash-20261018-000000.ndjson"]);
+    let recorded: Vec<RecordedSnapshot> = read(&files[0])
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let cpu: Vec<f32> = recorded.iter().map(|r| r.snapshot.cpu_usage).collect();
+    assert_eq!(cpu, [0.0, 2.0, 4.0]);
+    assert_eq!(recorded[1].timestamp, START + 2.0);
+    assert_eq!(recorded[1].snapshot, snapshot(2.0));
+}
+
+#[test]
+fn test_size_rotation_compresses_rotated_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut recorder = Recorder::new(
+        RecordingConfig {
+            // Small enough that every snapshot starts a new file.
+            max_file_bytes: 1,
+            ..config(dir.path())
+        },
+        START,
+    )
+    .unwrap();
+    for i in 0..3 {
+        recorder.record(&snapshot(i as f32), START).unwrap();
+    }
+    assert_eq!(recorder.status().files_written, 3);
+    recorder.finish();
+
+    let files = list_recordings(dir.path());
+    assert_eq!(
+        names(&files),
+        [
+            "rdash-20261018-000000-1.ndjson.gz",
+            "rdash-20261018-000000-2.ndjson.gz",
+            "rdash-20261018-000000.ndjson.gz",
+        ]
+    );
+    let mut cpu: Vec<f32> = files
+        .iter()
+        .map(|path| {
+            let line: RecordedSnapshot = serde_json::from_str(read(path).trim()).unwrap();
+            line.snapshot.cpu_usage
+        })
+        .collect();
+    cpu.sort_by(f32::total_cmp);
+    assert_eq!(cpu, [0.0, 1.0, 2.0]);
+}
+
+#[test]
+fn test_finish_in_background_compresses_last_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut recorder = Recorder::new(config(dir.path()), START).unwrap();
+    recorder.record(&snapshot(3.0), START).unwrap();
+    let (status, pending) = recorder.finish_in_background();
+    assert!(!status.active);
+    assert_eq!(status.snapshots_written, 1);
+    pending.wait();
+
+    let files = list_recordings(dir.path());
+    assert_eq!(names(&files), ["rdash-20261018-000000.ndjson.gz"]);
+    let line: RecordedSnapshot = serde_json::from_str(read(&files[0]).trim()).unwrap();
+    assert_eq!(line.snapshot, snapshot(3.0));
+}
+
+#[test]
+fn test_age_rotation_prunes_old_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut recorder = Recorder::new(
+        RecordingConfig {
+            max_file_age_seconds: 60,
+            max_files: 2,
+            compress: false,
+            ..config(dir.path())
+        },
+        START,
+    )
+    .unwrap();
+    for minute in 0..4 {
+        let at = START + minute as f64 * 60.0;
+        recorder.record(&snapshot(1.0), at).unwrap();
+        recorder.record(&snapshot(2.0), at + 30.0).unwrap();
+    }
+    let status = recorder.finish();
+    assert_eq!(status.files_written, 4);
+    assert_eq!(status.snapshots_written, 8);
+
+    let files = list_recordings(dir.path());
+    assert_eq!(
+        names(&files),
+        [
+            "rdash-20261018-000200.ndjson",
+            "rdash-20261018-000300.ndjson"
+        ]
+    );
+    assert_eq!(read(&files[1]).lines().count(), 2);
+}
+
+#[test]
+fn test_csv_recording() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut recorder = Recorder::new(
+        RecordingConfig {
+            format: RecordingFormat::Csv,
+            compress: false,
+            ..config(dir.path())
+        },
+        START,
+    )
+    .unwrap();
+    recorder.record(&snapshot(12.5), START).unwrap();
+    recorder.record(&snapshot(25.0), START + 1.0).unwrap();
+    recorder.finish();
+
+    let files = list_recordings(dir.path());
+    assert_eq!(names(&files), ["rdash-20261018-000000.csv"]);
+    let text = read(&files[0]);
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    assert_eq!(&reader.headers().unwrap()[1], "CPU Usage %");
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(&rows[1][0], "1792281601.000");
+    assert_eq!(&rows[1][1], "25.00");
+    assert_eq!(&rows[1][2], (1u64 << 30).to_string());
+    assert_eq!(&rows[1][5], "0.50");
+}
+
+#[test]
+fn test_config_validation_and_expiry() {
+    let dir = tempfile::tempdir().unwrap();
+    for bad in [
+        RecordingConfig {
+            every_nth: 0,
+            ..config(dir.path())
+        },
+        RecordingConfig {
+            max_file_bytes: 0,
+            ..config(dir.path())
+        },
+    ] {
+        assert!(matches!(
+            Recorder::new(bad, START),
+            Err(DashboardError::ConfigError(_))
+        ));
+    }
+
+    let recorder = Recorder::new(
+        RecordingConfig {
+            stop_after_seconds: Some(3600),
+            ..config(dir.path())
+        },
+        START,
+    )
+    .unwrap();
+    assert!(!recorder.expired(START + 3599.0));
+    assert!(recorder.expired(START + 3600.0));
+
+    let config: RecordingConfig = toml::from_str(
+        r#"
+        enabled = true
+        format = "csv"
+        every_nth = 5
+        "#,
+    )
+    .unwrap();
+    assert!(config.enabled);
+    assert_eq!(config.format, RecordingFormat::Csv);
+    assert!(config.compress);
+    assert_eq!(config.stop_after_seconds, None);
+}
+
+#[test]
+fn test_service_stops_expired_recording() {
+    let dir = tempfile::tempdir().unwrap();
+    let service = MonitorService::builder()
+        .interval(Duration::from_millis(100))
+        .tick(Duration::from_millis(20))
+        .build();
+    let status = service
+        .start_recording(RecordingConfig {
+            stop_after_seconds: Some(0),
+            compress: false,
+            ..config(dir.path())
+        })
+        .unwrap();
+    assert!(status.active);
+    assert_eq!(service.recording_status(), Some(status));
+
+    let events = service.subscribe();
+    service.start();
+    let stopped = loop {
+        match events.recv_timeout(Duration::from_secs(10)).unwrap() {
+            ServiceEvent::RecordingStopped(status) => break status,
+            _ => continue,
+        }
+    };
+    service.stop();
+    assert!(!stopped.active);
+    assert_eq!(stopped.error, None);
+    assert_eq!(stopped.snapshots_written, 1);
+    assert_eq!(service.recording_status(), None);
+    assert_eq!(service.stop_recording(), None);
+    assert_eq!(list_recordings(dir.path()).len(), 1);
+}
+
+#[test]
+fn test_service_compresses_stopped_recording_by_stop() {
+    let dir = tempfile::tempdir().unwrap();
+    let service = MonitorService::builder()
+        .interval(Duration::from_millis(100))
+        .tick(Duration::from_millis(20))
+        .build();
+    service.start_recording(config(dir.path())).unwrap();
+    let events = service.subscribe();
+    service.start();
+    while !matches!(
+        events.recv_timeout(Duration::from_secs(10)).unwrap(),
+        ServiceEvent::Snapshot(_)
+    ) {}
+    let status = service.stop_recording().unwrap();
+    assert!(!status.active);
+    assert!(status.snapshots_written >= 1);
+    service.stop();
+
+    let files = list_recordings(dir.path());
+    assert_eq!(files.len(), 1);
+    assert!(
+        files[0].extension().is_some_and(|e| e == "gz"),
+        "{:?}",
+        files
+    );
+}
@@ -0,0 +1,298 @@
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::remediation::{
+    RemediationAction, RemediationConfig, RemediationEngine, RemediationEvent, RemediationOutcome,
+    RemediationPolicy,
+};
+use rust_dashboard_lib::system::SystemMonitor;
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+fn policy(condition: String, action: RemediationAction) -> RemediationPolicy {
+    RemediationPolicy {
+        name: "test".to_string(),
+        condition,
+        for_seconds: 0,
+        action,
+        nice: 10,
+    }
+}
+
+fn engine(dry_run: bool, policies: Vec<RemediationPolicy>) -> RemediationEngine {
+    RemediationEngine::new(RemediationConfig {
+        dry_run,
+        policies,
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+/// A child process and a monitor that has seen it.
+fn sleeper() -> (Child, SystemMonitor) {
+    let child = Command::new("sleep").arg("30").spawn().unwrap();
+    (child, SystemMonitor::new())
+}
+
+fn finish(mut child: Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_dry_run_only_reports_once() {
+    let (mut child, mut monitor) = sleeper();
+    let mut engine = engine(
+        true,
+        vec![policy(
+            format!("pid == {}", child.id()),
+            RemediationAction::Kill,
+        )],
+    );
+    let events = engine.evaluate(&mut monitor, Instant::now());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].pid, child.id());
+    assert_eq!(events[0].outcome, RemediationOutcome::DryRun);
+    assert!(
+        events[0].message.contains("dry run"),
+        "{}",
+        events[0].message
+    );
+    assert!(child.try_wait().unwrap().is_none());
+    assert!(engine.evaluate(&mut monitor, Instant::now()).is_empty());
+    assert_eq!(engine.log().count(), 1);
+    finish(child);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_terminate_and_renice() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let (mut child, mut monitor) = sleeper();
+    let condition = format!("pid == {}", child.id());
+    let mut renice = engine(
+        false,
+        vec![RemediationPolicy {
+            nice: 7,
+            ..policy(condition.clone(), RemediationAction::Renice)
+        }],
+    );
+    let events = renice.evaluate(&mut monitor, Instant::now());
+    assert_eq!(events[0].outcome, RemediationOutcome::Applied);
+    assert_eq!(events[0].nice, Some(7));
+    let ps = Command::new("ps")
+        .args(["-o", "ni=", "-p", &child.id().to_string()])
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&ps.stdout).trim(), "7");
+
+    let mut terminate = engine(false, vec![policy(condition, RemediationAction::Terminate)]);
+    let events = terminate.evaluate(&mut monitor, Instant::now());
+    assert_eq!(events[0].outcome, RemediationOutcome::Applied);
+    assert_eq!(child.wait().unwrap().signal(), Some(15));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exited_process_is_not_signalled() {
+    let (child, mut monitor) = sleeper();
+    let pid = child.id();
+    // The monitor's table still lists the child after it's reaped.
+    finish(child);
+    let mut engine = engine(
+        false,
+        vec![policy(format!("pid == {}", pid), RemediationAction::Kill)],
+    );
+    let events = engine.evaluate(&mut monitor, Instant::now());
+    assert_eq!(events.len(), 1);
+    assert_eq!(
+        events[0].outcome,
+        RemediationOutcome::Failed {
+            error: "process exited before the action ran".to_string()
+        }
+    );
+    assert_eq!(monitor.process_start_time(pid), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_safeguards() {
+    let (mut child, mut monitor) = sleeper();
+    let mut engine = RemediationEngine::new(RemediationConfig {
+        dry_run: false,
+        protected: vec!["SLEEP".to_string()],
+        policies: vec![
+            policy(format!("pid == {}", child.id()), RemediationAction::Kill),
+            policy(
+                format!("pid == {}", std::process::id()),
+                RemediationAction::Kill,
+            ),
+        ],
+        ..Default::default()
+    })
+    .unwrap();
+    let mut events = engine.evaluate(&mut monitor, Instant::now());
+    events.sort_by_key(|e| e.pid != child.id());
+    let reasons: Vec<&RemediationOutcome> = events.iter().map(|e| &e.outcome).collect();
+    assert_eq!(
+        reasons,
+        [
+            &RemediationOutcome::Protected {
+                reason: "`sleep` is protected".to_string()
+            },
+            &RemediationOutcome::Protected {
+                reason: "the dashboard itself".to_string()
+            },
+        ]
+    );
+    assert!(child.try_wait().unwrap().is_none());
+    finish(child);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hold_time_and_audit_log() {
+    let dir = tempfile::tempdir().unwrap();
+    let audit = dir.path().join("audit.jsonl");
+    let (child, mut monitor) = sleeper();
+    let mut engine = RemediationEngine::new(RemediationConfig {
+        audit_log: Some(audit.clone()),
+        policies: vec![RemediationPolicy {
+            for_seconds: 600,
+            ..policy(
+                format!("pid == {}", child.id()),
+                RemediationAction::Terminate,
+            )
+        }],
+        ..Default::default()
+    })
+    .unwrap();
+    let start = Instant::now();
+    assert!(engine.evaluate(&mut monitor, start).is_empty());
+    let later = start + Duration::from_secs(300);
+    assert!(engine.evaluate(&mut monitor, later).is_empty());
+    let events = engine.evaluate(&mut monitor, start + Duration::from_secs(600));
+    assert_eq!(events.len(), 1);
+
+    let trail = std::fs::read_to_string(&audit).unwrap();
+    let logged: Vec<RemediationEvent> = trail
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    // JSON may round the timestamp's last digit.
+    assert_eq!(logged.len(), 1);
+    assert!((logged[0].timestamp - events[0].timestamp).abs() < 1e-3);
+    assert_eq!(
+        RemediationEvent {
+            timestamp: events[0].timestamp,
+            ..logged[0].clone()
+        },
+        events[0]
+    );
+    assert!(trail.contains(r#""status":"dry_run""#), "{}", trail);
+    finish(child);
+}
+
+#[test]
+fn test_config_and_validation() {
+    let config: RemediationConfig = toml::from_str(
+        r#"
+        dry_run = false
+
+        [[policies]]
+        name = "busy node"
+        condition = "name == node && cpu > 95"
+        for_seconds = 600
+        action = "renice"
+
+        [[policies]]
+        name = "huge node"
+        condition = "name == node && mem > 8G"
+        action = "terminate"
+        "#,
+    )
+    .unwrap();
+    assert!(!config.dry_run);
+    assert!(config.only_own_processes);
+    assert_eq!(config.policies[0].nice, 10);
+    assert_eq!(config.policies[1].action, RemediationAction::Terminate);
+    assert!(RemediationEngine::new(config).is_ok());
+    assert!(RemediationConfig::default().dry_run);
+
+    for (condition, nice) in [
+        ("full_in < 1d", 10),
+        ("anomaly(cpu_usage)", 10),
+        ("cpu >", 10),
+        ("cpu > 90", 25),
+    ] {
+        let config = RemediationConfig {
+            policies: vec![RemediationPolicy {
+                nice,
+                ..policy(condition.to_string(), RemediationAction::Renice)
+            }],
+            ..Default::default()
+        };
+        match RemediationEngine::new(config) {
+            Err(DashboardError::ConfigError(message)) => {
+                assert!(
+                    message.starts_with("remediation policy `test`"),
+                    "{}",
+                    message
+                )
+            }
+            _ => panic!("`{}` accepted", condition),
+        }
+    }
+}
+
+#[test]
+fn test_restricted_to_configured_safeguards() {
+    let configured = RemediationConfig {
+        protected: vec!["postgres".to_string()],
+        audit_log: Some("/home/me/.config/rust-dashboard/audit.jsonl".into()),
+        ..Default::default()
+    };
+    let received = RemediationConfig {
+        protected: vec!["nginx".to_string()],
+        audit_log: Some("/etc/cron.d/dashboard".into()),
+        policies: vec![policy("cpu > 95".to_string(), RemediationAction::Kill)],
+        ..Default::default()
+    };
+    let restricted = received.restricted_to(&configured).unwrap();
+    assert!(restricted.dry_run);
+    assert_eq!(restricted.audit_log, configured.audit_log);
+    assert_eq!(restricted.protected, ["nginx", "postgres"]);
+    assert_eq!(restricted.policies.len(), 1);
+
+    for (armed, setting) in [
+        (
+            RemediationConfig {
+                dry_run: false,
+                ..Default::default()
+            },
+            "dry_run",
+        ),
+        (
+            RemediationConfig {
+                only_own_processes: false,
+                ..Default::default()
+            },
+            "only_own_processes",
+        ),
+    ] {
+        match armed.restricted_to(&configured) {
+            Err(DashboardError::ConfigError(message)) => {
+                assert!(message.contains(setting), "{}", message)
+            }
+            other => panic!("{} turned off: {:?}", setting, other),
+        }
+    }
+
+    // Settings config.toml already turned off may stay off.
+    let armed = RemediationConfig {
+        dry_run: false,
+        only_own_processes: false,
+        ..Default::default()
+    };
+    assert!(armed.clone().restricted_to(&armed).is_ok());
+}
@@ -0,0 +1,300 @@
+use rust_dashboard_lib::config::MIN_SUBSYSTEM_INTERVAL_MS;
+use rust_dashboard_lib::service::{MonitorService, ServiceEvent};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+const WAIT: Duration = Duration::from_secs(5);
+
+fn fast_service() -> MonitorService {
+    MonitorService::builder()
+        .interval(Duration::from_millis(250))
+        .tick(Duration::from_millis(25))
+        .build()
+}
+
+fn next_snapshot(rx: &Receiver<ServiceEvent>) -> bool {
+    let deadline = std::time::Instant::now() + WAIT;
+    while let Some(left) = deadline.checked_duration_since(std::time::Instant::now()) {
+        match rx.recv_timeout(left) {
+            Ok(ServiceEvent::Snapshot(_)) => return true,
+            Ok(_) => continue,
+            Err(_) => return false,
+        }
+    }
+    false
+}
+
+#[test]
+fn test_subscriber_receives_snapshots() {
+    let service = fast_service();
+    let rx = service.subscribe();
+    service.start();
+    assert!(service.is_running());
+    assert!(next_snapshot(&rx));
+    assert!(next_snapshot(&rx));
+    assert!(service.latest().is_some());
+}
+
+#[test]
+fn test_pause_stops_publishing_until_resumed() {
+    let service = fast_service();
+    let rx = service.subscribe();
+    service.start();
+    assert!(next_snapshot(&rx));
+
+    service.pause();
+    assert!(service.is_paused());
+    // Let an in-flight cycle finish, then drain.
+    std::thread::sleep(Duration::from_millis(100));
+    while rx.try_recv().is_ok() {}
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(600)).err(),
+        Some(RecvTimeoutError::Timeout)
+    );
+
+    service.resume();
+    assert!(next_snapshot(&rx));
+}
+
+#[test]
+fn test_request_refresh_works_while_paused() {
+    let service = MonitorService::builder()
+        .tick(Duration::from_millis(25))
+        .paused(true)
+        .build();
+    let rx = service.subscribe();
+    service.start();
+    service.request_refresh();
+    assert!(next_snapshot(&rx));
+}
+
+#[test]
+fn test_late_subscriber_gets_latest_snapshot_immediately() {
+    let service = fast_service();
+    let first = service.subscribe();
+    service.start();
+    assert!(next_snapshot(&first));
+
+    service.pause();
+    let late = service.subscribe();
+    assert!(matches!(late.try_recv(), Ok(ServiceEvent::Snapshot(_))));
+}
+
+#[test]
+fn test_stalled_subscriber_still_gets_alerts() {
+    use rust_dashboard_lib::alerts::AlertRule;
+    use rust_dashboard_lib::service::SUBSCRIBER_BUFFER;
+
+    let service = MonitorService::builder()
+        .tick(Duration::from_millis(25))
+        .paused(true)
+        .build();
+    service
+        .set_alert_rules(&[AlertRule {
+            name: "anything".to_string(),
+            condition: "cpu >= 0".to_string(),
+            for_seconds: 1,
+            actions: vec![],
+        }])
+        .unwrap();
+    let rx = service.subscribe();
+    let reader = service.snapshots();
+    service.start();
+    // Fill the channel with snapshots before the rule fires.
+    while reader.generation() < SUBSCRIBER_BUFFER as u64 + 1 {
+        service.request_refresh();
+        std::thread::sleep(Duration::from_millis(30));
+    }
+    std::thread::sleep(Duration::from_millis(1100));
+    service.request_refresh();
+    std::thread::sleep(Duration::from_millis(100));
+
+    let deadline = std::time::Instant::now() + WAIT;
+    let mut alert = false;
+    while !alert && std::time::Instant::now() < deadline {
+        service.request_refresh();
+        match rx.recv_timeout(Duration::from_millis(50)) {
+            Ok(ServiceEvent::Alert(event)) => alert = event.rule == "anything",
+            Ok(_) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    assert!(alert, "alert was dropped for a stalled subscriber");
+}
+
+#[test]
+fn test_stalled_subscriber_holds_a_bounded_backlog() {
+    use rust_dashboard_lib::alerts::AlertRule;
+    use rust_dashboard_lib::service::SUBSCRIBER_BUFFER;
+
+    let rules: Vec<AlertRule> = (0..SUBSCRIBER_BUFFER * 4)
+        .map(|i| AlertRule {
+            name: format!("rule {}", i),
+            condition: "cpu >= 0".to_string(),
+            for_seconds: 0,
+            actions: vec![],
+        })
+        .collect();
+    let service = MonitorService::builder()
+        .tick(Duration::from_millis(25))
+        .paused(true)
+        .build();
+    service.set_alert_rules(&rules).unwrap();
+    let rx = service.subscribe();
+    service.start();
+    // Every rule fires on the first refresh, while nobody reads.
+    service.request_refresh();
+    std::thread::sleep(Duration::from_millis(500));
+
+    // Each later refresh moves held events into the drained channel.
+    let mut alerts = Vec::new();
+    for _ in 0..5 {
+        alerts.extend(rx.try_iter().filter_map(|event| match event {
+            ServiceEvent::Alert(event) => Some(event.rule),
+            _ => None,
+        }));
+        service.request_refresh();
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    assert!(
+        alerts.len() <= 2 * SUBSCRIBER_BUFFER,
+        "{} alerts were held",
+        alerts.len()
+    );
+    // The oldest are the ones dropped.
+    assert_eq!(alerts.last(), rules.last().map(|rule| &rule.name));
+}
+
+#[test]
+fn test_stop_closes_subscriptions() {
+    let service = fast_service();
+    let rx = service.subscribe();
+    service.start();
+    assert!(next_snapshot(&rx));
+    service.stop();
+    assert!(!service.is_running());
+    while rx.try_recv().is_ok() {}
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(200)).err(),
+        Some(RecvTimeoutError::Disconnected)
+    );
+}
+
+#[test]
+fn test_set_interval_is_clamped() {
+    let service = fast_service();
+    service.set_interval(Duration::from_millis(10));
+    assert_eq!(
+        service.interval(),
+        Duration::from_millis(MIN_SUBSYSTEM_INTERVAL_MS)
+    );
+    service.set_interval(Duration::from_secs(5));
+    assert_eq!(service.interval(), Duration::from_secs(5));
+}
+
+#[test]
+fn test_empty_visibility_drops_to_minimal_mode() {
+    use rust_dashboard_lib::adaptive::OverheadMode;
+    use std::collections::HashSet;
+
+    let service = fast_service();
+    service.set_visibility_source(HashSet::new);
+    let rx = service.subscribe();
+    service.start();
+    assert!(next_snapshot(&rx));
+    let monitor = service.monitor();
+    let mode = monitor.lock().unwrap().adaptive.mode;
+    assert_eq!(mode, OverheadMode::Minimal);
+}
+
+#[test]
+fn test_no_remediation_without_a_process_refresh() {
+    use rust_dashboard_lib::remediation::{
+        RemediationAction, RemediationConfig, RemediationPolicy,
+    };
+    use std::collections::HashSet;
+
+    let service = fast_service();
+    service
+        .set_remediation_config(RemediationConfig {
+            policies: vec![RemediationPolicy {
+                name: "everything".to_string(),
+                condition: "cpu >= 0".to_string(),
+                for_seconds: 0,
+                action: RemediationAction::Kill,
+                nice: 10,
+            }],
+            ..Default::default()
+        })
+        .unwrap();
+    // Minimal mode: processes aren't refreshed.
+    service.set_visibility_source(HashSet::new);
+    let rx = service.subscribe();
+    service.start();
+    let mut snapshots = 0;
+    while snapshots < 3 {
+        match rx.recv_timeout(WAIT).unwrap() {
+            ServiceEvent::Snapshot(_) => snapshots += 1,
+            ServiceEvent::Remediation(event) => panic!("{:?}", event),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn test_stream_subscription() {
+    use futures_util::StreamExt;
+
+    let service = fast_service();
+    let mut stream = service.subscribe_stream();
+    service.start();
+    let event = futures_executor::block_on(stream.next());
+    assert!(matches!(event, Some(ServiceEvent::Snapshot(_))));
+}
+
+#[test]
+fn test_reader_sees_published_generations() {
+    let service = fast_service();
+    let reader = service.snapshots();
+    let rx = service.subscribe();
+    service.start();
+    assert!(next_snapshot(&rx));
+
+    let generation = reader.generation();
+    assert!(generation >= 1);
+    let latest = reader.latest().expect("published snapshot");
+    assert!(next_snapshot(&rx));
+    assert!(reader.generation() > generation);
+    // The earlier snapshot is untouched; readers hold whole generations.
+    assert!(!std::sync::Arc::ptr_eq(&latest, &reader.latest().unwrap()));
+}
+
+#[test]
+fn test_refresh_requests_coalesce() {
+    let service = MonitorService::builder()
+        .tick(Duration::from_millis(25))
+        .paused(true)
+        .build();
+    let reader = service.snapshots();
+    service.start();
+    for _ in 0..10 {
+        service.request_refresh();
+    }
+    std::thread::sleep(Duration::from_millis(300));
+    let published = reader.generation();
+    assert!(published >= 1);
+    assert!(published < 10, "{} refreshes for 10 requests", published);
+}
+
+#[test]
+fn test_disk_rescan_publishes_while_paused() {
+    let service = MonitorService::builder()
+        .tick(Duration::from_millis(25))
+        .paused(true)
+        .build();
+    let rx = service.subscribe();
+    service.start();
+    service.request_disk_rescan();
+    assert!(next_snapshot(&rx));
+}
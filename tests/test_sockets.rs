@@ -0,0 +1,196 @@
+use rust_dashboard_lib::error::DashboardError;
+use rust_dashboard_lib::sockets::{
+    parse_inet_table, parse_unix_table, read_socket_table, SocketEntry, SocketFilter,
+    SocketProtocol, SocketState,
+};
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc")
+}
+
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(fixture_root().join("net").join(name)).expect("read fixture")
+}
+
+fn find(entries: &[SocketEntry], inode: u64) -> &SocketEntry {
+    entries
+        .iter()
+        .find(|e| e.inode == inode)
+        .unwrap_or_else(|| panic!("no socket with inode {}", inode))
+}
+
+#[test]
+fn test_parse_tcp_listen_and_established() {
+    let entries = parse_inet_table(&fixture("tcp"), SocketProtocol::Tcp);
+    assert_eq!(entries.len(), 4);
+
+    let listen = find(&entries, 41001);
+    assert_eq!(listen.local_address, "0.0.0.0");
+    assert_eq!(listen.local_port, Some(8080));
+    assert_eq!(listen.state, SocketState::Listen);
+    assert_eq!(listen.remote_address, None);
+    assert_eq!(listen.uid, Some(1000));
+    assert!(listen.is_listening());
+
+    let pg = find(&entries, 41002);
+    assert_eq!(pg.local_address, "127.0.0.1");
+    assert_eq!(pg.local_port, Some(5432));
+
+    let est = find(&entries, 41003);
+    assert_eq!(est.local_address, "10.0.2.15");
+    assert_eq!(est.remote_address.as_deref(), Some("192.168.0.42"));
+    assert_eq!(est.remote_port, Some(443));
+    assert_eq!(est.state, SocketState::Established);
+    assert_eq!(est.tx_queue, 0x20);
+    assert!(!est.is_listening());
+
+    let tw = find(&entries, 0);
+    assert_eq!(tw.state, SocketState::TimeWait);
+}
+
+#[test]
+fn test_parse_tcp6_addresses() {
+    let entries = parse_inet_table(&fixture("tcp6"), SocketProtocol::Tcp6);
+    assert_eq!(entries.len(), 2);
+
+    let any = find(&entries, 41004);
+    assert_eq!(any.local_address, "::");
+    assert_eq!(any.local_port, Some(80));
+    assert_eq!(any.state, SocketState::Listen);
+
+    let loopback = find(&entries, 41005);
+    assert_eq!(loopback.local_address, "::1");
+    assert_eq!(loopback.local_port, Some(8081));
+    assert_eq!(loopback.remote_address.as_deref(), Some("::1"));
+    assert_eq!(loopback.remote_port, Some(54321));
+}
+
+#[test]
+fn test_parse_udp_unconnected_is_listening() {
+    let entries = parse_inet_table(&fixture("udp"), SocketProtocol::Udp);
+    assert_eq!(entries.len(), 1);
+    let dns = &entries[0];
+    assert_eq!(dns.local_address, "127.0.0.53");
+    assert_eq!(dns.local_port, Some(53));
+    assert_eq!(dns.state, SocketState::Unconnected);
+    assert!(dns.is_listening());
+}
+
+#[test]
+fn test_parse_empty_table() {
+    assert!(parse_inet_table(&fixture("udp6"), SocketProtocol::Udp6).is_empty());
+    assert!(parse_inet_table("", SocketProtocol::Tcp).is_empty());
+}
+
+#[test]
+fn test_parse_unix_table() {
+    let entries = parse_unix_table(&fixture("unix"));
+    assert_eq!(entries.len(), 3);
+
+    let listening = find(&entries, 41007);
+    assert_eq!(listening.local_address, "/run/app.sock");
+    assert_eq!(listening.state, SocketState::Listen);
+
+    let unnamed = find(&entries, 41008);
+    assert_eq!(unnamed.local_address, "");
+    assert_eq!(unnamed.state, SocketState::Connected);
+
+    let abstract_sock = find(&entries, 41009);
+    assert!(abstract_sock.local_address.starts_with('@'));
+}
+
+#[test]
+fn test_parse_skips_malformed_lines() {
+    let contents = "header\n   0: garbage\n   1: 0100007F:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000   113        0 41002 1\n";
+    let entries = parse_inet_table(contents, SocketProtocol::Tcp);
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].inode, 41002);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_read_socket_table_maps_owners() {
+    let entries = read_socket_table(&fixture_root()).expect("read fixture tables");
+    assert_eq!(entries.len(), 10);
+
+    // 41001 is shared by 4242 and 5151 (forked server); lowest PID wins.
+    let web = find(&entries, 41001);
+    assert_eq!(web.pid, Some(4242));
+    assert_eq!(web.process_name.as_deref(), Some("webserver"));
+
+    let pg = find(&entries, 41002);
+    assert_eq!(pg.pid, Some(5151));
+    assert_eq!(pg.process_name.as_deref(), Some("postgres"));
+
+    let unix = find(&entries, 41007);
+    assert_eq!(unix.pid, Some(5151));
+
+    let orphan = find(&entries, 41006);
+    assert_eq!(orphan.pid, None);
+}
+
+#[test]
+fn test_read_socket_table_missing_root_errors() {
+    let dir = tempfile::tempdir().expect("create tempdir");
+    assert!(matches!(
+        read_socket_table(dir.path()),
+        Err(DashboardError::SocketTableUnavailable(_))
+    ));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_socket_filter() {
+    let entries = read_socket_table(&fixture_root()).expect("read fixture tables");
+
+    let port_8080 = SocketFilter {
+        port: Some(8080),
+        ..Default::default()
+    }
+    .apply(entries.clone());
+    assert_eq!(port_8080.len(), 1);
+    assert_eq!(port_8080[0].process_name.as_deref(), Some("webserver"));
+
+    let listening = SocketFilter {
+        listening_only: true,
+        ..Default::default()
+    }
+    .apply(entries.clone());
+    let inodes: Vec<u64> = listening.iter().map(|e| e.inode).collect();
+    for inode in [41001, 41002, 41004, 41006, 41007, 41009] {
+        assert!(inodes.contains(&inode), "{} should be listening", inode);
+    }
+    assert!(!inodes.contains(&41003));
+    assert!(!inodes.contains(&41008));
+
+    let postgres_tcp = SocketFilter {
+        protocol: Some(SocketProtocol::Tcp),
+        process: Some("POST".to_string()),
+        ..Default::default()
+    }
+    .apply(entries.clone());
+    assert_eq!(postgres_tcp.len(), 1);
+    assert_eq!(postgres_tcp[0].inode, 41002);
+
+    let by_pid = SocketFilter {
+        pid: Some(4242),
+        state: Some(SocketState::Established),
+        ..Default::default()
+    }
+    .apply(entries);
+    assert_eq!(by_pid.len(), 1);
+    assert_eq!(by_pid[0].inode, 41003);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_read_live_socket_table() {
+    // Smoke test against the real procfs: must not fail or panic.
+    let entries = read_socket_table(Path::new("/proc")).expect("read /proc/net");
+    for entry in entries {
+        if entry.protocol != SocketProtocol::Unix {
+            assert!(entry.local_port.is_some());
+        }
+    }
+}
@@ -188,6 +188,7 @@ fn test_disk_info_format() {
 }
 
 #[test]
+#[allow(unused_comparisons, clippy::absurd_extreme_comparisons)]
 fn test_network_info_format() {
     let mon = SystemMonitor::new();
     let networks = mon.network_info();
@@ -219,6 +220,7 @@ fn test_network_rates_are_finite_and_non_negative() {
 }
 
 #[test]
+#[allow(clippy::manual_range_contains)]
 fn test_cpu_usage_consistency() {
     let mut mon = SystemMonitor::new();
     let cpu1 = mon.global_cpu_usage();
@@ -255,3 +257,129 @@ fn test_memory_consistency_after_refresh() {
         "Used + free should <= total"
     );
 }
+
+#[test]
+fn test_refresh_due_respects_subsystem_cadence() {
+    use rust_dashboard_lib::config::{RefreshSchedule, Subsystem, SubsystemCadence};
+
+    let mut mon = SystemMonitor::new();
+    mon.set_global_interval(Duration::from_secs(60));
+    mon.set_schedule(RefreshSchedule {
+        cpu: SubsystemCadence::every_ms(250),
+        ..RefreshSchedule::default()
+    });
+
+    // Nothing is due right after construction.
+    assert!(mon.refresh_due().is_empty());
+
+    std::thread::sleep(Duration::from_millis(300));
+    let refreshed = mon.refresh_due();
+    assert_eq!(refreshed, vec![Subsystem::Cpu]);
+}
+
+#[test]
+fn test_refresh_due_skips_invisible_subsystems() {
+    use rust_dashboard_lib::config::{RefreshSchedule, Subsystem, SubsystemCadence};
+
+    let mut mon = SystemMonitor::new();
+    let visible_only = SubsystemCadence {
+        interval_ms: Some(250),
+        only_when_visible: true,
+    };
+    mon.set_schedule(RefreshSchedule {
+        cpu: visible_only,
+        memory: visible_only,
+        ..RefreshSchedule::default()
+    });
+    mon.set_visible([Subsystem::Memory]);
+
+    std::thread::sleep(Duration::from_millis(300));
+    let refreshed = mon.refresh_due();
+    assert!(refreshed.contains(&Subsystem::Memory));
+    assert!(!refreshed.contains(&Subsystem::Cpu));
+
+    // A forced refresh still honours visibility.
+    let before = mon.last_cpu_refresh;
+    mon.refresh();
+    assert_eq!(mon.last_cpu_refresh, before);
+}
+
+#[test]
+fn test_refresh_disks_now_bypasses_cadence() {
+    let mut mon = SystemMonitor::new();
+    let before = mon.last_disk_refresh;
+    std::thread::sleep(Duration::from_millis(10));
+    mon.refresh_disks_now();
+    assert!(mon.last_disk_refresh > before);
+}
+
+#[test]
+fn test_next_refresh_in_bounded_by_shortest_cadence() {
+    let mut mon = SystemMonitor::new();
+    mon.set_global_interval(Duration::from_secs(2));
+    let next = mon.next_refresh_in();
+    assert!(next <= Duration::from_secs(2));
+}
+
+#[test]
+fn test_minimal_mode_refreshes_only_cpu_and_memory() {
+    use rust_dashboard_lib::adaptive::{AdaptiveDecision, OverheadMode};
+    use rust_dashboard_lib::config::{RefreshSchedule, Subsystem, SubsystemCadence};
+
+    let mut mon = SystemMonitor::new();
+    mon.set_schedule(RefreshSchedule {
+        cpu: SubsystemCadence::every_ms(250),
+        memory: SubsystemCadence::every_ms(250),
+        processes: SubsystemCadence::every_ms(250),
+        disks: SubsystemCadence::every_ms(250),
+        networks: SubsystemCadence::every_ms(250),
+    });
+    mon.set_adaptive(AdaptiveDecision {
+        mode: OverheadMode::Minimal,
+        ..AdaptiveDecision::default()
+    });
+
+    std::thread::sleep(Duration::from_millis(300));
+    let mut refreshed = mon.refresh_due();
+    refreshed.sort_by_key(|s| format!("{:?}", s));
+    assert_eq!(refreshed, vec![Subsystem::Cpu, Subsystem::Memory]);
+}
+
+#[test]
+fn test_adaptive_multiplier_stretches_intervals() {
+    use rust_dashboard_lib::adaptive::AdaptiveDecision;
+    use rust_dashboard_lib::config::{RefreshSchedule, SubsystemCadence};
+
+    let mut mon = SystemMonitor::new();
+    mon.set_schedule(RefreshSchedule {
+        cpu: SubsystemCadence::every_ms(250),
+        ..RefreshSchedule::default()
+    });
+    mon.set_global_interval(Duration::from_secs(60));
+    mon.set_adaptive(AdaptiveDecision {
+        interval_multiplier: 4,
+        ..AdaptiveDecision::default()
+    });
+
+    // 250 ms cadence x4: not due yet after 300 ms.
+    std::thread::sleep(Duration::from_millis(300));
+    assert!(mon.refresh_due().is_empty());
+    assert!(mon.next_refresh_in() <= Duration::from_millis(1000));
+}
+
+#[test]
+fn test_refresh_records_own_cost() {
+    use rust_dashboard_lib::config::Subsystem;
+
+    let mut mon = SystemMonitor::new();
+    assert_eq!(mon.refresh_cost().cycles, 0);
+    mon.refresh();
+    mon.refresh();
+
+    let cost = mon.refresh_cost();
+    assert_eq!(cost.cycles, 2);
+    assert!(cost.avg_cycle_ms >= 0.0);
+    assert!(cost.subsystem_ms.contains_key(&Subsystem::Cpu));
+    assert!(cost.subsystem_ms.contains_key(&Subsystem::Processes));
+    assert!((0.0..=100.0).contains(&cost.busy_percent));
+}
@@ -0,0 +1,87 @@
+use rust_dashboard_lib::system::SystemMonitor;
+use rust_dashboard_lib::watch::{WatchList, WatchTarget, WATCH_HISTORY_CAPACITY};
+use std::process::{Child, Command};
+
+fn own_group(monitor: &SystemMonitor) -> String {
+    let pid = std::process::id();
+    monitor
+        .combined_process_list()
+        .iter()
+        .find(|p| p.pids.contains(&pid))
+        .map(|p| p.name.clone())
+        .expect("test process is listed")
+}
+
+fn spawn_sleep() -> Child {
+    Command::new("sleep")
+        .arg("30")
+        .spawn()
+        .expect("spawn sleep")
+}
+
+#[test]
+fn test_group_watch_records_each_process_refresh() {
+    let mut monitor = SystemMonitor::new();
+    let target = WatchTarget::Group {
+        name: own_group(&monitor),
+    };
+    let id = monitor.watches.add(target.clone());
+    assert_eq!(monitor.watches.add(target), id);
+
+    monitor.refresh();
+    monitor.refresh();
+    let watch = monitor.watches.get(id).unwrap();
+    assert_eq!(watch.history.len(), 2);
+    assert!(watch.pids.contains(&std::process::id()));
+    let last = watch.history.back().unwrap();
+    assert!(last.memory > 0);
+    assert!(last.pids >= 1);
+    assert!(watch.history[0].timestamp <= last.timestamp);
+
+    assert!(monitor.watches.remove(id));
+    assert!(!monitor.watches.remove(id));
+    assert!(monitor.watches.is_empty());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_process_watch_follows_restart_under_same_name() {
+    let mut first = spawn_sleep();
+    let mut monitor = SystemMonitor::new();
+    let id = monitor.watches.add(WatchTarget::Process {
+        name: "sleep".to_string(),
+        pid: first.id(),
+    });
+    monitor.refresh();
+    assert_eq!(monitor.watches.get(id).unwrap().pids, vec![first.id()]);
+
+    first.kill().unwrap();
+    first.wait().unwrap();
+    let mut second = spawn_sleep();
+    monitor.refresh();
+
+    let watch = monitor.watches.get(id).unwrap();
+    second.kill().ok();
+    second.wait().ok();
+    // Other `sleep` processes may exist on the host; the newest one
+    // with the same command line is ours.
+    assert_eq!(watch.pids, vec![second.id()]);
+    assert_eq!(watch.restarts, 1);
+    assert_eq!(watch.history.len(), 2);
+}
+
+#[test]
+fn test_history_is_capped() {
+    let monitor = SystemMonitor::new();
+    let mut watches = WatchList::default();
+    let id = watches.add(WatchTarget::Group {
+        name: own_group(&monitor),
+    });
+    watches.bind(&monitor);
+    for i in 0..WATCH_HISTORY_CAPACITY + 10 {
+        watches.record(&monitor, i as f64);
+    }
+    let history = &watches.get(id).unwrap().history;
+    assert_eq!(history.len(), WATCH_HISTORY_CAPACITY);
+    assert_eq!(history[0].timestamp, 10.0);
+}
@@ -0,0 +1,39 @@
+import type { CombinedProcess, SnapshotDelta, SystemSnapshot } from '$lib/types';
+
+// Apply a delta to the snapshot it was encoded against. Returns a new
+// snapshot (and a new processes array) so Svelte subscribers and any
+// `===`-based change detection see the update; unchanged rows keep
+// their object identity.
+export function applyDelta(snapshot: SystemSnapshot, delta: SnapshotDelta): SystemSnapshot {
+	const { metrics, processes } = delta;
+
+	let nextProcesses = snapshot.processes;
+	if (processes.added.length || processes.changed.length || processes.removed.length) {
+		const byName = new Map<string, CombinedProcess>(snapshot.processes.map((p) => [p.name, p]));
+		for (const name of processes.removed) {
+			byName.delete(name);
+		}
+		for (const p of processes.changed) {
+			byName.set(p.name, p);
+		}
+		for (const p of processes.added) {
+			byName.set(p.name, p);
+		}
+		nextProcesses = Array.from(byName.values());
+	}
+
+	return {
+		...snapshot,
+		cpu_usage: metrics.cpu_usage,
+		self_usage: metrics.self_usage,
+		uptime_seconds: metrics.uptime_seconds,
+		load_average: metrics.load_average,
+		overhead: metrics.overhead,
+		anomalies: metrics.anomalies ?? [],
+		per_cpu: metrics.per_cpu ?? snapshot.per_cpu,
+		memory: metrics.memory ?? snapshot.memory,
+		disks: metrics.disks ?? snapshot.disks,
+		networks: metrics.networks ?? snapshot.networks,
+		processes: nextProcesses
+	};
+}
@@ -1,6 +1,6 @@
 import { writable, get } from 'svelte/store';
 import { invoke } from '@tauri-apps/api/core';
-import type { ActiveView, AppConfig } from '$lib/types';
+import type { ActiveView, AppConfig, SnapshotInterest, Subsystem } from '$lib/types';
 import { logError } from '$lib/log';
 
 export const refreshInterval = writable<number>(2);
@@ -8,12 +8,46 @@ export const paused = writable<boolean>(false);
 export const theme = writable<'Dark' | 'Light'>('Light');
 export const activeView = writable<ActiveView>('overview');
 export const sidebarCollapsed = writable<boolean>(false);
+// Config as loaded from config.toml. Sections the UI doesn't edit
+// (refresh cadence, adaptive mode) are saved back from here instead of
+// being reset to defaults.
+const loadedConfig = writable<AppConfig | null>(null);
+
+const ALL_SUBSYSTEMS: Subsystem[] = ['cpu', 'memory', 'processes', 'disks', 'networks'];
+
+// What each view renders. The backend trims this window's snapshots
+// to match and, for `only_when_visible` cadences, skips subsystems no
+// visible window needs.
+const VIEW_INTEREST: Record<ActiveView | 'tray', SnapshotInterest> = {
+	// The process table pages through `query_processes` instead of
+	// receiving the list; the subsystem stays for refresh scheduling.
+	overview: { subsystems: ALL_SUBSYSTEMS, top_processes: 0 },
+	// The popup lists the five busiest processes.
+	tray: { subsystems: ALL_SUBSYSTEMS, top_processes: 5 },
+	cpu: { subsystems: ['cpu'], top_processes: null },
+	memory: { subsystems: ['memory'], top_processes: null },
+	disks: { subsystems: ['disks'], top_processes: null },
+	network: { subsystems: ['networks'], top_processes: null },
+	connections: { subsystems: ['networks'], top_processes: null },
+	processes: { subsystems: ['processes', 'networks'], top_processes: 0 },
+	// Diffs are computed in the backend from the full snapshot.
+	compare: { subsystems: ALL_SUBSYSTEMS, top_processes: 0 }
+};
+
+export async function registerSubscription(view: ActiveView | 'tray') {
+	try {
+		await invoke('set_subscription', { interest: VIEW_INTEREST[view] });
+	} catch (e) {
+		logError('Failed to register subscription', e);
+	}
+}
 
 export async function loadConfig() {
 	try {
 		const config = await invoke<AppConfig>('load_config');
 		refreshInterval.set(config.refresh_interval_seconds);
 		theme.set(config.theme === 'Light' ? 'Light' : 'Dark');
+		loadedConfig.set(config);
 	} catch (e) {
 		logError('Failed to load config', e);
 	}
@@ -38,7 +72,10 @@ export async function togglePause(value: boolean) {
 }
 
 export async function saveCurrentConfig() {
-	const config: AppConfig = {
+	// Before the config has loaded, the omitted sections fall back to
+	// their backend defaults.
+	const config: Partial<AppConfig> = {
+		...get(loadedConfig),
 		refresh_interval_seconds: get(refreshInterval),
 		theme: get(theme),
 		window_width: null,
@@ -1,24 +1,166 @@
-import { writable } from 'svelte/store';
+import { writable, get } from 'svelte/store';
 import { listen } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/core';
-import type { SystemSnapshot } from '$lib/types';
+import type { AlertEvent, LinkEvent, RecordingStatus, RemediationEvent, ReplayStatus, SnapshotDelta, SnapshotMessage, SystemSnapshot } from '$lib/types';
 import { logError } from '$lib/log';
+import { applyDelta } from '$lib/delta';
 
 export const systemSnapshot = writable<SystemSnapshot | null>(null);
 export const cpuHistory = writable<[number, number][]>([]);
 export const memoryHistory = writable<[number, number][]>([]);
+// x values of history points the backend flagged as anomalous, for
+// HistoryChart annotations.
+export const cpuAnomalies = writable<number[]>([]);
+export const memoryAnomalies = writable<number[]>([]);
 export const systemError = writable<string | null>(null);
+// Most recent link up/down transitions, newest first, with the time the
+// event arrived (ms since epoch).
+export const linkEvents = writable<(LinkEvent & { at: number })[]>([]);
+
+// Alert rules currently firing, keyed by rule name. Resolved events
+// remove their rule.
+export const firingAlerts = writable<Record<string, AlertEvent & { at: number }>>({});
+// Most recent remediation decisions (including dry runs), newest first.
+export const remediationEvents = writable<RemediationEvent[]>([]);
+// The recording in progress, started from the tray or a command; null
+// when not recording.
+export const recording = writable<RecordingStatus | null>(null);
+// The recording being replayed in place of live data; null when live.
+export const replay = writable<ReplayStatus | null>(null);
+
+const MAX_LINK_EVENTS = 20;
+const MAX_REMEDIATION_EVENTS = 20;
 
 let unlisten: (() => void) | null = null;
 let unlistenError: (() => void) | null = null;
+let unlistenLink: (() => void) | null = null;
+let unlistenAlert: (() => void) | null = null;
+let unlistenRemediation: (() => void) | null = null;
+let unlistenRecording: (() => void) | null = null;
+let unlistenReplay: (() => void) | null = null;
+
+// Sequence number of the snapshot held in `systemSnapshot`; null until
+// the first full snapshot arrives.
+let seq: number | null = null;
+let resyncing = false;
+// Deltas that arrive while a full snapshot is in flight, replayed on top
+// of it so the round-trip doesn't itself cause a gap.
+let pending: SnapshotDelta[] = [];
+
+function appendHistory(snapshot: SystemSnapshot) {
+	// Append to histories (cap at 300). Return a fresh array each
+	// update — mutating and returning the same reference still
+	// notifies subscribers, but breaks any downstream consumer
+	// that uses `===` to detect change (memoization, computed
+	// stores, signal-style integrations).
+	const x = performance.now() / 1000;
+	let oldest = x;
+	cpuHistory.update((hist) => {
+		const next: [number, number][] = [...hist, [x, snapshot.cpu_usage]];
+		const capped = next.length > 300 ? next.slice(-300) : next;
+		oldest = capped[0][0];
+		return capped;
+	});
+
+	memoryHistory.update((hist) => {
+		const usedGb = snapshot.memory.used / 1024 / 1024 / 1024;
+		const next: [number, number][] = [...hist, [x, usedGb]];
+		return next.length > 300 ? next.slice(-300) : next;
+	});
+
+	const flagged = snapshot.anomalies ?? [];
+	const mark = (marks: number[], series: string) => {
+		const kept = marks.filter((m) => m >= oldest);
+		return flagged.includes(series) ? [...kept, x] : kept;
+	};
+	cpuAnomalies.update((marks) => mark(marks, 'cpu_usage'));
+	memoryAnomalies.update((marks) => mark(marks, 'memory_used'));
+}
+
+/** Start the charts over, e.g. when switching between live and replayed data. */
+export function clearHistory() {
+	cpuHistory.set([]);
+	memoryHistory.set([]);
+	cpuAnomalies.set([]);
+	memoryAnomalies.set([]);
+}
+
+async function resync() {
+	if (resyncing) return;
+	resyncing = true;
+	pending = [];
+	try {
+		const message = await invoke<SnapshotMessage>('subscribe_snapshots');
+		if (message.kind === 'full') {
+			let snapshot = message.snapshot;
+			let at = message.seq;
+			for (const delta of pending.sort((a, b) => a.seq - b.seq)) {
+				if (delta.seq <= at) continue;
+				// Another gap; the next delta triggers a fresh resync.
+				if (delta.seq !== at + 1) break;
+				snapshot = applyDelta(snapshot, delta);
+				at = delta.seq;
+			}
+			seq = at;
+			systemSnapshot.set(snapshot);
+			systemError.set(null);
+		}
+	} catch (e) {
+		logError('Failed to fetch snapshot', e);
+		systemError.set(`Failed to connect to system monitor: ${e}`);
+	} finally {
+		pending = [];
+		resyncing = false;
+	}
+}
+
+function handleMessage(message: SnapshotMessage) {
+	if (message.kind === 'full') {
+		// Broadcast when the backend publishes before any window subscribed.
+		if (seq === null || message.seq > seq) {
+			seq = message.seq;
+			systemSnapshot.set(message.snapshot);
+			systemError.set(null);
+			appendHistory(message.snapshot);
+		}
+		return;
+	}
+	if (resyncing) {
+		pending.push(message);
+		return;
+	}
+	const current = get(systemSnapshot);
+	if (seq === null || current === null) {
+		resync();
+		return;
+	}
+	if (message.seq <= seq) return;
+	if (message.seq !== seq + 1) {
+		resync();
+		return;
+	}
+	const next = applyDelta(current, message);
+	seq = message.seq;
+	systemSnapshot.set(next);
+	systemError.set(null);
+	appendHistory(next);
+}
 
 export async function initSystemListener() {
-	// Fetch initial data
+	// Listen before fetching so no delta between the two is lost; any
+	// that arrive early are buffered by resync().
 	try {
-		const snapshot = await invoke<SystemSnapshot>('get_system_snapshot');
-		systemSnapshot.set(snapshot);
-		systemError.set(null);
+		unlisten = await listen<SnapshotMessage>('system-delta', (event) => {
+			handleMessage(event.payload);
+		});
+	} catch (e) {
+		logError('Failed to listen for system updates', e);
+		systemError.set('Failed to connect to system event stream');
+	}
 
+	// Fetch initial data
+	await resync();
+	try {
 		const cpuHist = await invoke<[number, number][]>('get_cpu_history');
 		cpuHistory.set(cpuHist);
 
@@ -29,47 +171,78 @@ export async function initSystemListener() {
 		systemError.set(`Failed to connect to system monitor: ${e}`);
 	}
 
-	// Listen for push updates
+	// Listen for explicit system-error events from the backend (e.g.
+	// mutex-poisoning recovery). The next successful system-delta will
+	// auto-clear systemError, so the banner disappears once the
+	// monitor is healthy again.
 	try {
-		unlisten = await listen<SystemSnapshot>('system-update', (event) => {
-			const snapshot = event.payload;
-			systemSnapshot.set(snapshot);
-			systemError.set(null);
+		unlistenError = await listen<string>('system-error', (event) => {
+			systemError.set(event.payload);
+		});
+	} catch (e) {
+		logError('Failed to listen for system-error events', e);
+	}
 
-			// Append to histories (cap at 300). Return a fresh array each
-			// update — mutating and returning the same reference still
-			// notifies subscribers, but breaks any downstream consumer
-			// that uses `===` to detect change (memoization, computed
-			// stores, signal-style integrations).
-			cpuHistory.update((hist) => {
-				const next: [number, number][] = [
-					...hist,
-					[performance.now() / 1000, snapshot.cpu_usage]
-				];
-				return next.length > 300 ? next.slice(-300) : next;
-			});
+	try {
+		unlistenLink = await listen<LinkEvent>('network-link-changed', (event) => {
+			linkEvents.update((events) =>
+				[{ ...event.payload, at: Date.now() }, ...events].slice(0, MAX_LINK_EVENTS)
+			);
+		});
+	} catch (e) {
+		logError('Failed to listen for network-link-changed events', e);
+	}
 
-			memoryHistory.update((hist) => {
-				const usedGb = snapshot.memory.used / 1024 / 1024 / 1024;
-				const next: [number, number][] = [...hist, [performance.now() / 1000, usedGb]];
-				return next.length > 300 ? next.slice(-300) : next;
+	try {
+		unlistenAlert = await listen<AlertEvent>('alert', (event) => {
+			const alert = event.payload;
+			firingAlerts.update((firing) => {
+				const next = { ...firing };
+				if (alert.state === 'firing') {
+					next[alert.rule] = { ...alert, at: Date.now() };
+				} else {
+					delete next[alert.rule];
+				}
+				return next;
 			});
 		});
 	} catch (e) {
-		logError('Failed to listen for system updates', e);
-		systemError.set('Failed to connect to system event stream');
+		logError('Failed to listen for alert events', e);
 	}
 
-	// Listen for explicit system-error events from the backend (e.g.
-	// mutex-poisoning recovery). The next successful system-update will
-	// auto-clear systemError, so the banner disappears once the
-	// monitor is healthy again.
 	try {
-		unlistenError = await listen<string>('system-error', (event) => {
-			systemError.set(event.payload);
+		unlistenRemediation = await listen<RemediationEvent>('remediation', (event) => {
+			remediationEvents.update((events) =>
+				[event.payload, ...events].slice(0, MAX_REMEDIATION_EVENTS)
+			);
 		});
 	} catch (e) {
-		logError('Failed to listen for system-error events', e);
+		logError('Failed to listen for remediation events', e);
+	}
+
+	try {
+		recording.set(await invoke<RecordingStatus | null>('get_recording_status'));
+		unlistenRecording = await listen<RecordingStatus | null>('recording-changed', (event) => {
+			recording.set(event.payload?.active ? event.payload : null);
+		});
+	} catch (e) {
+		logError('Failed to listen for recording events', e);
+	}
+
+	try {
+		replay.set(await invoke<ReplayStatus | null>('get_replay_status'));
+		unlistenReplay = await listen<ReplayStatus | null>('replay-changed', (event) => {
+			const next = event.payload;
+			const previous = get(replay);
+			// Opening, closing or jumping back leaves the charts' points
+			// out of order; start them over.
+			if (!next || !previous || next.position < previous.position) {
+				clearHistory();
+			}
+			replay.set(next);
+		});
+	} catch (e) {
+		logError('Failed to listen for replay events', e);
 	}
 }
 
@@ -78,8 +251,29 @@ export function destroySystemListener() {
 		unlisten();
 		unlisten = null;
 	}
+	seq = null;
 	if (unlistenError) {
 		unlistenError();
 		unlistenError = null;
 	}
+	if (unlistenLink) {
+		unlistenLink();
+		unlistenLink = null;
+	}
+	if (unlistenAlert) {
+		unlistenAlert();
+		unlistenAlert = null;
+	}
+	if (unlistenRemediation) {
+		unlistenRemediation();
+		unlistenRemediation = null;
+	}
+	if (unlistenRecording) {
+		unlistenRecording();
+		unlistenRecording = null;
+	}
+	if (unlistenReplay) {
+		unlistenReplay();
+		unlistenReplay = null;
+	}
 }
@@ -0,0 +1,45 @@
+import { writable, get } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
+import type { Watch, WatchTarget } from '$lib/types';
+import { logError } from '$lib/log';
+
+// Pinned processes and groups with their session history. Recorded in
+// the backend on every process refresh; fetched on snapshot ticks by
+// the process table.
+export const watches = writable<Watch[]>([]);
+
+export async function refreshWatches() {
+	try {
+		watches.set(await invoke<Watch[]>('get_watches'));
+	} catch (e) {
+		logError('Failed to load watches', e);
+	}
+}
+
+function sameTarget(a: WatchTarget, b: WatchTarget): boolean {
+	return a.kind === b.kind && a.name === b.name && (a.kind === 'group' || (b.kind === 'process' && a.pid === b.pid));
+}
+
+/** Pin `target`, or unpin it if it's already watched. */
+export async function toggleWatch(target: WatchTarget) {
+	const existing = get(watches).find((w) => sameTarget(w.target, target));
+	try {
+		if (existing) {
+			await invoke('unwatch_process', { id: existing.id });
+		} else {
+			await invoke('watch_process', { target });
+		}
+	} catch (e) {
+		logError('Failed to update watch', e);
+	}
+	await refreshWatches();
+}
+
+/** The watch to show for a group row: the group itself, else a pinned
+ * process of that name. */
+export function watchForGroup(all: Watch[], name: string): Watch | undefined {
+	return (
+		all.find((w) => w.target.kind === 'group' && w.target.name === name) ??
+		all.find((w) => w.target.kind === 'process' && w.target.name === name)
+	);
+}
@@ -8,6 +8,62 @@ export interface SystemSnapshot {
 	self_usage: SelfUsage | null;
 	uptime_seconds: number;
 	load_average: [number, number, number];
+	overhead: MonitorOverhead;
+	/** System series whose latest point is anomalous, e.g. 'cpu_usage'. */
+	anomalies?: string[];
+}
+
+// Incremental snapshot protocol (`system-delta` events). A window takes
+// a full snapshot from `subscribe_snapshots`, then applies deltas whose
+// seq follows its own; on a gap it calls `subscribe_snapshots` again.
+export interface MetricsDelta {
+	cpu_usage: number;
+	self_usage: SelfUsage | null;
+	uptime_seconds: number;
+	load_average: [number, number, number];
+	overhead: MonitorOverhead;
+	anomalies?: string[];
+	// Present only when changed
+	per_cpu?: number[];
+	memory?: MemoryInfo;
+	disks?: DiskInfo[];
+	networks?: NetworkInfo[];
+}
+
+export interface ProcessDelta {
+	added: CombinedProcess[];
+	changed: CombinedProcess[];
+	/** Names of process groups that exited. */
+	removed: string[];
+}
+
+export interface SnapshotDelta {
+	seq: number;
+	metrics: MetricsDelta;
+	processes: ProcessDelta;
+}
+
+export type SnapshotMessage =
+	| { kind: 'full'; seq: number; snapshot: SystemSnapshot }
+	| ({ kind: 'delta' } & SnapshotDelta);
+
+export type OverheadMode = 'full' | 'minimal';
+
+export interface RefreshCost {
+	cycles: number;
+	last_cycle_ms: number;
+	avg_cycle_ms: number;
+	subsystem_ms: Partial<Record<Subsystem, number>>;
+	/** Share of wall-clock time spent refreshing, percent of one core. */
+	busy_percent: number;
+}
+
+export interface MonitorOverhead {
+	mode: OverheadMode;
+	interval_multiplier: number;
+	on_battery: boolean;
+	under_load: boolean;
+	cost: RefreshCost;
 }
 
 export interface SelfUsage {
@@ -31,14 +87,194 @@ export interface DiskInfo {
 	used: number;
 	available: number;
 	total: number;
+	growth_bytes_per_hour?: number | null;
+	full_in_seconds?: number | null;
 }
 
+export type LinkState = 'up' | 'down' | 'unknown';
+
 export interface NetworkInfo {
 	interface: string;
 	rx_bytes: number;
 	tx_bytes: number;
 	rx_rate: number;
 	tx_rate: number;
+	mac_address: string;
+	ipv4: string[];
+	ipv6: string[];
+	mtu: number;
+	link_state: LinkState;
+	speed_mbps: number | null;
+	rx_packets: number;
+	tx_packets: number;
+	rx_errors: number;
+	tx_errors: number;
+	rx_dropped: number;
+	tx_dropped: number;
+}
+
+export interface LinkEvent {
+	interface: string;
+	previous: LinkState;
+	current: LinkState;
+}
+
+/** What a watch follows; see `watch::WatchTarget`. */
+export type WatchTarget = { kind: 'group'; name: string } | { kind: 'process'; name: string; pid: number };
+
+/** Usage of a watched target at one refresh. Rates are bytes/sec. */
+export interface WatchSample {
+	/** Seconds since the Unix epoch. */
+	timestamp: number;
+	cpu_usage: number;
+	memory: number;
+	disk_read_rate: number;
+	disk_write_rate: number;
+	net_rx_rate: number;
+	net_tx_rate: number;
+	/** Zero while the target isn't running. */
+	pids: number;
+	cpu_anomaly?: boolean;
+	memory_anomaly?: boolean;
+}
+
+export interface Watch {
+	id: number;
+	target: WatchTarget;
+	pids: number[];
+	/** Times a process watch followed a restart to a new PID. */
+	restarts: number;
+	history: WatchSample[];
+}
+
+/** A process group whose memory has grown steadily; see `leaks.rs`. */
+export interface SuspectedLeak {
+	name: string;
+	memory: number;
+	growth_bytes_per_hour: number;
+	r_squared: number;
+	window_seconds: number;
+}
+
+export type AlertState = 'firing' | 'resolved';
+
+/** An alert rule starting or stopping to fire (`alert` event). */
+export interface AlertEvent {
+	rule: string;
+	state: AlertState;
+	/** Group names matching when the rule fired. */
+	matches: string[];
+	message: string;
+}
+
+export type RemediationAction = 'renice' | 'terminate' | 'kill';
+
+export type RemediationOutcome =
+	| { status: 'applied' }
+	| { status: 'dry_run' }
+	| { status: 'protected'; reason: string }
+	| { status: 'failed'; error: string };
+
+/** A remediation policy acting on a process (`remediation` event). */
+export interface RemediationEvent {
+	timestamp: number;
+	policy: string;
+	pid: number;
+	name: string;
+	user: string | null;
+	action: RemediationAction;
+	/** Target nice value, for renice. */
+	nice: number | null;
+	outcome: RemediationOutcome;
+	message: string;
+}
+
+export type RecordingFormat = 'ndjson' | 'csv';
+
+/** An active or just-finished recording (`recording-changed` event). */
+export interface RecordingStatus {
+	active: boolean;
+	directory: string;
+	format: RecordingFormat;
+	/** File being written, if one is open. */
+	file: string | null;
+	/** Unix seconds. */
+	started_at: number;
+	snapshots_written: number;
+	files_written: number;
+	/** Why the recording stopped, if it failed. */
+	error: string | null;
+}
+
+/** A recording being replayed (`replay-changed` event); times are Unix seconds. */
+export interface ReplayStatus {
+	files: string[];
+	start: number;
+	end: number;
+	/** Recorded time of the snapshot on screen. */
+	position: number;
+	frame: number;
+	frames: number;
+	playing: boolean;
+	speed: number;
+}
+
+/** A saved baseline (`list_baselines`); `timestamp` is Unix seconds. */
+export interface BaselineInfo {
+	name: string;
+	timestamp: number;
+	hostname: string;
+}
+
+export interface Change {
+	before: number;
+	after: number;
+	delta: number;
+}
+
+export type Presence = 'both' | 'new' | 'gone';
+
+export interface SystemChange {
+	metric: string;
+	/** '%', 'bytes', 'groups' or '' (load). */
+	unit: string;
+	change: Change;
+}
+
+export interface ProcessChange {
+	name: string;
+	presence: Presence;
+	cpu: Change;
+	memory: Change;
+	instances: Change;
+}
+
+export interface DiskChange {
+	mount_point: string;
+	presence: Presence;
+	used: Change;
+	total: Change;
+}
+
+export interface NetworkChange {
+	interface: string;
+	presence: Presence;
+	rx_rate: Change;
+	tx_rate: Change;
+}
+
+/** `compare_baseline`: the snapshot on screen against a baseline. */
+export interface SnapshotDiff {
+	baseline: string;
+	baseline_timestamp: number;
+	timestamp: number;
+	system: SystemChange[];
+	new_processes: ProcessChange[];
+	gone_processes: ProcessChange[];
+	cpu_changes: ProcessChange[];
+	memory_changes: ProcessChange[];
+	disks: DiskChange[];
+	networks: NetworkChange[];
 }
 
 export interface CombinedProcess {
@@ -46,6 +282,9 @@ export interface CombinedProcess {
 	cpu_usage: number;
 	memory_usage: number;
 	pids: number[];
+	net_rx_rate: number;
+	net_tx_rate: number;
+	connections: number;
 }
 
 export interface ProcessDetails {
@@ -54,6 +293,73 @@ export interface ProcessDetails {
 	parent: number | null;
 }
 
+export type SocketProtocol = 'tcp' | 'tcp6' | 'udp' | 'udp6' | 'unix';
+
+export type SocketState =
+	| 'ESTABLISHED'
+	| 'SYN_SENT'
+	| 'SYN_RECV'
+	| 'FIN_WAIT1'
+	| 'FIN_WAIT2'
+	| 'TIME_WAIT'
+	| 'CLOSE'
+	| 'CLOSE_WAIT'
+	| 'LAST_ACK'
+	| 'LISTEN'
+	| 'CLOSING'
+	| 'CONNECTED'
+	| 'UNCONNECTED'
+	| 'UNKNOWN';
+
+export interface SocketEntry {
+	protocol: SocketProtocol;
+	local_address: string;
+	local_port: number | null;
+	remote_address: string | null;
+	remote_port: number | null;
+	state: SocketState;
+	inode: number;
+	uid: number | null;
+	tx_queue: number;
+	rx_queue: number;
+	pid: number | null;
+	process_name: string | null;
+}
+
+export interface SocketFilter {
+	protocol?: SocketProtocol | null;
+	state?: SocketState | null;
+	port?: number | null;
+	pid?: number | null;
+	process?: string | null;
+	listening_only?: boolean;
+}
+
+export type Subsystem = 'cpu' | 'memory' | 'processes' | 'disks' | 'networks';
+
+export interface SubsystemCadence {
+	/** Milliseconds between refreshes; null follows the global interval. */
+	interval_ms: number | null;
+	only_when_visible: boolean;
+}
+
+export type RefreshSchedule = Record<Subsystem, SubsystemCadence>;
+
+/** What a window renders; the backend trims its snapshots to match. */
+export interface SnapshotInterest {
+	subsystems: Subsystem[];
+	/** Keep only the N busiest process groups by CPU; null keeps all. */
+	top_processes: number | null;
+}
+
+export interface AdaptiveConfig {
+	enabled: boolean;
+	minimal_when_hidden: boolean;
+	battery_multiplier: number;
+	load_threshold_percent: number;
+	load_multiplier: number;
+}
+
 export interface AppConfig {
 	refresh_interval_seconds: number;
 	theme: string;
@@ -61,9 +367,45 @@ export interface AppConfig {
 	window_height: number | null;
 	window_x: number | null;
 	window_y: number | null;
+	refresh: RefreshSchedule;
+	adaptive: AdaptiveConfig;
 }
 
-export type SortColumn = 'name' | 'cpu' | 'memory' | 'pids';
+export type SortColumn = 'name' | 'cpu' | 'memory' | 'network' | 'pids';
 export type SortDirection = 'asc' | 'desc';
-export type ActiveView = 'overview' | 'cpu' | 'memory' | 'disks' | 'network' | 'processes';
+
+/** Evaluated by the backend's `query_processes`; omitted fields don't filter. */
+export interface ProcessQuery {
+	sort?: SortColumn;
+	direction?: SortDirection;
+	/** Regex on the group name; prefix `(?i)` for case-insensitive. */
+	name?: string | null;
+	/** Regex on any PID's command line. */
+	cmdline?: string | null;
+	user?: string | null;
+	/** Filter expression, e.g. `user == "postgres" && cpu > 20`. */
+	filter?: string | null;
+	min_cpu?: number;
+	/** Bytes. */
+	min_memory?: number;
+	offset?: number;
+	limit?: number | null;
+}
+
+export interface ProcessPage {
+	/** Groups matching the filters, before offset/limit. */
+	total: number;
+	/** Groups in the unfiltered list. */
+	groups: number;
+	processes: CombinedProcess[];
+}
+export type ActiveView =
+	| 'overview'
+	| 'cpu'
+	| 'memory'
+	| 'disks'
+	| 'network'
+	| 'connections'
+	| 'processes'
+	| 'compare';
 export type DetachableView = 'cpu' | 'memory' | 'disks' | 'network' | 'processes';
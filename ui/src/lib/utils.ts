@@ -42,3 +42,12 @@ export function formatUptime(seconds: number): string {
 	if (hours > 0) return `${hours}h ${mins}m`;
 	return `${mins}m`;
 }
+
+/** Rough "~3 days"-style duration for forecasts. */
+export function formatHorizon(seconds: number): string {
+	const plural = (n: number, unit: string) => `~${n} ${unit}${n === 1 ? '' : 's'}`;
+	if (seconds >= 14 * 86400) return plural(Math.round(seconds / (7 * 86400)), 'week');
+	if (seconds >= 86400) return plural(Math.round(seconds / 86400), 'day');
+	if (seconds >= 3600) return plural(Math.round(seconds / 3600), 'hour');
+	return plural(Math.max(1, Math.round(seconds / 60)), 'minute');
+}